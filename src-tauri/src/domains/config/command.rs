@@ -1,12 +1,11 @@
 use crate::domains::common::model::CommandResponse;
+use crate::domains::config::error::ConfigError;
 use crate::domains::config::model::{AppConfig, AppConfigSaveDto, DatabaseHealth};
 use crate::domains::config::service::{
     init_database as init_database_service, load_config as load_config_service,
     load_database as load_database_service, save_config as save_config_service,
 };
-use crate::domains::document::embedding::initialize_embedding_model;
 use tauri::AppHandle;
-use tracing::warn;
 
 #[tauri::command]
 pub fn load_config(app_handle: AppHandle) -> CommandResponse<AppConfig> {
@@ -26,16 +25,34 @@ pub fn load_config(app_handle: AppHandle) -> CommandResponse<AppConfig> {
     }
 }
 
+/// Runs off the async runtime: a changed embedding provider or vector storage
+/// initializes the database again (see `init_database`).
 #[tauri::command]
-pub fn save_config(app_handle: AppHandle, config: AppConfigSaveDto) -> CommandResponse<AppConfig> {
-    match save_config_service(&app_handle, &config) {
-        Ok(config) => CommandResponse {
+pub async fn save_config(
+    app_handle: AppHandle,
+    config: AppConfigSaveDto,
+) -> CommandResponse<AppConfig> {
+    let result =
+        tauri::async_runtime::spawn_blocking(move || save_config_service(&app_handle, &config))
+            .await;
+
+    match result {
+        Ok(Ok(config)) => CommandResponse {
             success: true,
             code: 200,
             message: "Config saved successfully".to_string(),
             data: Some(config),
         },
-        Err(_) => CommandResponse {
+        Ok(Err(ConfigError::EmbeddingProviderUnavailable(reason))) => CommandResponse {
+            success: false,
+            code: 500,
+            message: format!(
+                "Config saved, but the embedding provider is unavailable: {}",
+                reason
+            ),
+            data: None,
+        },
+        _ => CommandResponse {
             success: false,
             code: 500,
             message: "Cannot save config".to_string(),
@@ -45,20 +62,27 @@ pub fn save_config(app_handle: AppHandle, config: AppConfigSaveDto) -> CommandRe
 }
 
 #[tauri::command]
-pub fn init_database(app_handle: AppHandle) -> CommandResponse<()> {
-    match init_database_service(&app_handle) {
-        Ok(_) => {
-            if let Err(e) = initialize_embedding_model(&app_handle) {
-                warn!("Embedding model initialization failed (fallback will be used): {}", e);
-            }
-            CommandResponse {
-                success: true,
-                code: 200,
-                message: "Database initialized successfully".to_string(),
-                data: None,
-            }
-        }
-        Err(_) => CommandResponse {
+pub async fn init_database(app_handle: AppHandle) -> CommandResponse<()> {
+    let result =
+        tauri::async_runtime::spawn_blocking(move || init_database_service(&app_handle)).await;
+
+    match result {
+        Ok(Ok(())) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Database initialized successfully".to_string(),
+            data: None,
+        },
+        Ok(Err(ConfigError::EmbeddingProviderUnavailable(reason))) => CommandResponse {
+            success: false,
+            code: 500,
+            message: format!(
+                "Database initialized, but the embedding provider is unavailable: {}",
+                reason
+            ),
+            data: None,
+        },
+        _ => CommandResponse {
             success: false,
             code: 500,
             message: "Cannot initialize database".to_string(),
@@ -30,4 +30,10 @@ pub enum ConfigError {
 
     #[error("Failed to run migrations: {0}")]
     DatabaseMigrationsRunning(#[source] RusqliteError),
+
+    #[error("Failed to sync embedding metadata: {0}")]
+    EmbeddingMetadataSync(#[source] RusqliteError),
+
+    #[error("Embedding provider unavailable: {0}")]
+    EmbeddingProviderUnavailable(String),
 }
@@ -2,3 +2,4 @@ pub mod command;
 pub mod error;
 pub mod model;
 pub mod service;
+pub mod state;
@@ -2,19 +2,59 @@ use crate::domains::config::model::tab::Tab;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct VectorSettings {
     pub similarity_threshold: f32,  // 0.0 ~ 1.0, default 0.5
+    pub keyword_weight: f32,        // weight of title/tag/content ranks in hybrid search, default 1.0
+    pub semantic_weight: f32,       // weight of the vector similarity rank, default 1.0
+    pub rrf_k: f32,                 // reciprocal rank fusion damping constant, default 60
+    pub storage: VectorStorage,     // how vec_blocks stores vectors, default float
+    pub rescore: bool,              // re-score quantized matches with full-precision copies, default false
 }
 
 impl Default for VectorSettings {
     fn default() -> Self {
         VectorSettings {
             similarity_threshold: 0.5,
+            keyword_weight: 1.0,
+            semantic_weight: 1.0,
+            rrf_k: 60.0,
+            storage: VectorStorage::default(),
+            rescore: false,
         }
     }
 }
 
+/// Vector type of `vec_blocks`. `Int8` takes a quarter of the space of `Float`
+/// and `Binary` a thirty-second, at some cost in recall.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum VectorStorage {
+    #[default]
+    Float,
+    Int8,
+    Binary,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum EmbeddingProviderKind {
+    #[default]
+    Onnx,             // bundled BGE-M3 model
+    OpenAiCompatible, // any server exposing an OpenAI-style /v1/embeddings endpoint
+    Fallback,         // deterministic token hashing, no model
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EmbeddingSettings {
+    pub provider: EmbeddingProviderKind,
+    pub model: Option<String>,     // model name sent to the HTTP endpoint
+    pub base_url: Option<String>,  // e.g. http://localhost:11434/v1
+    pub api_key: Option<String>,
+    pub dimension: Option<usize>,  // probed from the endpoint when unset
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct GraphColors {
@@ -86,6 +126,7 @@ pub struct AppConfigSaveDto {
     pub tabs: Option<Option<Vec<Tab>>>,
     pub vector_settings: Option<Option<VectorSettings>>,
     pub graph_settings: Option<Option<GraphSettings>>,
+    pub embedding_settings: Option<Option<EmbeddingSettings>>,
     pub gemini_api_key: Option<Option<String>>,
     pub gemini_model: Option<Option<GeminiModel>>,
 }
@@ -98,6 +139,7 @@ pub struct AppConfig {
     pub tabs: Vec<Tab>,
     pub vector_settings: VectorSettings,
     pub graph_settings: GraphSettings,
+    pub embedding_settings: EmbeddingSettings,
     pub gemini_api_key: Option<String>,
     pub gemini_model: GeminiModel,
 }
@@ -110,6 +152,7 @@ impl Default for AppConfig {
             tabs: Vec::new(),
             vector_settings: VectorSettings::default(),
             graph_settings: GraphSettings::default(),
+            embedding_settings: EmbeddingSettings::default(),
             gemini_api_key: None,
             gemini_model: GeminiModel::default(),
         }
@@ -144,6 +187,11 @@ impl AppConfig {
                 Some(None) => GraphSettings::default(),
                 Some(Some(settings)) => settings.clone(),
             },
+            embedding_settings: match &dto.embedding_settings {
+                None => self.embedding_settings.clone(),
+                Some(None) => EmbeddingSettings::default(),
+                Some(Some(settings)) => settings.clone(),
+            },
             gemini_api_key: match &dto.gemini_api_key {
                 None => self.gemini_api_key.clone(),
                 Some(None) => None,
@@ -2,5 +2,7 @@ pub mod app_config;
 pub mod database_health;
 pub mod tab;
 
-pub use app_config::{AppConfig, AppConfigSaveDto, VectorSettings, GraphSettings};
+pub use app_config::{
+    AppConfig, AppConfigSaveDto, EmbeddingProviderKind, EmbeddingSettings, VectorStorage,
+};
 pub use database_health::DatabaseHealth;
@@ -1,18 +1,42 @@
 use crate::domains::config::error::ConfigError;
 use crate::domains::config::model::{AppConfig, AppConfigSaveDto, DatabaseHealth};
+use crate::domains::config::state::ConfigState;
 use crate::infrastructure::database::connection::create_connection;
 use crate::infrastructure::database::extension::load_sqlite_vec_extension;
 use crate::infrastructure::database::migrations::run_migrations;
-use crate::infrastructure::database::schema::init_schema;
+use crate::infrastructure::database::pool::DatabaseState;
+use crate::domains::document::repository::delete_superseded_vectors;
+use crate::domains::document::scheduler::INDEXING_SCHEDULER;
+use crate::domains::document::embedding::{
+    configure_embedding_provider, configure_previous_provider, current_dimension, current_model_id,
+    DEFAULT_EMBEDDING_DIMENSION,
+};
+use crate::infrastructure::database::schema::{
+    apply_vector_layout, init_schema, previous_vector_model, recreate_vector_table,
+    sync_embedding_metadata, vector_dimension, VectorLayout,
+};
 use crate::utils::app_data::get_app_data_path;
 use crate::utils::error_logger::{log_error_with_trace, ResultExt};
 use crate::utils::file_system::{read_file, save_file, SaveMode};
 use anyhow::Context;
 use rusqlite::OptionalExtension;
 use std::path::PathBuf;
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
 
 pub fn load_config(app_handle: &AppHandle) -> Result<AppConfig, ConfigError> {
+    let state = app_handle.state::<ConfigState>();
+    if let Some(config) = state.get() {
+        return Ok(config);
+    }
+
+    let config = read_config(app_handle)?;
+    state.set(config.clone());
+
+    Ok(config)
+}
+
+fn read_config(app_handle: &AppHandle) -> Result<AppConfig, ConfigError> {
     let config_file_path = get_app_data_path(app_handle)
         .map_err_log("load_config::get_app_data_path", ConfigError::AppDataPath)?
         .join("config.json");
@@ -65,9 +89,32 @@ pub fn save_config(
         .context("Failed to save config file")
         .map_err_log("save_config::save_file", ConfigError::ConfigSaving)?;
 
+    app_handle.state::<ConfigState>().invalidate();
+    if current_config.storage_path != merged_config.storage_path {
+        app_handle.state::<DatabaseState>().invalidate();
+    }
+
+    // Switch providers now; a new dimension also needs the vector table rebuilt.
+    // A new vector storage is converted by the indexing scheduler
+    if current_config.embedding_settings != merged_config.embedding_settings
+        && merged_config.is_database_initialized
+    {
+        init_database(app_handle)?;
+    }
+    if current_config.vector_settings.storage != merged_config.vector_settings.storage
+        || current_config.vector_settings.rescore != merged_config.vector_settings.rescore
+    {
+        INDEXING_SCHEDULER.wake_up();
+    }
+
     Ok(merged_config)
 }
 
+/// Sets up the embedding provider, schema and vector table, leaving its storage to
+/// `apply_vector_settings`. Blocks on loading the model or probing the provider's
+/// endpoint, so callers keep it off the async runtime.
+/// Fails with `EmbeddingProviderUnavailable` when the fallback had to stand in for
+/// the configured provider; the database is usable either way.
 pub fn init_database(app_handle: &AppHandle) -> Result<(), ConfigError> {
     let config = load_config(app_handle)?;
     let storage_path = PathBuf::from(config.storage_path.unwrap_or_default());
@@ -82,17 +129,107 @@ pub fn init_database(app_handle: &AppHandle) -> Result<(), ConfigError> {
         ConfigError::DatabaseExtensionLoading,
     )?;
 
-    init_schema(&mut conn).map_err_log(
+    // Configure the provider first, since `vec_blocks` is created with its dimension.
+    // If it can't be set up, the fallback keeps the existing table's dimension and
+    // the database is still initialized before the failure is returned
+    let existing_dimension = vector_dimension(&conn).map_err_log(
+        "init_database::vector_dimension",
+        ConfigError::DatabaseSchemaInitialization,
+    )?;
+    let provider_result = configure_embedding_provider(
+        app_handle,
+        &config.embedding_settings,
+        existing_dimension.unwrap_or(DEFAULT_EMBEDDING_DIMENSION),
+    );
+    let dimension = current_dimension();
+
+    init_schema(&mut conn, dimension).map_err_log(
         "init_database::init_schema",
         ConfigError::DatabaseSchemaInitialization,
     )?;
 
-    run_migrations(&mut conn).map_err_log(
+    run_migrations(&mut conn, &current_model_id()).map_err_log(
         "init_database::run_migrations",
         ConfigError::DatabaseMigrationsRunning,
     )?;
 
-    Ok(())
+    // Vectors of another size can't stay in `vec_blocks`, so a new dimension
+    // starts the index over
+    if let Some(previous) = existing_dimension.filter(|previous| *previous != dimension) {
+        info!(
+            "Embedding dimension changed from {} to {}, re-indexing all blocks",
+            previous, dimension
+        );
+        recreate_vector_table(&mut conn, dimension).map_err_log(
+            "init_database::recreate_vector_table",
+            ConfigError::DatabaseSchemaInitialization,
+        )?;
+    }
+
+    // A new model of the same dimension keeps the old vectors, and their edges,
+    // until the scheduler re-embeds each block; until then searches embed the
+    // query with the previous model too. The fallback only stands in while the
+    // configured provider is unavailable, so the index stays recorded for the
+    // configured model and the scheduler waits for it
+    if provider_result.is_ok() {
+        let model_id = current_model_id();
+        let previous = sync_embedding_metadata(&conn, &model_id, dimension).map_err_log(
+            "init_database::sync_embedding_metadata",
+            ConfigError::EmbeddingMetadataSync,
+        )?;
+        if let Some((previous_model, previous_dimension)) = previous {
+            info!(
+                "Embedding model changed from {} ({} dimensions) to {} ({} dimensions), re-embedding in the background",
+                previous_model, previous_dimension, model_id, dimension
+            );
+        }
+        let previous_model = previous_vector_model(&conn, &model_id).map_err_log(
+            "init_database::previous_vector_model",
+            ConfigError::EmbeddingMetadataSync,
+        )?;
+        if previous_model.is_none() {
+            delete_superseded_vectors(&conn).map_err_log(
+                "init_database::delete_superseded_vectors",
+                ConfigError::EmbeddingMetadataSync,
+            )?;
+        }
+        configure_previous_provider(app_handle, previous_model.as_deref(), dimension);
+    }
+
+    provider_result.map_err_log(
+        "init_database::configure_embedding_provider",
+        ConfigError::EmbeddingProviderUnavailable,
+    )
+}
+
+/// Converts `vec_blocks` to the configured vector storage if it is stored
+/// differently, and returns whether it was. Converting every vector can take a
+/// while, so the indexing scheduler runs it in the background rather than the
+/// commands that change the settings.
+pub fn apply_vector_settings(app_handle: &AppHandle) -> Result<bool, ConfigError> {
+    let config = load_config(app_handle)?;
+    let storage_path = PathBuf::from(config.storage_path.unwrap_or_default());
+    let mut conn = app_handle
+        .state::<DatabaseState>()
+        .writer(&storage_path)
+        .map_err_log(
+            "apply_vector_settings::writer",
+            ConfigError::DatabaseConnectionCreation,
+        )?;
+
+    let settings = &config.vector_settings;
+    let layout = VectorLayout::new(settings.storage, settings.rescore, current_dimension());
+    let changed = apply_vector_layout(&mut conn, layout).map_err_log(
+        "apply_vector_settings::apply_vector_layout",
+        ConfigError::DatabaseSchemaInitialization,
+    )?;
+    if changed && layout.storage != settings.storage {
+        warn!(
+            "Binary vectors need a dimension divisible by 8, storing {} dimensions as int8",
+            layout.dimension
+        );
+    }
+    Ok(changed)
 }
 
 pub fn load_database(app_handle: &AppHandle) -> Result<DatabaseHealth, ConfigError> {
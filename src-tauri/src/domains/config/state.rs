@@ -0,0 +1,32 @@
+use crate::domains::config::model::AppConfig;
+use std::sync::RwLock;
+
+/// In-memory copy of `config.json`, so hot paths (indexing, search) do not
+/// re-read and re-merge the file on every call. Invalidated by `save_config`.
+#[derive(Default)]
+pub struct ConfigState {
+    cached: RwLock<Option<AppConfig>>,
+}
+
+impl ConfigState {
+    pub fn get(&self) -> Option<AppConfig> {
+        self.cached
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    pub fn set(&self, config: AppConfig) {
+        *self
+            .cached
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(config);
+    }
+
+    pub fn invalidate(&self) {
+        *self
+            .cached
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
+}
@@ -1,5 +1,11 @@
 use crate::domains::common::model::CommandResponse;
-use crate::domains::document::model::{Document, DocumentGraphInfo, GraphData, SearchResult};
+use crate::domains::document::error::DocumentError;
+use crate::domains::document::model::{
+    Backlink, Block, Document, DocumentGraphInfo, DocumentListRequest, DocumentPage, EdgeExplanation,
+    FailedBlock, GraphData, IndexingProgress, IndexingStats, ReindexScope, RelatedDocument, SearchResult,
+    VectorStorageBenchmark,
+};
+use crate::domains::document::scheduler::INDEXING_SCHEDULER;
 use crate::domains::document::service;
 use tauri::AppHandle;
 
@@ -74,15 +80,20 @@ pub fn save_document(
 #[tauri::command]
 pub fn retrieve_document(
     app_handle: AppHandle,
-    query: Option<String>,
-    page: i64,
-) -> CommandResponse<Vec<Document>> {
-    match service::retrieve_document(&app_handle, query, page) {
-        Ok(documents) => CommandResponse {
+    request: Option<DocumentListRequest>,
+) -> CommandResponse<DocumentPage> {
+    match service::retrieve_document(&app_handle, &request.unwrap_or_default()) {
+        Ok(page) => CommandResponse {
             success: true,
             code: 200,
             message: "Documents retrieved successfully".to_string(),
-            data: Some(documents),
+            data: Some(page),
+        },
+        Err(DocumentError::InvalidQueryError(reason)) => CommandResponse {
+            success: false,
+            code: 400,
+            message: format!("Invalid document list request: {}", reason),
+            data: None,
         },
         Err(_) => CommandResponse {
             success: false,
@@ -112,14 +123,23 @@ pub fn delete_document(app_handle: AppHandle, document_id: String) -> CommandRes
 }
 
 #[tauri::command]
-pub fn search_documents(app_handle: AppHandle, query: String) -> CommandResponse<Vec<SearchResult>> {
-    match service::search_documents(&app_handle, &query) {
+pub async fn search_documents(
+    app_handle: AppHandle,
+    query: String,
+) -> CommandResponse<Vec<SearchResult>> {
+    match service::search_documents(&app_handle, &query).await {
         Ok(results) => CommandResponse {
             success: true,
             code: 200,
             message: "Search completed successfully".to_string(),
             data: Some(results),
         },
+        Err(DocumentError::InvalidQueryError(reason)) => CommandResponse {
+            success: false,
+            code: 400,
+            message: format!("Invalid search query: {}", reason),
+            data: None,
+        },
         Err(_) => CommandResponse {
             success: false,
             code: 500,
@@ -129,10 +149,71 @@ pub fn search_documents(app_handle: AppHandle, query: String) -> CommandResponse
     }
 }
 
+/// Documents most similar to the given one as a whole, for "related notes".
+#[tauri::command]
+pub fn find_similar_documents(
+    app_handle: AppHandle,
+    document_id: String,
+    limit: Option<i64>,
+) -> CommandResponse<Vec<RelatedDocument>> {
+    match service::find_similar_documents(&app_handle, &document_id, limit) {
+        Ok(documents) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Similar documents retrieved successfully".to_string(),
+            data: Some(documents),
+        },
+        Err(_) => CommandResponse {
+            success: false,
+            code: 500,
+            message: "Failed to find similar documents".to_string(),
+            data: None,
+        },
+    }
+}
+
+/// Documents that reference the given one with `[[...]]` links, with the referencing blocks.
+#[tauri::command]
+pub fn get_backlinks(app_handle: AppHandle, document_id: String) -> CommandResponse<Vec<Backlink>> {
+    match service::get_backlinks(&app_handle, &document_id) {
+        Ok(backlinks) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Backlinks retrieved successfully".to_string(),
+            data: Some(backlinks),
+        },
+        Err(_) => CommandResponse {
+            success: false,
+            code: 500,
+            message: "Failed to get backlinks".to_string(),
+            data: None,
+        },
+    }
+}
+
 // ============================================
 // Block Commands
 // ============================================
 
+/// A single block, e.g. the source of a block to embed or quote.
+#[tauri::command]
+pub fn get_block(app_handle: AppHandle, block_id: String) -> CommandResponse<Block> {
+    match service::get_block(&app_handle, &block_id) {
+        Ok(block) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Block retrieved successfully".to_string(),
+            data: block,
+        },
+        Err(_) => CommandResponse {
+            success: false,
+            code: 500,
+            message: "Failed to get block".to_string(),
+            data: None,
+        },
+    }
+}
+
 #[tauri::command]
 pub fn delete_block(app_handle: AppHandle, block_id: String) -> CommandResponse<()> {
     match service::delete_block(&app_handle, &block_id) {
@@ -155,46 +236,208 @@ pub fn delete_block(app_handle: AppHandle, block_id: String) -> CommandResponse<
 // Reindexing Commands
 // ============================================
 
+/// Reindexes every block. Search and the graph keep using the current vectors
+/// until the new ones are swapped in. Returns the number of blocks queued.
+#[tauri::command]
+pub fn trigger_reindex_all(app_handle: AppHandle) -> CommandResponse<i64> {
+    trigger_reindex(app_handle, ReindexScope::All)
+}
+
+/// Reindexes the blocks of one document, or of the documents with a tag.
 #[tauri::command]
-pub fn trigger_reindex_all(app_handle: AppHandle) -> CommandResponse<()> {
-    // 1. Delete all vectors
-    if let Err(_) = service::clear_all_vectors(&app_handle) {
-        return CommandResponse {
+pub fn trigger_reindex(app_handle: AppHandle, scope: ReindexScope) -> CommandResponse<i64> {
+    match service::start_reindex(&app_handle, &scope) {
+        Ok(total) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Reindex triggered successfully".to_string(),
+            data: Some(total),
+        },
+        Err(_) => CommandResponse {
             success: false,
             code: 500,
-            message: "Failed to clear vectors".to_string(),
+            message: "Failed to start reindex".to_string(),
             data: None,
-        };
+        },
     }
+}
 
-    // 2. Delete all edges
-    if let Err(_) = service::clear_all_edges(&app_handle) {
-        return CommandResponse {
+#[tauri::command]
+pub fn cancel_reindex(app_handle: AppHandle) -> CommandResponse<()> {
+    match service::cancel_reindex(&app_handle) {
+        Ok(()) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Reindex cancelled".to_string(),
+            data: None,
+        },
+        Err(_) => CommandResponse {
+            success: false,
+            code: 500,
+            message: "Failed to cancel reindex".to_string(),
+            data: None,
+        },
+    }
+}
+
+#[tauri::command]
+pub fn get_indexing_stats(app_handle: AppHandle) -> CommandResponse<IndexingStats> {
+    match service::get_indexing_stats(&app_handle) {
+        Ok(stats) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Indexing stats retrieved successfully".to_string(),
+            data: Some(stats),
+        },
+        Err(_) => CommandResponse {
+            success: false,
+            code: 500,
+            message: "Failed to get indexing stats".to_string(),
+            data: None,
+        },
+    }
+}
+
+#[tauri::command]
+pub fn list_failed_blocks(app_handle: AppHandle) -> CommandResponse<Vec<FailedBlock>> {
+    match service::list_failed_blocks(&app_handle) {
+        Ok(blocks) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Failed blocks retrieved successfully".to_string(),
+            data: Some(blocks),
+        },
+        Err(_) => CommandResponse {
+            success: false,
+            code: 500,
+            message: "Failed to list failed blocks".to_string(),
+            data: None,
+        },
+    }
+}
+
+/// Queues failed blocks for indexing again: the given ones, or all of them.
+#[tauri::command]
+pub fn retry_failed_blocks(
+    app_handle: AppHandle,
+    block_ids: Option<Vec<String>>,
+) -> CommandResponse<u64> {
+    match service::retry_failed_blocks(&app_handle, block_ids.as_deref()) {
+        Ok(count) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Failed blocks queued for retry".to_string(),
+            data: Some(count),
+        },
+        Err(_) => CommandResponse {
             success: false,
             code: 500,
-            message: "Failed to clear edges".to_string(),
+            message: "Failed to retry failed blocks".to_string(),
             data: None,
-        };
+        },
     }
+}
 
-    // 3. Reset all indexing status
-    if let Err(_) = service::reset_all_indexing_status(&app_handle) {
-        return CommandResponse {
+#[tauri::command]
+pub fn get_indexing_progress(app_handle: AppHandle) -> CommandResponse<IndexingProgress> {
+    match INDEXING_SCHEDULER.progress(&app_handle) {
+        Ok(progress) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Indexing progress retrieved successfully".to_string(),
+            data: Some(progress),
+        },
+        Err(_) => CommandResponse {
             success: false,
             code: 500,
-            message: "Failed to reset indexing status".to_string(),
+            message: "Failed to get indexing progress".to_string(),
             data: None,
-        };
+        },
     }
+}
 
+/// Pauses background indexing after the batch in flight is saved.
+#[tauri::command]
+pub fn pause_indexing() -> CommandResponse<()> {
+    INDEXING_SCHEDULER.pause();
     CommandResponse {
         success: true,
         code: 200,
-        message: "Reindex triggered successfully".to_string(),
+        message: "Indexing paused".to_string(),
         data: None,
     }
 }
 
+#[tauri::command]
+pub fn resume_indexing() -> CommandResponse<()> {
+    INDEXING_SCHEDULER.resume();
+    CommandResponse {
+        success: true,
+        code: 200,
+        message: "Indexing resumed".to_string(),
+        data: None,
+    }
+}
+
+/// Pauses background indexing and discards the batch in flight; its blocks stay pending.
+#[tauri::command]
+pub fn cancel_indexing() -> CommandResponse<()> {
+    INDEXING_SCHEDULER.cancel();
+    CommandResponse {
+        success: true,
+        code: 200,
+        message: "Indexing cancelled".to_string(),
+        data: None,
+    }
+}
+
+/// Sets how often an idle scheduler checks for pending blocks. Returns the
+/// interval actually applied, after clamping.
+#[tauri::command]
+pub fn set_indexing_interval(interval_secs: u64) -> CommandResponse<u64> {
+    let interval_secs = INDEXING_SCHEDULER.set_idle_interval(interval_secs);
+    CommandResponse {
+        success: true,
+        code: 200,
+        message: "Indexing interval updated".to_string(),
+        data: Some(interval_secs),
+    }
+}
+
+/// Compares the recall and size of the vector storage modes on this vault.
+/// Runs brute-force searches, so it is kept off the async runtime.
+#[tauri::command]
+pub async fn benchmark_vector_storage(
+    app_handle: AppHandle,
+    sample_size: Option<i64>,
+) -> CommandResponse<Vec<VectorStorageBenchmark>> {
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        service::benchmark_vector_storage(&app_handle, sample_size)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(results)) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Vector storage benchmark completed".to_string(),
+            data: Some(results),
+        },
+        Ok(Err(DocumentError::BenchmarkUnavailableError(reason))) => CommandResponse {
+            success: false,
+            code: 400,
+            message: format!("Vector benchmark unavailable: {}", reason),
+            data: None,
+        },
+        _ => CommandResponse {
+            success: false,
+            code: 500,
+            message: "Vector storage benchmark failed".to_string(),
+            data: None,
+        },
+    }
+}
+
 // ============================================
 // Graph Commands
 // ============================================
@@ -217,3 +460,155 @@ pub fn get_graph_data(app_handle: AppHandle) -> CommandResponse<GraphData> {
     }
 }
 
+/// Subgraph around a document, `hops` deep (default: the configured multi-hop level).
+#[tauri::command]
+pub fn get_document_neighborhood(
+    app_handle: AppHandle,
+    document_id: String,
+    hops: Option<u32>,
+    min_weight: Option<f64>,
+) -> CommandResponse<GraphData> {
+    match service::get_document_neighborhood(&app_handle, &document_id, hops, min_weight) {
+        Ok(graph_data) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Document neighborhood retrieved successfully".to_string(),
+            data: Some(graph_data),
+        },
+        Err(_) => CommandResponse {
+            success: false,
+            code: 500,
+            message: "Failed to get document neighborhood".to_string(),
+            data: None,
+        },
+    }
+}
+
+/// Pins (or unpins) the similar edge between two documents against the scheduler.
+#[tauri::command]
+pub fn pin_edge(
+    app_handle: AppHandle,
+    source_id: String,
+    target_id: String,
+    pinned: bool,
+) -> CommandResponse<()> {
+    match service::pin_edge(&app_handle, &source_id, &target_id, pinned) {
+        Ok(_) => CommandResponse {
+            success: true,
+            code: 200,
+            message: if pinned { "Edge pinned" } else { "Edge unpinned" }.to_string(),
+            data: None,
+        },
+        Err(DocumentError::EdgeNotFoundError(edge)) => CommandResponse {
+            success: false,
+            code: 400,
+            message: format!("Edge not found: {}", edge),
+            data: None,
+        },
+        Err(_) => CommandResponse {
+            success: false,
+            code: 500,
+            message: "Failed to pin edge".to_string(),
+            data: None,
+        },
+    }
+}
+
+/// Removes the similar edge between two documents and keeps the scheduler from restoring it.
+#[tauri::command]
+pub fn block_edge(app_handle: AppHandle, source_id: String, target_id: String) -> CommandResponse<()> {
+    match service::block_edge(&app_handle, &source_id, &target_id) {
+        Ok(_) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Document pair blocked".to_string(),
+            data: None,
+        },
+        Err(_) => CommandResponse {
+            success: false,
+            code: 500,
+            message: "Failed to block document pair".to_string(),
+            data: None,
+        },
+    }
+}
+
+#[tauri::command]
+pub fn unblock_edge(app_handle: AppHandle, source_id: String, target_id: String) -> CommandResponse<()> {
+    match service::unblock_edge(&app_handle, &source_id, &target_id) {
+        Ok(_) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Document pair unblocked".to_string(),
+            data: None,
+        },
+        Err(_) => CommandResponse {
+            success: false,
+            code: 500,
+            message: "Failed to unblock document pair".to_string(),
+            data: None,
+        },
+    }
+}
+
+#[tauri::command]
+pub fn annotate_edge(
+    app_handle: AppHandle,
+    source_id: String,
+    target_id: String,
+    relation_type: String,
+    label: Option<String>,
+    note: Option<String>,
+) -> CommandResponse<()> {
+    match service::annotate_edge(
+        &app_handle,
+        &source_id,
+        &target_id,
+        &relation_type,
+        label.as_deref(),
+        note.as_deref(),
+    ) {
+        Ok(_) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Edge annotated".to_string(),
+            data: None,
+        },
+        Err(DocumentError::EdgeNotFoundError(edge)) => CommandResponse {
+            success: false,
+            code: 400,
+            message: format!("Edge not found: {}", edge),
+            data: None,
+        },
+        Err(_) => CommandResponse {
+            success: false,
+            code: 500,
+            message: "Failed to annotate edge".to_string(),
+            data: None,
+        },
+    }
+}
+
+/// The block pairs that make two documents similar, best first, with snippets and scores.
+#[tauri::command]
+pub fn explain_edge(
+    app_handle: AppHandle,
+    source_id: String,
+    target_id: String,
+    limit: Option<i64>,
+) -> CommandResponse<EdgeExplanation> {
+    match service::explain_edge(&app_handle, &source_id, &target_id, limit) {
+        Ok(explanation) => CommandResponse {
+            success: true,
+            code: 200,
+            message: "Edge explained".to_string(),
+            data: Some(explanation),
+        },
+        Err(_) => CommandResponse {
+            success: false,
+            code: 500,
+            message: "Failed to explain edge".to_string(),
+            data: None,
+        },
+    }
+}
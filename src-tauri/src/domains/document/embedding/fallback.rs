@@ -0,0 +1,104 @@
+use crate::domains::document::embedding::provider::{l2_normalize, EmbeddingProvider};
+use crate::domains::document::search::tokenizer::{contains_cjk, tokenize};
+use crate::utils::hash::fnv1a_64;
+use std::collections::HashMap;
+
+/// Versioned with `hash_embedding`, so vectors hashed by an earlier version are re-embedded.
+pub const FALLBACK_MODEL_ID: &str = "hash-fallback-v2";
+
+/// Deterministic bag-of-tokens vectors, hashed into `dimension` buckets. Needs no
+/// model, so it is used when none is configured or the configured one can't be loaded.
+pub struct FallbackProvider {
+    dimension: usize,
+}
+
+impl FallbackProvider {
+    pub fn new(dimension: usize) -> Self {
+        Self {
+            dimension: dimension.max(1),
+        }
+    }
+}
+
+impl EmbeddingProvider for FallbackProvider {
+    fn model_id(&self) -> String {
+        FALLBACK_MODEL_ID.to_string()
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+        Ok(texts
+            .iter()
+            .map(|text| hash_embedding(text, self.dimension))
+            .collect())
+    }
+}
+
+fn hash_embedding(text: &str, dimension: usize) -> Vec<f32> {
+    let mut embedding = vec![0.0f32; dimension];
+
+    if text.is_empty() {
+        return embedding;
+    }
+
+    // Stems and bigrams let Korean words match regardless of attached particles
+    let tokens: Vec<String> = tokenize(text)
+        .into_iter()
+        .filter(|t| contains_cjk(t) || t.chars().count() > 1)
+        .collect();
+
+    if tokens.is_empty() {
+        return embedding;
+    }
+
+    let mut token_freq: HashMap<&str, u32> = HashMap::new();
+    for token in tokens.iter() {
+        *token_freq.entry(token.as_str()).or_insert(0) += 1;
+    }
+
+    for (token, freq) in token_freq {
+        let idx = (fnv1a_64(token.as_bytes()) as usize) % dimension;
+        let tf_weight = (1.0 + freq as f32).ln();
+        embedding[idx] += tf_weight;
+    }
+
+    l2_normalize(embedding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn norm(embedding: &[f32]) -> f32 {
+        embedding.iter().map(|x| x * x).sum::<f32>().sqrt()
+    }
+
+    #[test]
+    fn embeddings_are_deterministic_unit_vectors() {
+        let first = hash_embedding("Graph notes and linked ideas", 64);
+        let second = hash_embedding("Graph notes and linked ideas", 64);
+
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 64);
+        assert!((norm(&first) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn text_without_tokens_embeds_to_zero() {
+        assert!(hash_embedding("", 16).iter().all(|x| *x == 0.0));
+        // Single-character Latin words are dropped
+        assert!(hash_embedding("a b c", 16).iter().all(|x| *x == 0.0));
+    }
+
+    #[test]
+    fn korean_particles_do_not_change_the_stem_bucket() {
+        let with_particle = hash_embedding("노트를", 256);
+        let bare = hash_embedding("노트", 256);
+
+        let shared: f32 = with_particle.iter().zip(&bare).map(|(a, b)| a * b).sum();
+        assert!(shared > 0.5);
+    }
+}
@@ -0,0 +1,282 @@
+pub mod fallback;
+pub mod onnx;
+pub mod openai;
+pub mod provider;
+
+use crate::domains::config::model::{EmbeddingProviderKind, EmbeddingSettings};
+use crate::domains::document::embedding::fallback::{FallbackProvider, FALLBACK_MODEL_ID};
+use crate::domains::document::embedding::onnx::{OnnxProvider, ONNX_DIMENSION, ONNX_MODEL_ID};
+use crate::domains::document::embedding::openai::OpenAiCompatibleProvider;
+use crate::domains::document::embedding::provider::EmbeddingProvider;
+use once_cell::sync::Lazy;
+use std::sync::{Arc, RwLock};
+use tauri::AppHandle;
+use tracing::{error, info};
+
+/// Vector size used before any provider is configured.
+pub const DEFAULT_EMBEDDING_DIMENSION: usize = ONNX_DIMENSION;
+/// Texts per provider call; bounds the size of the padded input tensors and HTTP requests.
+const EMBEDDING_BATCH_SIZE: usize = 16;
+
+struct ActiveProvider {
+    provider: Arc<dyn EmbeddingProvider>,
+    /// The configured provider couldn't be set up and the fallback stands in for it.
+    degraded: bool,
+    /// Incremented whenever the configured provider is set up successfully.
+    generation: u64,
+    /// Provider of the model blocks were embedded with before the last model change,
+    /// kept to embed search queries until every block is re-embedded.
+    previous: Option<Arc<dyn EmbeddingProvider>>,
+}
+
+static EMBEDDING_PROVIDER: Lazy<RwLock<ActiveProvider>> = Lazy::new(|| {
+    RwLock::new(ActiveProvider {
+        provider: Arc::new(FallbackProvider::new(DEFAULT_EMBEDDING_DIMENSION)),
+        degraded: false,
+        generation: 0,
+        previous: None,
+    })
+});
+
+fn active_provider() -> Arc<dyn EmbeddingProvider> {
+    match EMBEDDING_PROVIDER.read() {
+        Ok(active) => active.provider.clone(),
+        Err(poisoned) => poisoned.into_inner().provider.clone(),
+    }
+}
+
+/// Makes the provider selected in `settings` the active one. If it can't be set
+/// up, the hash fallback with `fallback_dimension` is activated instead and the
+/// error is returned.
+pub fn configure_embedding_provider(
+    app_handle: &AppHandle,
+    settings: &EmbeddingSettings,
+    fallback_dimension: usize,
+) -> Result<(), String> {
+    let result: Result<Arc<dyn EmbeddingProvider>, String> = match settings.provider {
+        EmbeddingProviderKind::Onnx => {
+            OnnxProvider::load(app_handle).map(|p| Arc::new(p) as Arc<dyn EmbeddingProvider>)
+        }
+        EmbeddingProviderKind::OpenAiCompatible => OpenAiCompatibleProvider::connect(settings)
+            .map(|p| Arc::new(p) as Arc<dyn EmbeddingProvider>),
+        EmbeddingProviderKind::Fallback => Ok(Arc::new(FallbackProvider::new(
+            settings.dimension.unwrap_or(fallback_dimension),
+        ))),
+    };
+
+    let (provider, outcome): (Arc<dyn EmbeddingProvider>, Result<(), String>) = match result {
+        Ok(provider) => (provider, Ok(())),
+        Err(e) => (Arc::new(FallbackProvider::new(fallback_dimension)), Err(e)),
+    };
+
+    info!(
+        "Embedding provider: {} ({} dimensions)",
+        provider.model_id(),
+        provider.dimension()
+    );
+    let mut active = EMBEDDING_PROVIDER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let replaced_model = active.provider.model_id() != provider.model_id()
+        && active.provider.dimension() == provider.dimension();
+    if outcome.is_ok() && replaced_model && !active.degraded {
+        active.previous = Some(active.provider.clone());
+    }
+    active.provider = provider;
+    active.degraded = outcome.is_err();
+    if outcome.is_ok() {
+        active.generation += 1;
+    }
+
+    outcome
+}
+
+/// Keeps a provider for `model_id`, the model blocks are still stored with from
+/// before a model change, or drops the previous provider once there is none. The
+/// provider replaced by the last `configure_embedding_provider` is reused; after a
+/// restart only the bundled model and the fallback can be set up again, without
+/// their settings, so vectors of an endpoint's model are not searched until re-embedded.
+pub fn configure_previous_provider(app_handle: &AppHandle, model_id: Option<&str>, dimension: usize) {
+    let mut active = EMBEDDING_PROVIDER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let Some(model_id) = model_id else {
+        active.previous = None;
+        return;
+    };
+    if active
+        .previous
+        .as_ref()
+        .is_some_and(|previous| previous.model_id() == model_id)
+    {
+        return;
+    }
+
+    active.previous = match model_id {
+        ONNX_MODEL_ID => match OnnxProvider::load(app_handle) {
+            Ok(provider) => Some(Arc::new(provider)),
+            Err(e) => {
+                error!("Failed to load the previous embedding model: {}", e);
+                None
+            }
+        },
+        FALLBACK_MODEL_ID => Some(Arc::new(FallbackProvider::new(dimension))),
+        _ => None,
+    };
+    match &active.previous {
+        Some(_) => info!("Searching vectors of {} until they are re-embedded", model_id),
+        None => info!("Vectors of {} are not searched until they are re-embedded", model_id),
+    }
+}
+
+/// Model of the provider kept for vectors from before the last model change, if any.
+pub fn previous_model_id() -> Option<String> {
+    EMBEDDING_PROVIDER
+        .read()
+        .ok()
+        .and_then(|active| active.previous.as_ref().map(|previous| previous.model_id()))
+}
+
+/// Whether the fallback is standing in for a configured provider that couldn't be
+/// set up, so configuring it again may succeed once e.g. its server is up.
+pub fn is_provider_degraded() -> bool {
+    EMBEDDING_PROVIDER
+        .read()
+        .map(|active| active.degraded)
+        .unwrap_or(false)
+}
+
+/// Changes every time a provider is set up successfully; blocks that failed with
+/// an earlier provider are worth retrying once it changes.
+pub fn provider_generation() -> u64 {
+    EMBEDDING_PROVIDER
+        .read()
+        .map(|active| active.generation)
+        .unwrap_or(0)
+}
+
+/// Id of the model `calculate_chunk_embeddings` currently embeds with.
+pub fn current_model_id() -> String {
+    active_provider().model_id()
+}
+
+/// Vector size of the active provider.
+pub fn current_dimension() -> usize {
+    active_provider().dimension()
+}
+
+/// A query embedded with one model: the model id and the embedding, or the provider's error.
+pub type QueryEmbedding = (String, Result<Vec<f32>, String>);
+
+/// Embeds a search query with the active provider and, while blocks are still
+/// stored with the previous model, with that one too, since each vector is only
+/// comparable with vectors of its own model. Returns (model id, embedding) per model;
+/// an empty query gets a zero vector.
+pub fn calculate_query_embeddings(text: &str) -> Vec<QueryEmbedding> {
+    let providers: Vec<Arc<dyn EmbeddingProvider>> = {
+        let active = EMBEDDING_PROVIDER
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        std::iter::once(active.provider.clone())
+            .chain(active.previous.clone())
+            .collect()
+    };
+
+    providers
+        .into_iter()
+        .map(|provider| {
+            let embedding = embed_with(provider.as_ref(), &[text])
+                .pop()
+                .unwrap_or_else(|| Err("Provider returned no embedding".to_string()));
+            (provider.model_id(), embedding)
+        })
+        .collect()
+}
+
+/// Embeds each text separately, so a batch the provider fails on only fails its own texts.
+fn embed_with(provider: &dyn EmbeddingProvider, texts: &[&str]) -> Vec<Result<Vec<f32>, String>> {
+    let dimension = provider.dimension();
+    let mut embeddings: Vec<Result<Vec<f32>, String>> =
+        vec![Ok(vec![0.0f32; dimension]); texts.len()];
+    let pending: Vec<(usize, &str)> = texts
+        .iter()
+        .enumerate()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(index, text)| (index, *text))
+        .collect();
+
+    for batch in pending.chunks(EMBEDDING_BATCH_SIZE) {
+        let batch_texts: Vec<&str> = batch.iter().map(|(_, text)| *text).collect();
+
+        match provider.embed(&batch_texts) {
+            Ok(batch_embeddings) => {
+                for ((index, _), embedding) in batch.iter().zip(batch_embeddings) {
+                    embeddings[*index] = Ok(embedding);
+                }
+            }
+            Err(e) => {
+                error!("Failed to compute embeddings: {}", e);
+                for (index, _) in batch {
+                    embeddings[*index] = Err(e.clone());
+                }
+            }
+        }
+    }
+
+    embeddings
+}
+
+/// A window of a block's content and its embedding. Offsets are in characters.
+pub struct ChunkEmbedding {
+    pub start_offset: usize,
+    pub end_offset: usize,
+    pub embedding: Vec<f32>,
+}
+
+/// Embeds each text as the chunks the active provider splits it into, so the end
+/// of a long block is not cut off at the model's input limit. The chunks of all
+/// texts are embedded together in batches; a text fails if any of its chunks does.
+pub fn calculate_chunk_embeddings(texts: &[&str]) -> Vec<Result<Vec<ChunkEmbedding>, String>> {
+    let provider = active_provider();
+
+    // (text index, byte range) of every chunk
+    let mut pieces: Vec<(usize, usize, usize)> = Vec::new();
+    for (index, text) in texts.iter().enumerate() {
+        let ranges = provider.chunk_ranges(text).unwrap_or_else(|e| {
+            error!("Failed to chunk text: {}", e);
+            vec![(0, text.len())]
+        });
+        pieces.extend(
+            ranges
+                .into_iter()
+                .filter(|(start, end)| text.get(*start..*end).is_some())
+                .map(|(start, end)| (index, start, end)),
+        );
+    }
+
+    let piece_texts: Vec<&str> = pieces
+        .iter()
+        .map(|(index, start, end)| &texts[*index][*start..*end])
+        .collect();
+    let embeddings = embed_with(provider.as_ref(), &piece_texts);
+
+    let mut chunks: Vec<Result<Vec<ChunkEmbedding>, String>> =
+        texts.iter().map(|_| Ok(Vec::new())).collect();
+    for ((index, start, end), embedding) in pieces.into_iter().zip(embeddings) {
+        let text = texts[index];
+        match embedding {
+            Ok(embedding) => {
+                if let Ok(text_chunks) = &mut chunks[index] {
+                    text_chunks.push(ChunkEmbedding {
+                        start_offset: text[..start].chars().count(),
+                        end_offset: text[..end].chars().count(),
+                        embedding,
+                    });
+                }
+            }
+            Err(e) => chunks[index] = Err(e),
+        }
+    }
+
+    chunks
+}
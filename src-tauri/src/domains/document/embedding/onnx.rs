@@ -0,0 +1,308 @@
+use crate::domains::document::embedding::provider::{l2_normalize, EmbeddingProvider};
+use ort::session::{builder::GraphOptimizationLevel, Session};
+use std::sync::{Mutex, Once};
+use tauri::{path::BaseDirectory, AppHandle, Manager};
+use tokenizers::Tokenizer;
+use tracing::{info, warn};
+
+pub const ONNX_MODEL_ID: &str = "bge-m3";
+pub const ONNX_DIMENSION: usize = 1024;
+const MAX_SEQUENCE_LENGTH: usize = 512;
+/// Tokens per chunk, leaving room for the special tokens added around each chunk.
+const CHUNK_TOKENS: usize = MAX_SEQUENCE_LENGTH - 2;
+/// Tokens shared by consecutive chunks, so text cut at a chunk boundary is
+/// still embedded with its context in the neighbouring chunk.
+const CHUNK_OVERLAP_TOKENS: usize = 64;
+
+static ORT_INIT: Once = Once::new();
+
+fn get_onnx_filename() -> &'static str {
+    #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+    return "bge_m3_macos_aarch64.onnx";
+
+    #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
+    return "bge_m3_macos_x86_64.onnx";
+
+    #[cfg(all(target_os = "windows", target_arch = "x86_64"))]
+    return "bge_m3_windows_x86_64.onnx";
+
+    #[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+    return "bge_m3_linux_aarch64.onnx";
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    return "bge_m3_linux_x86_64.onnx";
+
+    #[cfg(not(any(
+        all(target_os = "macos", target_arch = "aarch64"),
+        all(target_os = "macos", target_arch = "x86_64"),
+        all(target_os = "windows", target_arch = "x86_64"),
+        all(target_os = "linux", target_arch = "aarch64"),
+        all(target_os = "linux", target_arch = "x86_64"),
+    )))]
+    compile_error!("Unsupported platform");
+}
+
+fn get_ort_dylib_filename() -> &'static str {
+    #[cfg(target_os = "macos")]
+    return "libonnxruntime.dylib";
+
+    #[cfg(target_os = "windows")]
+    return "onnxruntime.dll";
+
+    #[cfg(target_os = "linux")]
+    return "libonnxruntime.so";
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    compile_error!("Unsupported platform");
+}
+
+fn initialize_ort_runtime(app_handle: &AppHandle) -> Result<(), String> {
+    let dylib_path = app_handle
+        .path()
+        .resolve(
+            format!("resources/onnxruntime/{}", get_ort_dylib_filename()),
+            BaseDirectory::Resource,
+        )
+        .map_err(|e| format!("Failed to resolve ONNX Runtime library path: {}", e))?;
+
+    if !dylib_path.exists() {
+        return Err(format!("ONNX Runtime library not found: {:?}", dylib_path));
+    }
+
+    info!("Loading ONNX Runtime from: {:?}", dylib_path);
+
+    let dylib_path_str = dylib_path
+        .to_str()
+        .ok_or_else(|| "Invalid ONNX Runtime library path".to_string())?;
+
+    ort::init_from(dylib_path_str)
+        .commit()
+        .map_err(|e| format!("Failed to initialize ONNX Runtime: {}", e))?;
+
+    Ok(())
+}
+
+/// The bundled BGE-M3 model, run in-process with ONNX Runtime.
+pub struct OnnxProvider {
+    session: Mutex<Session>,
+    tokenizer: Tokenizer,
+    /// Same tokenizer without truncation, used to split long text into chunks.
+    chunk_tokenizer: Tokenizer,
+}
+
+impl OnnxProvider {
+    pub fn load(app_handle: &AppHandle) -> Result<Self, String> {
+        // Initialize ONNX Runtime with dynamic loading (only once)
+        let mut init_error: Option<String> = None;
+        ORT_INIT.call_once(|| {
+            if let Err(e) = initialize_ort_runtime(app_handle) {
+                init_error = Some(e);
+            }
+        });
+
+        if let Some(e) = init_error {
+            return Err(e);
+        }
+
+        let onnx_path = app_handle
+            .path()
+            .resolve(
+                format!("resources/embedding/{}", get_onnx_filename()),
+                BaseDirectory::Resource,
+            )
+            .map_err(|e| format!("Failed to resolve ONNX path: {}", e))?;
+
+        let tokenizer_path = app_handle
+            .path()
+            .resolve("resources/embedding/tokenizer.json", BaseDirectory::Resource)
+            .map_err(|e| format!("Failed to resolve tokenizer path: {}", e))?;
+
+        if !onnx_path.exists() {
+            let msg = format!("ONNX model not found: {:?}", onnx_path);
+            warn!("{}", msg);
+            return Err(msg);
+        }
+
+        if !tokenizer_path.exists() {
+            let msg = format!("Tokenizer not found: {:?}", tokenizer_path);
+            warn!("{}", msg);
+            return Err(msg);
+        }
+
+        info!("Loading ONNX model from: {:?}", onnx_path);
+        let session = Session::builder()
+            .map_err(|e| format!("Failed to create session builder: {}", e))?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .map_err(|e| format!("Failed to set optimization level: {}", e))?
+            .with_intra_threads(4)
+            .map_err(|e| format!("Failed to set threads: {}", e))?
+            .commit_from_file(&onnx_path)
+            .map_err(|e| format!("Failed to load ONNX model: {}", e))?;
+
+        info!("Loading tokenizer from: {:?}", tokenizer_path);
+        let tokenizer = Tokenizer::from_file(&tokenizer_path)
+            .map_err(|e| format!("Failed to load tokenizer: {}", e))?;
+
+        let mut chunk_tokenizer = tokenizer.clone();
+        chunk_tokenizer
+            .with_truncation(None)
+            .map_err(|e| format!("Failed to disable truncation: {}", e))?;
+
+        Ok(Self {
+            session: Mutex::new(session),
+            tokenizer,
+            chunk_tokenizer,
+        })
+    }
+}
+
+impl EmbeddingProvider for OnnxProvider {
+    fn model_id(&self) -> String {
+        ONNX_MODEL_ID.to_string()
+    }
+
+    fn dimension(&self) -> usize {
+        ONNX_DIMENSION
+    }
+
+    fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|e| format!("Failed to lock ONNX session: {}", e))?;
+        compute_embeddings(&mut session, &self.tokenizer, texts)
+    }
+
+    /// Windows of `CHUNK_TOKENS` tokens that overlap by `CHUNK_OVERLAP_TOKENS`,
+    /// so the end of a long block is not cut off at the model's sequence limit.
+    fn chunk_ranges(&self, text: &str) -> Result<Vec<(usize, usize)>, String> {
+        let encoding = self
+            .chunk_tokenizer
+            .encode(text, false)
+            .map_err(|e| format!("Tokenization failed: {}", e))?;
+        let offsets = encoding.get_offsets();
+
+        if offsets.len() <= CHUNK_TOKENS {
+            return Ok(vec![(0, text.len())]);
+        }
+
+        let stride = CHUNK_TOKENS - CHUNK_OVERLAP_TOKENS;
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        loop {
+            let end = (start + CHUNK_TOKENS).min(offsets.len());
+            ranges.push((offsets[start].0, offsets[end - 1].1));
+            if end == offsets.len() {
+                break;
+            }
+            start += stride;
+        }
+
+        Ok(ranges)
+    }
+}
+
+/// Runs the model on a batch of texts. Sequences are padded to the longest one in
+/// the batch, and padding is left out of mean pooling via the attention mask.
+fn compute_embeddings(
+    session: &mut Session,
+    tokenizer: &Tokenizer,
+    texts: &[&str],
+) -> Result<Vec<Vec<f32>>, String> {
+    use ort::value::Tensor;
+
+    let encodings = tokenizer
+        .encode_batch(texts.to_vec(), true)
+        .map_err(|e| format!("Tokenization failed: {}", e))?;
+
+    let batch_size = encodings.len();
+    let seq_len = encodings
+        .iter()
+        .map(|encoding| encoding.get_ids().len().min(MAX_SEQUENCE_LENGTH))
+        .max()
+        .unwrap_or(0);
+
+    if batch_size == 0 || seq_len == 0 {
+        return Ok(vec![vec![0.0f32; ONNX_DIMENSION]; texts.len()]);
+    }
+
+    let pad_id = tokenizer.token_to_id("<pad>").unwrap_or(0) as i64;
+    let mut input_ids: Vec<i64> = vec![pad_id; batch_size * seq_len];
+    let mut attention_mask: Vec<i64> = vec![0; batch_size * seq_len];
+    let mut token_type_ids: Vec<i64> = vec![0; batch_size * seq_len];
+
+    for (row, encoding) in encodings.iter().enumerate() {
+        let tokens = encoding
+            .get_ids()
+            .iter()
+            .zip(encoding.get_attention_mask())
+            .zip(encoding.get_type_ids())
+            .take(MAX_SEQUENCE_LENGTH);
+        for (i, ((&id, &mask), &type_id)) in tokens.enumerate() {
+            input_ids[row * seq_len + i] = id as i64;
+            attention_mask[row * seq_len + i] = mask as i64;
+            token_type_ids[row * seq_len + i] = type_id as i64;
+        }
+    }
+
+    let input_ids_tensor = Tensor::from_array(([batch_size, seq_len], input_ids.into_boxed_slice()))
+        .map_err(|e| format!("Failed to create input_ids tensor: {}", e))?;
+    let attention_mask_tensor = Tensor::from_array(([batch_size, seq_len], attention_mask.clone().into_boxed_slice()))
+        .map_err(|e| format!("Failed to create attention_mask tensor: {}", e))?;
+    let token_type_ids_tensor = Tensor::from_array(([batch_size, seq_len], token_type_ids.into_boxed_slice()))
+        .map_err(|e| format!("Failed to create token_type_ids tensor: {}", e))?;
+
+    let outputs = session
+        .run(ort::inputs![
+            "input_ids" => input_ids_tensor,
+            "attention_mask" => attention_mask_tensor,
+            "token_type_ids" => token_type_ids_tensor,
+        ])
+        .map_err(|e| format!("Inference failed: {}", e))?;
+
+    let output_value = outputs
+        .get("last_hidden_state")
+        .or_else(|| outputs.get("sentence_embedding"))
+        .ok_or("No output tensor found")?;
+
+    let (shape, data) = output_value
+        .try_extract_tensor::<f32>()
+        .map_err(|e| format!("Failed to extract tensor: {}", e))?;
+
+    let dims: Vec<usize> = shape.iter().map(|&d| d as usize).collect();
+
+    let embeddings: Vec<Vec<f32>> = if dims.len() == 3 {
+        // Shape: [batch, seq_len, hidden_size] - mean pooling over the unpadded tokens
+        let seq_length = dims[1];
+        let hidden_size = dims[2];
+
+        (0..batch_size)
+            .map(|row| {
+                let mut pooled = vec![0.0f32; hidden_size];
+                let mut token_count = 0.0f32;
+                for i in 0..seq_length {
+                    if attention_mask[row * seq_len + i] == 0 {
+                        continue;
+                    }
+                    let base = (row * seq_length + i) * hidden_size;
+                    for (j, value) in pooled.iter_mut().enumerate() {
+                        *value += data[base + j];
+                    }
+                    token_count += 1.0;
+                }
+                if token_count > 0.0 {
+                    for val in &mut pooled {
+                        *val /= token_count;
+                    }
+                }
+                pooled
+            })
+            .collect()
+    } else {
+        // Shape: [batch, hidden_size] - already pooled
+        let hidden_size = dims.last().copied().unwrap_or(0).max(1);
+        data.chunks(hidden_size).take(batch_size).map(|row| row.to_vec()).collect()
+    };
+
+    Ok(embeddings.into_iter().map(l2_normalize).collect())
+}
@@ -0,0 +1,168 @@
+use crate::domains::config::model::EmbeddingSettings;
+use crate::domains::document::embedding::provider::{l2_normalize, EmbeddingProvider};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::info;
+
+const REQUEST_TIMEOUT_SECS: u64 = 60;
+/// Characters per chunk. Endpoints don't report their model's input limit, so
+/// this stays well below the 512 tokens most embedding models accept.
+const CHUNK_CHARS: usize = 1000;
+const CHUNK_OVERLAP_CHARS: usize = 100;
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [&'a str],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: usize,
+}
+
+/// An OpenAI-compatible `/embeddings` endpoint, e.g. a local Ollama or llama.cpp server.
+pub struct OpenAiCompatibleProvider {
+    client: Client,
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+    dimension: usize,
+}
+
+impl OpenAiCompatibleProvider {
+    /// Builds the provider from the settings. Without a configured dimension,
+    /// the endpoint is probed once to learn it.
+    pub fn connect(settings: &EmbeddingSettings) -> Result<Self, String> {
+        let base_url = settings
+            .base_url
+            .as_deref()
+            .filter(|url| !url.trim().is_empty())
+            .ok_or_else(|| "Embedding base URL is not configured".to_string())?;
+        let model = settings
+            .model
+            .clone()
+            .filter(|model| !model.trim().is_empty())
+            .ok_or_else(|| "Embedding model is not configured".to_string())?;
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        let mut provider = Self {
+            client,
+            endpoint: format!("{}/embeddings", base_url.trim_end_matches('/')),
+            model,
+            api_key: settings.api_key.clone().filter(|key| !key.is_empty()),
+            dimension: settings.dimension.unwrap_or(0),
+        };
+
+        if provider.dimension == 0 {
+            provider.dimension = provider
+                .request(&["dimension probe"])?
+                .first()
+                .map(Vec::len)
+                .filter(|len| *len > 0)
+                .ok_or_else(|| "Embedding endpoint returned an empty vector".to_string())?;
+            info!("Embedding endpoint {} returns {} dimensions", provider.endpoint, provider.dimension);
+        }
+
+        Ok(provider)
+    }
+
+    fn request(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+        let mut request = self.client.post(&self.endpoint).json(&EmbeddingRequest {
+            model: &self.model,
+            input: texts,
+        });
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .map_err(|e| format!("Embedding request failed: {}", e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(format!("Embedding endpoint returned {}: {}", status, body));
+        }
+
+        let mut response: EmbeddingResponse = response
+            .json()
+            .map_err(|e| format!("Failed to parse embedding response: {}", e))?;
+        if response.data.len() != texts.len() {
+            return Err(format!(
+                "Embedding endpoint returned {} vectors for {} texts",
+                response.data.len(),
+                texts.len()
+            ));
+        }
+
+        response.data.sort_by_key(|data| data.index);
+        Ok(response
+            .data
+            .into_iter()
+            .map(|data| l2_normalize(data.embedding))
+            .collect())
+    }
+}
+
+impl EmbeddingProvider for OpenAiCompatibleProvider {
+    fn model_id(&self) -> String {
+        self.model.clone()
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+        let embeddings = self.request(texts)?;
+        if let Some(embedding) = embeddings.iter().find(|e| e.len() != self.dimension) {
+            return Err(format!(
+                "Expected {} dimensions but the endpoint returned {}",
+                self.dimension,
+                embedding.len()
+            ));
+        }
+        Ok(embeddings)
+    }
+
+    /// Windows of `CHUNK_CHARS` characters that overlap by `CHUNK_OVERLAP_CHARS`.
+    fn chunk_ranges(&self, text: &str) -> Result<Vec<(usize, usize)>, String> {
+        let boundaries: Vec<usize> = text
+            .char_indices()
+            .map(|(offset, _)| offset)
+            .chain(std::iter::once(text.len()))
+            .collect();
+        let char_count = boundaries.len() - 1;
+
+        if char_count <= CHUNK_CHARS {
+            return Ok(vec![(0, text.len())]);
+        }
+
+        let stride = CHUNK_CHARS - CHUNK_OVERLAP_CHARS;
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        loop {
+            let end = (start + CHUNK_CHARS).min(char_count);
+            ranges.push((boundaries[start], boundaries[end]));
+            if end == char_count {
+                break;
+            }
+            start += stride;
+        }
+
+        Ok(ranges)
+    }
+}
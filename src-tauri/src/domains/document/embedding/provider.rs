@@ -0,0 +1,28 @@
+/// A source of text embeddings. Implementations are called from the embedding
+/// worker thread only, so they may block.
+pub trait EmbeddingProvider: Send + Sync {
+    /// Identifier recorded in `blocks.embedding_model` for the vectors this provider produces.
+    fn model_id(&self) -> String;
+
+    /// Length of every vector `embed` returns; `vec_blocks` is created with it.
+    fn dimension(&self) -> usize;
+
+    /// Embeds a batch of non-empty texts, one L2-normalized vector per text.
+    fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String>;
+
+    /// Byte ranges of `text` to embed as separate chunks. Providers without an
+    /// input limit embed the whole text as one chunk.
+    fn chunk_ranges(&self, text: &str) -> Result<Vec<(usize, usize)>, String> {
+        Ok(vec![(0, text.len())])
+    }
+}
+
+pub fn l2_normalize(mut embedding: Vec<f32>) -> Vec<f32> {
+    let magnitude: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if magnitude > 0.0 {
+        for val in &mut embedding {
+            *val /= magnitude;
+        }
+    }
+    embedding
+}
@@ -0,0 +1,174 @@
+use crate::domains::document::embedding::{
+    calculate_chunk_embeddings, calculate_query_embeddings, ChunkEmbedding, QueryEmbedding,
+};
+use once_cell::sync::Lazy;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use tokio::sync::oneshot;
+use tracing::{error, info};
+
+/// Order in which queued requests are served: interactive requests (search
+/// queries) go ahead of background indexing. Requests of equal priority are FIFO.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EmbeddingPriority {
+    Background,
+    Interactive,
+}
+
+enum EmbeddingJob {
+    Query {
+        text: String,
+        reply: oneshot::Sender<Vec<QueryEmbedding>>,
+    },
+    Chunks {
+        texts: Vec<String>,
+        reply: oneshot::Sender<Vec<Result<Vec<ChunkEmbedding>, String>>>,
+    },
+}
+
+struct QueuedJob {
+    priority: EmbeddingPriority,
+    sequence: u64,
+    job: EmbeddingJob,
+}
+
+impl PartialEq for QueuedJob {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.sequence == other.sequence
+    }
+}
+
+impl Eq for QueuedJob {}
+
+impl PartialOrd for QueuedJob {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedJob {
+    // BinaryHeap pops the greatest: higher priority first, then the earlier request
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+#[derive(Default)]
+struct JobQueue {
+    jobs: BinaryHeap<QueuedJob>,
+    next_sequence: u64,
+}
+
+#[derive(Default)]
+struct SharedQueue {
+    queue: Mutex<JobQueue>,
+    available: Condvar,
+}
+
+/// Runs embedding inference on a dedicated thread, so the CPU-heavy model never
+/// blocks a tokio worker or a command thread. Callers queue a request and await its result.
+pub struct EmbeddingWorker {
+    shared: Arc<SharedQueue>,
+}
+
+impl EmbeddingWorker {
+    fn spawn() -> Self {
+        let shared = Arc::new(SharedQueue::default());
+        let worker_shared = shared.clone();
+
+        thread::Builder::new()
+            .name("embedding-worker".to_string())
+            .spawn(move || run(worker_shared))
+            .expect("Failed to spawn embedding worker thread");
+
+        info!("Embedding worker started");
+        Self { shared }
+    }
+
+    fn submit(&self, priority: EmbeddingPriority, job: EmbeddingJob) {
+        let mut queue = self
+            .shared
+            .queue
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let sequence = queue.next_sequence;
+        queue.next_sequence += 1;
+        queue.jobs.push(QueuedJob {
+            priority,
+            sequence,
+            job,
+        });
+        self.shared.available.notify_one();
+    }
+}
+
+fn run(shared: Arc<SharedQueue>) {
+    loop {
+        let queued = {
+            let mut queue = shared
+                .queue
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            loop {
+                if let Some(queued) = queue.jobs.pop() {
+                    break queued;
+                }
+                queue = shared
+                    .available
+                    .wait(queue)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+            }
+        };
+
+        // A panicking job drops its reply sender, so only that caller sees an error
+        if catch_unwind(AssertUnwindSafe(|| process(queued.job))).is_err() {
+            error!("Embedding job panicked");
+        }
+    }
+}
+
+fn process(job: EmbeddingJob) {
+    match job {
+        EmbeddingJob::Query { text, reply } => {
+            let _ = reply.send(calculate_query_embeddings(&text));
+        }
+        EmbeddingJob::Chunks { texts, reply } => {
+            let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+            let _ = reply.send(calculate_chunk_embeddings(&texts));
+        }
+    }
+}
+
+static EMBEDDING_WORKER: Lazy<EmbeddingWorker> = Lazy::new(EmbeddingWorker::spawn);
+
+/// Embeds a search query with each model vectors are stored with, see
+/// `embedding::calculate_query_embeddings`.
+pub async fn embed_query(
+    text: String,
+    priority: EmbeddingPriority,
+) -> Result<Vec<QueryEmbedding>, String> {
+    let (reply, receiver) = oneshot::channel();
+    EMBEDDING_WORKER.submit(priority, EmbeddingJob::Query { text, reply });
+
+    receiver
+        .await
+        .map_err(|_| "Embedding worker dropped the request".to_string())
+}
+
+/// Embeds each text as chunks, see `embedding::calculate_chunk_embeddings`.
+pub async fn embed_chunks(
+    texts: Vec<String>,
+    priority: EmbeddingPriority,
+) -> Result<Vec<Result<Vec<ChunkEmbedding>, String>>, String> {
+    let (reply, receiver) = oneshot::channel();
+    EMBEDDING_WORKER.submit(priority, EmbeddingJob::Chunks { texts, reply });
+
+    receiver
+        .await
+        .map_err(|_| "Embedding worker dropped the request".to_string())
+}
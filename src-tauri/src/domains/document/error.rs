@@ -22,4 +22,13 @@ pub enum DocumentError {
 
     #[error("Failed to query database")]
     DatabaseQueryError(RusqliteError),
+
+    #[error("Invalid search query: {0}")]
+    InvalidQueryError(String),
+
+    #[error("Vector benchmark unavailable: {0}")]
+    BenchmarkUnavailableError(String),
+
+    #[error("Edge not found: {0}")]
+    EdgeNotFoundError(String),
 }
@@ -0,0 +1,135 @@
+/// How many characters of context a backlink snippet keeps on each side of the reference.
+const SNIPPET_CONTEXT: usize = 60;
+
+/// A `[[...]]` reference in block content: its byte range, brackets included,
+/// and the title or document id it points to.
+pub struct LinkReference<'a> {
+    pub start: usize,
+    pub end: usize,
+    pub target: &'a str,
+}
+
+/// The `[[Title]]` / `[[doc-id]]` references in `content`, in order. An alias
+/// (`[[Title|shown text]]`) or heading (`[[Title#Heading]]`) suffix is not part
+/// of the target; references spanning lines or with an empty target are skipped.
+pub fn find_references(content: &str) -> Vec<LinkReference<'_>> {
+    let mut references = Vec::new();
+    let mut rest = 0;
+
+    while let Some(open) = content[rest..].find("[[").map(|i| rest + i) {
+        let inner_start = open + 2;
+        let Some(close) = content[inner_start..].find("]]").map(|i| inner_start + i) else {
+            break;
+        };
+        let inner = &content[inner_start..close];
+
+        // A second opening inside means the first one was never closed
+        if let Some(nested) = inner.rfind("[[") {
+            rest = inner_start + nested;
+            continue;
+        }
+        rest = close + 2;
+
+        if inner.contains('\n') {
+            continue;
+        }
+        let target = inner.split(['|', '#']).next().unwrap_or("").trim();
+        if !target.is_empty() {
+            references.push(LinkReference {
+                start: open,
+                end: close + 2,
+                target,
+            });
+        }
+    }
+
+    references
+}
+
+/// The text around the first reference in `content` whose target satisfies
+/// `is_target`, with an ellipsis where the content was cut.
+pub fn reference_snippet(content: &str, is_target: impl Fn(&str) -> bool) -> Option<String> {
+    let reference = find_references(content)
+        .into_iter()
+        .find(|reference| is_target(reference.target))?;
+
+    let before = &content[..reference.start];
+    let after = &content[reference.end..];
+    let start = before
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CONTEXT - 1)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    let end = after
+        .char_indices()
+        .nth(SNIPPET_CONTEXT)
+        .map(|(i, _)| reference.end + i)
+        .unwrap_or(content.len());
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    snippet.push_str(content[start..end].trim());
+    if end < content.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(content: &str) -> Vec<&str> {
+        find_references(content).into_iter().map(|r| r.target).collect()
+    }
+
+    #[test]
+    fn references_drop_alias_and_heading_suffixes() {
+        assert_eq!(
+            targets("See [[Rust]], [[Graph Theory|graphs]] and [[ doc-1#Intro ]]."),
+            vec!["Rust", "Graph Theory", "doc-1"]
+        );
+    }
+
+    #[test]
+    fn unclosed_multiline_and_empty_references_are_skipped() {
+        assert_eq!(targets("[[open [[Closed]] [[]] [[|alias]] [[split\nline]] [[tail"), vec!["Closed"]);
+    }
+
+    #[test]
+    fn reference_ranges_include_the_brackets() {
+        let content = "a [[B|c]] d";
+        let reference = &find_references(content)[0];
+
+        assert_eq!(&content[reference.start..reference.end], "[[B|c]]");
+    }
+
+    #[test]
+    fn snippets_show_the_first_matching_reference() {
+        let snippet = reference_snippet("[[Other]] then [[Target]] here", |t| t == "Target");
+
+        assert_eq!(snippet.as_deref(), Some("[[Other]] then [[Target]] here"));
+        assert!(reference_snippet("[[Other]]", |t| t == "Target").is_none());
+    }
+
+    #[test]
+    fn long_snippets_are_cut_around_the_reference() {
+        let before = "é".repeat(SNIPPET_CONTEXT + 10);
+        let after = "x".repeat(SNIPPET_CONTEXT + 10);
+        let content = format!("{}[[Target]]{}", before, after);
+
+        let snippet = reference_snippet(&content, |t| t == "Target").unwrap();
+
+        assert_eq!(
+            snippet,
+            format!(
+                "…{}[[Target]]{}…",
+                "é".repeat(SNIPPET_CONTEXT),
+                "x".repeat(SNIPPET_CONTEXT)
+            )
+        );
+    }
+}
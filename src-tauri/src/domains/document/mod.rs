@@ -1,7 +1,10 @@
 pub mod command;
 pub mod embedding;
+pub mod embedding_worker;
 pub mod error;
+pub mod link;
 pub mod model;
 pub mod repository;
 pub mod scheduler;
+pub mod search;
 pub mod service;
@@ -1,3 +1,4 @@
+use crate::domains::config::model::VectorStorage;
 use serde::{Deserialize, Serialize};
 
 // ============================================
@@ -24,11 +25,113 @@ pub struct Block {
     pub content: Option<String>,
     pub order_index: f64,
     pub source_document_id: Option<String>,
+    /// The block this one embeds or quotes, in the document `source_document_id`.
+    #[serde(default)]
+    pub source_block_id: Option<String>,
+    /// 'embed' follows the source's content; 'quote' is a copy, flagged by
+    /// `source_changed` once the source is edited.
+    #[serde(default)]
+    pub reference_kind: Option<String>,
+    #[serde(default)]
+    pub source_changed: bool,
     pub indexing_status: i16,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexingStats {
+    pub pending: i64,
+    pub indexed: i64,
+    pub failed: i64,
+    pub stale: i64,
+    pub total: i64,
+}
+
+/// A block that exhausted its indexing attempts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedBlock {
+    pub block_id: String,
+    pub document_id: String,
+    pub document_title: Option<String>,
+    pub excerpt: Option<String>,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// Blocks a reindex covers.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ReindexScope {
+    All,
+    Document {
+        #[serde(rename = "documentId")]
+        document_id: String,
+    },
+    Tag {
+        tag: String,
+    },
+}
+
+/// A reindex in progress: how many blocks in its scope have been embedded into
+/// the shadow tables so far.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReindexProgress {
+    pub scope: ReindexScope,
+    pub total: i64,
+    pub embedded: i64,
+    pub failed: i64,
+    pub started_at: Option<String>,
+    /// The embedding model isn't the one the job started with; it resumes once it is again.
+    pub paused: bool,
+}
+
+// ============================================
+// Listing Models
+// ============================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DocumentSortKey {
+    #[default]
+    Updated,
+    Created,
+    Title,
+    Connections,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DocumentListRequest {
+    pub cursor: Option<String>,     // next_cursor of the previous page
+    pub limit: Option<i64>,         // default 50, max 500
+    pub sort_by: DocumentSortKey,
+    pub direction: SortDirection,
+    pub statuses: Option<Vec<i16>>, // default: everything but ARCHIVED
+    pub tag: Option<String>,
+    pub query: Option<String>,      // same syntax as search_documents
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentPage {
+    pub items: Vec<Document>,
+    pub next_cursor: Option<String>,
+    pub total_count: i64,
+}
+
 // ============================================
 // Graph Models
 // ============================================
@@ -46,6 +149,7 @@ pub struct GraphNode {
     pub id: String,
     pub label: String,
     pub node_type: String,
+    pub hop: Option<u32>, // distance from the document a neighborhood was expanded from
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,7 +158,11 @@ pub struct GraphEdge {
     pub source: String,
     pub target: String,
     pub edge_type: String,
+    pub relation_type: Option<String>, // 'similar' or 'link' for document-document edges
     pub weight: Option<f64>,
+    pub pinned: bool, // kept by the indexing scheduler whatever the similarity
+    pub label: Option<String>,
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,6 +181,32 @@ pub struct EdgeChangeInfo {
     pub removed_edges: Vec<GraphEdge>,
 }
 
+/// Why two documents are connected: the block pairs the indexing scheduler found
+/// similar across them, best first. A block pair counts when its distance
+/// (1 - similarity) is below `similarity_threshold`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EdgeExplanation {
+    pub source_id: String,
+    pub target_id: String,
+    pub weight: Option<f64>, // weight of the similar edge, if there is one
+    pub pinned: bool,
+    pub document_similarity: Option<f32>,
+    pub similarity_threshold: f32,
+    pub block_pairs: Vec<BlockPairEvidence>,
+    pub block_pairs_measured: bool, // closest chunks measured now, for an edge drawn from the document vectors
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockPairEvidence {
+    pub source_block_id: String,
+    pub source_snippet: Option<String>,
+    pub target_block_id: String,
+    pub target_snippet: Option<String>,
+    pub similarity: f32,
+}
+
 // ============================================
 // Event Models
 // ============================================
@@ -92,6 +226,31 @@ pub struct DocumentUpdatedEvent {
     pub updated_at: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IndexingState {
+    Running,
+    Idle,
+    Paused,
+    /// The fallback stands in for the configured provider; indexing waits for it.
+    WaitingForProvider,
+}
+
+/// Payload of the `indexing-progress` event, emitted after each indexed batch
+/// and whenever the scheduler changes state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexingProgress {
+    pub state: IndexingState,
+    pub queue_length: i64,
+    pub failed: i64,
+    pub indexed_this_session: u64,
+    pub blocks_per_second: Option<f64>,
+    pub eta_seconds: Option<u64>,
+    pub idle_interval_secs: u64,
+    pub reindex: Option<ReindexProgress>,
+}
+
 // ============================================
 // Search Models
 // ============================================
@@ -106,4 +265,52 @@ pub struct SearchResult {
     pub match_type: String, // "title", "tag", "content", "similar"
     pub match_snippet: Option<String>,
     pub similarity_score: Option<f32>,
+    pub score: f32,                   // hybrid (reciprocal rank fusion) score, higher is better
+    pub matched_signals: Vec<String>, // "title", "tag", "content", "semantic"
+    pub block_hits: Vec<BlockHit>,    // matching blocks, best first
+}
+
+/// A document close to another one by document vector, for "related notes".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedDocument {
+    pub id: String,
+    pub title: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub status: i16,
+    pub similarity_score: f32,
+}
+
+/// A document linking to another one through `[[...]]` references; `blocks` are
+/// the referencing blocks, with the text around the reference as snippet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Backlink {
+    pub id: String,
+    pub title: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub status: i16,
+    pub blocks: Vec<BlockHit>,
+}
+
+/// A block that matched a search, so the editor can open the document at it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockHit {
+    pub block_id: String,
+    pub order_index: f64,
+    pub snippet: Option<String>, // FTS snippet with <mark> highlights, or the block's opening text
+    pub similarity_score: Option<f32>,
+}
+
+/// Recall and size of one vector storage mode, measured on the vault's own vectors.
+/// `recall` is the share of the exact nearest neighbours the mode also returns.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VectorStorageBenchmark {
+    pub storage: VectorStorage,
+    pub rescore: bool,
+    pub recall: f64,
+    pub bytes_per_vector: i64,
+    pub total_bytes: i64,
 }
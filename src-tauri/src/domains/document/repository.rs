@@ -1,5 +1,17 @@
-use crate::domains::document::model::{Block, Document, SearchResult};
+use crate::domains::document::embedding::ChunkEmbedding;
+use crate::domains::document::link::find_references;
+use crate::domains::document::model::{
+    Backlink, Block, BlockHit, BlockPairEvidence, Document, DocumentSortKey, FailedBlock, GraphEdge,
+    IndexingStats, ReindexProgress, ReindexScope, RelatedDocument, SearchResult, SortDirection,
+};
+use crate::domains::document::search::fts::{build_match_expression, column_filter};
+use crate::domains::document::search::query::{ParsedQuery, SqlFilter};
+use crate::domains::document::search::tokenizer::index_text;
 use crate::infrastructure::database::query::{query_all, query_one};
+use crate::domains::config::model::VectorStorage;
+use crate::infrastructure::database::schema::{vector_layout, VectorLayout, RESCORE_OVERSAMPLING};
+use crate::utils::hash::content_hash;
+use rusqlite::types::Value;
 use rusqlite::{Connection, Result};
 
 // ============================================
@@ -72,6 +84,134 @@ pub fn find_documents(conn: &Connection, limit: i64, offset: i64) -> Result<Vec<
     )
 }
 
+/// Filter for document listings: the query's structured filters, plus a full-text
+/// restriction on title, tags or block content when the query has free text.
+fn document_list_filter(query: &ParsedQuery, first_param: usize) -> SqlFilter {
+    let mut filter = query.to_sql_filter(first_param);
+
+    if let Some(match_expression) = build_match_expression(&query.text) {
+        let placeholder = format!("?{}", first_param + filter.params.len());
+        filter.clause = format!(
+            "{} AND (d.seq IN (SELECT rowid FROM fts_documents WHERE fts_documents MATCH {p})
+                 OR d.id IN (
+                     SELECT b.document_id FROM fts_blocks
+                     JOIN blocks b ON b.seq = fts_blocks.rowid
+                     WHERE fts_blocks MATCH {p}
+                 ))",
+            filter.clause,
+            p = placeholder
+        );
+        filter.params.push(Value::Text(match_expression));
+    }
+
+    filter
+}
+
+/// One page of a document listing using keyset pagination.
+/// Returns each document with its sort value; `after` is the (sort value, id)
+/// of the last row of the previous page. Rows are compared on the sort columns
+/// themselves, so each sort walks its `(column, id)` index.
+pub fn find_document_page(
+    conn: &Connection,
+    query: &ParsedQuery,
+    sort_by: DocumentSortKey,
+    direction: SortDirection,
+    after: Option<(String, String)>,
+    limit: i64,
+) -> Result<Vec<(Document, String)>> {
+    let filter = document_list_filter(query, 1);
+    let mut params = filter.params;
+
+    let sort_column = match sort_by {
+        DocumentSortKey::Updated => "d.updated_at",
+        DocumentSortKey::Created => "d.created_at",
+        DocumentSortKey::Title => "COALESCE(d.title, '') COLLATE NOCASE",
+        DocumentSortKey::Connections => "d.connection_count",
+    };
+    let (order, comparison) = match direction {
+        SortDirection::Asc => ("ASC", ">"),
+        SortDirection::Desc => ("DESC", "<"),
+    };
+
+    let cursor_clause = match after {
+        Some((after_value, after_id)) => {
+            let after_value = match sort_by {
+                DocumentSortKey::Connections => {
+                    Value::Integer(after_value.parse().unwrap_or_default())
+                }
+                _ => Value::Text(after_value),
+            };
+            params.push(after_value);
+            params.push(Value::Text(after_id));
+            format!(
+                "AND ({}, d.id) {} (?{}, ?{})",
+                sort_column,
+                comparison,
+                params.len() - 1,
+                params.len()
+            )
+        }
+        None => String::new(),
+    };
+    params.push(Value::Integer(limit));
+
+    let sql = format!(
+        "SELECT d.id, d.title, d.status, d.tags, d.created_at, d.updated_at, {sort_column}
+        FROM documents d
+        WHERE {filter} {cursor_clause}
+        ORDER BY {sort_column} {order}, d.id {order}
+        LIMIT ?{limit_param}",
+        sort_column = sort_column,
+        filter = filter.clause,
+        cursor_clause = cursor_clause,
+        order = order,
+        limit_param = params.len()
+    );
+
+    query_all(
+        conn,
+        &sql,
+        rusqlite::params_from_iter(params),
+        |row| {
+            let tags_str: Option<String> = row.get(3)?;
+            let tags = tags_str.filter(|s| !s.is_empty()).map(|s| {
+                s.split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            });
+
+            let document = Document {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                status: row.get(2)?,
+                tags,
+                created_at: row.get(4)?,
+                updated_at: row.get(5)?,
+                blocks: Vec::new(),
+            };
+
+            let sort_value = match row.get::<_, Value>(6)? {
+                Value::Integer(count) => count.to_string(),
+                Value::Text(text) => text,
+                _ => String::new(),
+            };
+
+            Ok((document, sort_value))
+        },
+    )
+}
+
+pub fn count_documents(conn: &Connection, query: &ParsedQuery) -> Result<i64> {
+    let filter = document_list_filter(query, 1);
+
+    conn.query_row(
+        &format!("SELECT COUNT(*) FROM documents d WHERE {}", filter.clause),
+        rusqlite::params_from_iter(filter.params),
+        |row| row.get(0),
+    )
+}
+
 pub fn upsert_document(conn: &Connection, document: &Document) -> Result<()> {
     let tags_str = document
         .tags
@@ -79,15 +219,27 @@ pub fn upsert_document(conn: &Connection, document: &Document) -> Result<()> {
         .map(|t| t.join(","))
         .unwrap_or_default();
 
+    let title_tokens = index_text(document.title.as_deref().unwrap_or(""));
+    let tag_tokens = index_text(&tags_str);
+
     conn.execute(
-        "INSERT INTO documents (id, title, status, tags)
-         VALUES (?1, ?2, ?3, ?4)
+        "INSERT INTO documents (id, title, status, tags, title_tokens, tag_tokens)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
          ON CONFLICT(id) DO UPDATE SET
              title = excluded.title,
              status = excluded.status,
              tags = excluded.tags,
+             title_tokens = excluded.title_tokens,
+             tag_tokens = excluded.tag_tokens,
              updated_at = datetime('now', 'localtime')",
-        (&document.id, &document.title, &document.status, &tags_str),
+        (
+            &document.id,
+            &document.title,
+            &document.status,
+            &tags_str,
+            &title_tokens,
+            &tag_tokens,
+        ),
     )?;
 
     Ok(())
@@ -106,7 +258,8 @@ pub fn find_blocks_by_document_id(conn: &Connection, document_id: &str) -> Resul
     query_all(
         conn,
         "SELECT id, document_id, content, order_index,
-                source_document_id, indexing_status, created_at, updated_at
+                source_document_id, indexing_status, created_at, updated_at,
+                source_block_id, reference_kind
          FROM blocks
          WHERE document_id = ?
          ORDER BY order_index ASC",
@@ -118,6 +271,9 @@ pub fn find_blocks_by_document_id(conn: &Connection, document_id: &str) -> Resul
                 content: row.get(2)?,
                 order_index: row.get(3)?,
                 source_document_id: row.get(4)?,
+                source_block_id: row.get(8)?,
+                reference_kind: row.get(9)?,
+                source_changed: false,
                 indexing_status: row.get(5)?,
                 created_at: row.get(6)?,
                 updated_at: row.get(7)?,
@@ -130,7 +286,8 @@ pub fn find_block_by_id(conn: &Connection, block_id: &str) -> Result<Option<Bloc
     query_one(
         conn,
         "SELECT id, document_id, content, order_index,
-                source_document_id, indexing_status, created_at, updated_at
+                source_document_id, indexing_status, created_at, updated_at,
+                source_block_id, reference_kind
          FROM blocks
          WHERE id = ?",
         [block_id],
@@ -141,6 +298,9 @@ pub fn find_block_by_id(conn: &Connection, block_id: &str) -> Result<Option<Bloc
                 content: row.get(2)?,
                 order_index: row.get(3)?,
                 source_document_id: row.get(4)?,
+                source_block_id: row.get(8)?,
+                reference_kind: row.get(9)?,
+                source_changed: false,
                 indexing_status: row.get(5)?,
                 created_at: row.get(6)?,
                 updated_at: row.get(7)?,
@@ -149,16 +309,29 @@ pub fn find_block_by_id(conn: &Connection, block_id: &str) -> Result<Option<Bloc
     )
 }
 
-pub fn find_oldest_pending_block(conn: &Connection) -> Result<Option<Block>> {
-    query_one(
+/// Up to `limit` blocks that need (re-)embedding and are not backing off after a
+/// failure: PENDING blocks first, then INDEXED blocks whose vector was built from
+/// older content or by another model than `model_id`, least recently updated first.
+/// Embeds and quotes only copy their source, which is indexed in their place.
+pub fn find_oldest_pending_blocks(
+    conn: &Connection,
+    model_id: &str,
+    limit: i64,
+) -> Result<Vec<Block>> {
+    query_all(
         conn,
         "SELECT id, document_id, content, order_index,
-                source_document_id, indexing_status, created_at, updated_at
+                source_document_id, indexing_status, created_at, updated_at,
+                source_block_id, reference_kind
          FROM blocks
-         WHERE indexing_status = 0
-         ORDER BY updated_at ASC
-         LIMIT 1",
-        [],
+         WHERE (indexing_status = 0
+                OR (indexing_status = 1
+                    AND (indexed_hash IS NOT content_hash OR embedding_model IS NOT ?1)))
+           AND source_block_id IS NULL
+           AND (next_attempt_at IS NULL OR next_attempt_at <= datetime('now', 'localtime'))
+         ORDER BY indexing_status ASC, updated_at ASC
+         LIMIT ?2",
+        rusqlite::params![model_id, limit],
         |row| {
             Ok(Block {
                 id: row.get(0)?,
@@ -166,6 +339,9 @@ pub fn find_oldest_pending_block(conn: &Connection) -> Result<Option<Block>> {
                 content: row.get(2)?,
                 order_index: row.get(3)?,
                 source_document_id: row.get(4)?,
+                source_block_id: row.get(8)?,
+                reference_kind: row.get(9)?,
+                source_changed: false,
                 indexing_status: row.get(5)?,
                 created_at: row.get(6)?,
                 updated_at: row.get(7)?,
@@ -174,21 +350,48 @@ pub fn find_oldest_pending_block(conn: &Connection) -> Result<Option<Block>> {
     )
 }
 
+/// The indexing status sent by the client is ignored: a block goes back to
+/// PENDING only when the hash of its content changes. A reference is in sync with
+/// its source when it is new, points at another source, or has the same content.
 pub fn upsert_block(conn: &Connection, block: &Block) -> Result<()> {
+    let content = block.content.as_deref().unwrap_or("");
+    let hash = content_hash(content);
+    let content_tokens = index_text(content);
+
     conn.execute(
         "INSERT INTO blocks (id, document_id, content, order_index,
-                             source_document_id, indexing_status)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                             source_document_id, indexing_status, content_hash, content_tokens,
+                             source_block_id, reference_kind, source_hash)
+         VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6, ?7, ?8, ?9,
+                 (SELECT content_hash FROM blocks WHERE id = ?8))
          ON CONFLICT(id) DO UPDATE SET
              content = excluded.content,
              order_index = excluded.order_index,
              source_document_id = excluded.source_document_id,
+             source_block_id = excluded.source_block_id,
+             reference_kind = excluded.reference_kind,
+             source_hash = CASE
+                 WHEN excluded.source_block_id IS NOT blocks.source_block_id
+                      OR excluded.content_hash IS excluded.source_hash THEN excluded.source_hash
+                 ELSE blocks.source_hash
+             END,
+             content_hash = excluded.content_hash,
+             content_tokens = excluded.content_tokens,
              indexing_status = CASE
-                 WHEN blocks.content IS NOT excluded.content THEN 0
+                 WHEN blocks.content_hash IS NOT excluded.content_hash
+                      OR excluded.source_block_id IS NOT NULL THEN 0
                  ELSE blocks.indexing_status
              END,
+             indexing_attempts = CASE
+                 WHEN blocks.content_hash IS NOT excluded.content_hash THEN 0
+                 ELSE blocks.indexing_attempts
+             END,
+             next_attempt_at = CASE
+                 WHEN blocks.content_hash IS NOT excluded.content_hash THEN NULL
+                 ELSE blocks.next_attempt_at
+             END,
              updated_at = CASE
-                 WHEN blocks.content IS NOT excluded.content THEN datetime('now', 'localtime')
+                 WHEN blocks.content_hash IS NOT excluded.content_hash THEN datetime('now', 'localtime')
                  ELSE blocks.updated_at
              END",
         (
@@ -197,13 +400,105 @@ pub fn upsert_block(conn: &Connection, block: &Block) -> Result<()> {
             &block.content,
             &block.order_index,
             &block.source_document_id,
-            &block.indexing_status,
+            &hash,
+            &content_tokens,
+            &block.source_block_id,
+            &block.reference_kind,
         ),
     )?;
 
     Ok(())
 }
 
+/// Content of the blocks in a document that reference another block, as
+/// (block id, source content, whether a quote's source changed since it was copied).
+pub fn find_block_sources(
+    conn: &Connection,
+    document_id: &str,
+) -> Result<Vec<(String, Option<String>, bool)>> {
+    query_all(
+        conn,
+        "SELECT b.id, s.content,
+                b.reference_kind = 'quote' AND b.source_hash IS NOT s.content_hash
+         FROM blocks b
+         JOIN blocks s ON s.id = b.source_block_id
+         WHERE b.document_id = ?",
+        [document_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+}
+
+/// Copies a block's content into the blocks embedding it and returns the documents
+/// whose blocks changed, which count as updated.
+pub fn propagate_block_content(conn: &Connection, source_block_id: &str) -> Result<Vec<String>> {
+    let document_ids: Vec<String> = query_all(
+        conn,
+        "SELECT DISTINCT b.document_id
+         FROM blocks b
+         JOIN blocks s ON s.id = b.source_block_id
+         WHERE b.source_block_id = ?1
+           AND b.reference_kind = 'embed'
+           AND b.content_hash IS NOT s.content_hash",
+        [source_block_id],
+        |row| row.get(0),
+    )?;
+    if document_ids.is_empty() {
+        return Ok(document_ids);
+    }
+
+    conn.execute(
+        "UPDATE blocks
+         SET content = s.content,
+             content_hash = s.content_hash,
+             content_tokens = s.content_tokens,
+             source_hash = s.content_hash,
+             indexing_status = 0,
+             indexing_attempts = 0,
+             next_attempt_at = NULL,
+             updated_at = datetime('now', 'localtime')
+         FROM (SELECT content, content_hash, content_tokens FROM blocks WHERE id = ?1) AS s
+         WHERE blocks.source_block_id = ?1
+           AND blocks.reference_kind = 'embed'
+           AND blocks.content_hash IS NOT s.content_hash",
+        [source_block_id],
+    )?;
+    let ids_json = serde_json::to_string(&document_ids).unwrap_or_else(|_| "[]".to_string());
+    conn.execute(
+        "UPDATE documents SET updated_at = datetime('now', 'localtime')
+         WHERE id IN (SELECT value FROM json_each(?1))",
+        [&ids_json],
+    )?;
+
+    Ok(document_ids)
+}
+
+/// Points the blocks referencing `block_id` at its own source, for a block that
+/// became a reference itself, so references never chain.
+pub fn repoint_block_references(
+    conn: &Connection,
+    block_id: &str,
+    source_block_id: &str,
+    source_document_id: &str,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE blocks SET source_block_id = ?2, source_document_id = ?3 WHERE source_block_id = ?1",
+        [block_id, source_block_id, source_document_id],
+    )?;
+    Ok(())
+}
+
+/// Turns the blocks referencing a deleted block into plain blocks that keep
+/// their last content.
+pub fn detach_block_references(conn: &Connection, block_id: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE blocks
+         SET source_block_id = NULL, source_document_id = NULL, reference_kind = NULL, source_hash = NULL
+         WHERE source_block_id = ?",
+        [block_id],
+    )?;
+    Ok(())
+}
+
 pub fn update_block_indexing_status(conn: &Connection, block_id: &str, status: i16) -> Result<()> {
     conn.execute(
         "UPDATE blocks SET indexing_status = ? WHERE id = ?",
@@ -212,6 +507,108 @@ pub fn update_block_indexing_status(conn: &Connection, block_id: &str, status: i
     Ok(())
 }
 
+/// Marks a block INDEXED and records which content and model its vector was built from.
+pub fn mark_block_indexed(
+    conn: &Connection,
+    block_id: &str,
+    indexed_hash: &str,
+    model_id: &str,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE blocks
+         SET indexing_status = 1, indexed_hash = ?1, embedding_model = ?2,
+             indexing_attempts = 0, indexing_error = NULL, next_attempt_at = NULL,
+             last_error_at = NULL
+         WHERE id = ?3",
+        rusqlite::params![indexed_hash, model_id, block_id],
+    )?;
+    Ok(())
+}
+
+/// Records a failed indexing attempt. The block is retried after a delay that
+/// doubles with each attempt, from `base_delay_secs` up to `max_delay_secs`, and
+/// is marked FAILED once it has failed `max_attempts` times.
+pub fn record_indexing_failure(
+    conn: &Connection,
+    block_id: &str,
+    error: &str,
+    max_attempts: i64,
+    base_delay_secs: i64,
+    max_delay_secs: i64,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE blocks
+         SET indexing_attempts = COALESCE(indexing_attempts, 0) + 1,
+             indexing_error = ?2,
+             last_error_at = datetime('now', 'localtime'),
+             indexing_status = CASE
+                 WHEN COALESCE(indexing_attempts, 0) + 1 >= ?3 THEN 2
+                 ELSE indexing_status
+             END,
+             next_attempt_at = datetime(
+                 'now', 'localtime',
+                 '+' || MIN(?4 << MIN(COALESCE(indexing_attempts, 0), 20), ?5) || ' seconds'
+             )
+         WHERE id = ?1",
+        rusqlite::params![block_id, error, max_attempts, base_delay_secs, max_delay_secs],
+    )?;
+    Ok(())
+}
+
+/// FAILED blocks with their document, most recently failed first.
+pub fn find_failed_blocks(conn: &Connection, limit: i64) -> Result<Vec<FailedBlock>> {
+    query_all(
+        conn,
+        "SELECT b.id, b.document_id, d.title, substr(b.content, 1, 160),
+                COALESCE(b.indexing_attempts, 0), b.indexing_error, b.updated_at
+         FROM blocks b
+         JOIN documents d ON d.id = b.document_id
+         WHERE b.indexing_status = 2
+         ORDER BY b.last_error_at DESC, b.seq DESC
+         LIMIT ?1",
+        [limit],
+        |row| {
+            Ok(FailedBlock {
+                block_id: row.get(0)?,
+                document_id: row.get(1)?,
+                document_title: row.get(2)?,
+                excerpt: row.get(3)?,
+                attempts: row.get(4)?,
+                last_error: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        },
+    )
+}
+
+pub fn count_indexing_stats(conn: &Connection, model_id: &str) -> Result<IndexingStats> {
+    conn.query_row(
+        "SELECT
+            COALESCE(SUM(CASE WHEN indexing_status = 0 THEN 1 ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN indexing_status = 1 AND stale = 0 THEN 1 ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN indexing_status = 2 THEN 1 ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN indexing_status = 1 AND stale = 1 THEN 1 ELSE 0 END), 0),
+            COUNT(*)
+         FROM (
+            SELECT
+                indexing_status,
+                (indexed_hash IS NOT content_hash OR embedding_model IS NOT ?1) AS stale
+            FROM blocks
+            WHERE source_block_id IS NULL
+         )",
+        [model_id],
+        |row| {
+            Ok(IndexingStats {
+                pending: row.get(0)?,
+                indexed: row.get(1)?,
+                failed: row.get(2)?,
+                stale: row.get(3)?,
+                total: row.get(4)?,
+            })
+        },
+    )
+}
+
 pub fn delete_block(conn: &Connection, block_id: &str) -> Result<()> {
     conn.execute("DELETE FROM blocks WHERE id = ?", [block_id])?;
     Ok(())
@@ -226,21 +623,79 @@ pub fn delete_blocks_by_document_id(conn: &Connection, document_id: &str) -> Res
 // Vector Repository
 // ============================================
 
-pub fn upsert_block_vector(conn: &Connection, block_id: &str, embedding: &[f32]) -> Result<()> {
-    // Convert f32 slice to bytes for sqlite-vec
-    let embedding_bytes: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+/// Excludes chunk `c` once its block has vectors of another model stored after it.
+/// Storing vectors only replaces those of the same model, so after a model change
+/// a block keeps its previous vectors, searched with the previous model, until it
+/// is re-embedded; `delete_superseded_vectors` drops them once every block is.
+pub const LIVE_CHUNK: &str = "NOT EXISTS (
+    SELECT 1 FROM block_chunks newer
+    WHERE newer.block_id = c.block_id AND newer.model_id != c.model_id AND newer.id > c.id
+)";
+
+/// Replaces the vectors `model_id` produced for a block with one vector per chunk.
+/// Vectors of other models are kept, see `LIVE_CHUNK`.
+pub fn replace_block_vectors(
+    conn: &Connection,
+    block_id: &str,
+    chunks: &[ChunkEmbedding],
+    model_id: &str,
+) -> Result<()> {
+    let layout = vector_layout(conn)?;
+    delete_block_model_vectors(conn, block_id, model_id)?;
+
+    for (chunk_index, chunk) in chunks.iter().enumerate() {
+        // Convert f32 slice to bytes for sqlite-vec
+        let embedding_bytes: Vec<u8> =
+            chunk.embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+
+        insert_block_chunk(
+            conn,
+            &layout,
+            block_id,
+            chunk_index as i64,
+            (chunk.start_offset as i64, chunk.end_offset as i64),
+            model_id,
+            &embedding_bytes,
+        )?;
+    }
+
+    Ok(())
+}
 
-    // Delete existing vector if any (using block_id as rowid reference)
+/// Inserts one chunk of a block and its vector, given as little-endian f32 bytes.
+/// The vector is stored as `layout` says, with a full-precision copy in
+/// `block_chunks` when quantized matches are re-scored.
+fn insert_block_chunk(
+    conn: &Connection,
+    layout: &VectorLayout,
+    block_id: &str,
+    chunk_index: i64,
+    (start_offset, end_offset): (i64, i64),
+    model_id: &str,
+    embedding_bytes: &[u8],
+) -> Result<()> {
     conn.execute(
-        "DELETE FROM vec_blocks WHERE rowid IN (SELECT rowid FROM blocks WHERE id = ?)",
-        rusqlite::params![block_id],
+        "INSERT INTO block_chunks
+            (block_id, chunk_index, start_offset, end_offset, model_id, dimension, embedding)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            block_id,
+            chunk_index,
+            start_offset,
+            end_offset,
+            model_id,
+            (embedding_bytes.len() / std::mem::size_of::<f32>()) as i64,
+            layout.keeps_full_vectors().then_some(embedding_bytes)
+        ],
     )?;
 
-    // Insert new vector using block's rowid
+    // vec_blocks rows are keyed by chunk id
     conn.execute(
-        "INSERT INTO vec_blocks (rowid, embedding)
-         SELECT rowid, ?2 FROM blocks WHERE id = ?1",
-        rusqlite::params![block_id, &embedding_bytes],
+        &format!(
+            "INSERT INTO vec_blocks (rowid, model_id, embedding) VALUES (?1, ?2, {})",
+            layout.vector_sql("?3")
+        ),
+        rusqlite::params![conn.last_insert_rowid(), model_id, embedding_bytes],
     )?;
 
     Ok(())
@@ -249,55 +704,363 @@ pub fn upsert_block_vector(conn: &Connection, block_id: &str, embedding: &[f32])
 pub fn find_similar_blocks(
     conn: &Connection,
     embedding: &[f32],
+    model_id: &str,
     threshold: f32,
     limit: i64,
 ) -> Result<Vec<(String, f32)>> {
     // Convert f32 slice to bytes for sqlite-vec
     let embedding_bytes: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+    let layout = vector_layout(conn)?;
 
     query_all(
         conn,
-        "SELECT b.id, v.distance
-         FROM vec_blocks v
-         JOIN blocks b ON b.rowid = v.rowid
-         WHERE v.embedding MATCH ?1
-           AND k = ?2
-           AND v.distance < ?3",
-        rusqlite::params![&embedding_bytes, limit, threshold],
-        |row| Ok((row.get(0)?, row.get(1)?)),
+        &format!(
+            "SELECT b.id, MIN(knn.distance) AS distance
+             FROM ({knn}) knn
+             JOIN block_chunks c ON c.id = knn.chunk_id
+             JOIN blocks b ON b.id = c.block_id
+             WHERE knn.distance < ?3
+               AND {live}
+             GROUP BY b.id",
+            knn = layout.knn_sql("?1", "?2", "?4"),
+            live = LIVE_CHUNK
+        ),
+        rusqlite::params![
+            &embedding_bytes,
+            limit,
+            layout.native_distance(threshold),
+            model_id
+        ],
+        |row| Ok((row.get(0)?, layout.to_l2(row.get(1)?))),
     )
 }
 
-/// Returns (block_id, document_id, distance) for similar blocks
+/// Returns (block_id, document_id, chunk_index, distance) for similar blocks, using
+/// the closest chunk of each block. Only vectors of `model_id` are compared.
 pub fn find_similar_blocks_with_document(
     conn: &Connection,
     embedding: &[f32],
+    model_id: &str,
     threshold: f32,
     limit: i64,
-) -> Result<Vec<(String, String, f32)>> {
+) -> Result<Vec<(String, String, i64, f32)>> {
     let embedding_bytes: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+    let layout = vector_layout(conn)?;
 
     query_all(
         conn,
-        "SELECT b.id, b.document_id, v.distance
-         FROM vec_blocks v
-         JOIN blocks b ON b.rowid = v.rowid
-         WHERE v.embedding MATCH ?1
-           AND k = ?2
-           AND v.distance < ?3",
-        rusqlite::params![&embedding_bytes, limit, threshold],
-        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        &format!(
+            "SELECT b.id, b.document_id, c.chunk_index, MIN(knn.distance) AS distance
+             FROM ({knn}) knn
+             JOIN block_chunks c ON c.id = knn.chunk_id
+             JOIN blocks b ON b.id = c.block_id
+             WHERE knn.distance < ?3
+               AND {live}
+             GROUP BY b.id",
+            knn = layout.knn_sql("?1", "?2", "?4"),
+            live = LIVE_CHUNK
+        ),
+        rusqlite::params![
+            &embedding_bytes,
+            limit,
+            layout.native_distance(threshold),
+            model_id
+        ],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, layout.to_l2(row.get(3)?))),
     )
 }
 
+/// Deletes every vector of a block, of any model.
 pub fn delete_block_vector(conn: &Connection, block_id: &str) -> Result<()> {
     conn.execute(
-        "DELETE FROM vec_blocks WHERE rowid IN (SELECT rowid FROM blocks WHERE id = ?)",
+        "DELETE FROM vec_blocks WHERE rowid IN (SELECT id FROM block_chunks WHERE block_id = ?)",
+        rusqlite::params![block_id],
+    )?;
+    conn.execute(
+        "DELETE FROM block_chunks WHERE block_id = ?",
         rusqlite::params![block_id],
     )?;
     Ok(())
 }
 
+/// Drops the vectors and block similarities of a block that became an embed or
+/// quote, and returns whether it had vectors.
+pub fn delete_reference_copy_vectors(conn: &Connection, block_id: &str) -> Result<bool> {
+    let removed = conn.execute(
+        "DELETE FROM vec_blocks WHERE rowid IN (SELECT id FROM block_chunks WHERE block_id = ?)",
+        [block_id],
+    )?;
+    conn.execute("DELETE FROM block_chunks WHERE block_id = ?", [block_id])?;
+    conn.execute(
+        "DELETE FROM block_similarities WHERE block_id = ?1 OR similar_block_id = ?1",
+        [block_id],
+    )?;
+    Ok(removed > 0)
+}
+
+fn delete_block_model_vectors(conn: &Connection, block_id: &str, model_id: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM vec_blocks
+         WHERE rowid IN (SELECT id FROM block_chunks WHERE block_id = ?1 AND model_id = ?2)",
+        rusqlite::params![block_id, model_id],
+    )?;
+    conn.execute(
+        "DELETE FROM block_chunks WHERE block_id = ?1 AND model_id = ?2",
+        rusqlite::params![block_id, model_id],
+    )?;
+    Ok(())
+}
+
+/// Deletes the vectors blocks were re-embedded over with another model, see
+/// `LIVE_CHUNK`. Returns how many chunks were dropped.
+pub fn delete_superseded_vectors(conn: &Connection) -> Result<usize> {
+    let superseded = format!("SELECT c.id FROM block_chunks c WHERE NOT {}", LIVE_CHUNK);
+    conn.execute(
+        &format!("DELETE FROM vec_blocks WHERE rowid IN ({})", superseded),
+        [],
+    )?;
+    conn.execute(&format!("DELETE FROM block_chunks WHERE id IN ({})", superseded), [])
+}
+
+/// (model_id, length, stored vector, full-precision copy) of a chunk.
+type ChunkVectorRow = (String, i64, Vec<u8>, Option<Vec<u8>>);
+
+/// Recomputes the vectors of a document, one per model it has chunks of, as the
+/// mean of those chunk vectors, each weighted by its length, so a one-block note
+/// and a long one are compared on the same footing. A model the document has no
+/// chunks of anymore loses its vector.
+pub fn refresh_document_vector(conn: &Connection, document_id: &str) -> Result<()> {
+    let layout = vector_layout(conn)?;
+    let chunks: Vec<ChunkVectorRow> = query_all(
+        conn,
+        &format!(
+            "SELECT c.model_id, MAX(c.end_offset - c.start_offset, 1), v.embedding, c.embedding
+             FROM block_chunks c
+             JOIN vec_blocks v ON v.rowid = c.id
+             JOIN blocks b ON b.id = c.block_id
+             WHERE b.document_id = ?1 AND {}",
+            LIVE_CHUNK
+        ),
+        [document_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )?;
+
+    delete_document_vector(conn, document_id)?;
+
+    let mut means: std::collections::BTreeMap<&str, Vec<f32>> = std::collections::BTreeMap::new();
+    for (model_id, length, stored, full) in &chunks {
+        let vector = match full {
+            Some(full) => decode_vector(full, VectorStorage::Float, layout.dimension),
+            None => decode_vector(stored, layout.storage, layout.dimension),
+        };
+        let mean = means
+            .entry(model_id.as_str())
+            .or_insert_with(|| vec![0.0f32; layout.dimension]);
+        for (sum, value) in mean.iter_mut().zip(vector) {
+            *sum += value * *length as f32;
+        }
+    }
+
+    for (model_id, mean) in means {
+        let norm = mean.iter().map(|value| value * value).sum::<f32>().sqrt();
+        if norm == 0.0 {
+            continue;
+        }
+        let embedding_bytes: Vec<u8> = mean
+            .iter()
+            .flat_map(|value| (value / norm).to_le_bytes())
+            .collect();
+
+        conn.execute(
+            "INSERT INTO document_vectors (document_id, model_id) VALUES (?1, ?2)",
+            [document_id, model_id],
+        )?;
+        conn.execute(
+            "INSERT INTO vec_documents (rowid, model_id, embedding) VALUES (?1, ?2, ?3)",
+            rusqlite::params![conn.last_insert_rowid(), model_id, embedding_bytes],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Reads a vector of `vec_blocks` back as floats. Quantized vectors only keep
+/// their direction, which is all a mean of normalized vectors needs.
+fn decode_vector(bytes: &[u8], storage: VectorStorage, dimension: usize) -> Vec<f32> {
+    match storage {
+        VectorStorage::Float => bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+        VectorStorage::Int8 => bytes
+            .iter()
+            .map(|&b| (b as i8 as f32 + 0.5) / 127.5)
+            .collect(),
+        // Element i is bit i % 8 of byte i / 8
+        VectorStorage::Binary => (0..dimension)
+            .map(|i| match bytes.get(i / 8) {
+                Some(byte) if (byte >> (i % 8)) & 1 == 1 => 1.0,
+                _ => -1.0,
+            })
+            .collect(),
+    }
+}
+
+/// Deletes the vectors of a document, of every model.
+pub fn delete_document_vector(conn: &Connection, document_id: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM vec_documents
+         WHERE rowid IN (SELECT id FROM document_vectors WHERE document_id = ?1)",
+        [document_id],
+    )?;
+    conn.execute(
+        "DELETE FROM document_vectors WHERE document_id = ?1",
+        [document_id],
+    )?;
+    Ok(())
+}
+
+/// Documents that have chunks of `model_id` but no document vector of it yet,
+/// such as those indexed before document vectors existed or under another model.
+pub fn find_documents_without_vector(
+    conn: &Connection,
+    model_id: &str,
+    limit: i64,
+) -> Result<Vec<String>> {
+    query_all(
+        conn,
+        &format!(
+            "SELECT DISTINCT b.document_id
+             FROM block_chunks c
+             JOIN blocks b ON b.id = c.block_id
+             WHERE c.model_id = ?1
+               AND {}
+               AND b.document_id NOT IN (
+                   SELECT document_id FROM document_vectors WHERE model_id = ?1
+               )
+             LIMIT ?2",
+            LIVE_CHUNK
+        ),
+        rusqlite::params![model_id, limit],
+        |row| row.get(0),
+    )
+}
+
+/// Picks the vector of `document_id` that comparisons use: the one of `model_id`,
+/// or of the model it was stored with before a model change, which it is compared
+/// within until its blocks are re-embedded.
+const OWN_DOCUMENT_VECTOR: &str = "own AS (
+    SELECT v.embedding, dv.model_id
+    FROM document_vectors dv
+    JOIN vec_documents v ON v.rowid = dv.id
+    WHERE dv.document_id = ?1
+    ORDER BY dv.model_id = ?2 DESC
+    LIMIT 1
+)";
+
+/// Documents whose vectors are nearest to the vector of `document_id`, closest
+/// first. Similarity is reported as 1 - distance, like block similarity.
+pub fn find_similar_documents(
+    conn: &Connection,
+    document_id: &str,
+    model_id: &str,
+    limit: i64,
+) -> Result<Vec<RelatedDocument>> {
+    // The document itself is its own nearest neighbour, hence the extra one
+    query_all(
+        conn,
+        &format!(
+            "WITH {own},
+         similar AS (
+            SELECT rowid AS id, distance
+            FROM vec_documents
+            WHERE embedding MATCH (SELECT embedding FROM own)
+              AND k = ?3 + 1
+              AND model_id = (SELECT model_id FROM own)
+         )
+         SELECT d.id, d.title, d.tags, d.status, s.distance
+         FROM similar s
+         JOIN document_vectors dv ON dv.id = s.id
+         JOIN documents d ON d.id = dv.document_id
+         WHERE d.id != ?1
+         ORDER BY s.distance
+         LIMIT ?3",
+            own = OWN_DOCUMENT_VECTOR
+        ),
+        rusqlite::params![document_id, model_id, limit],
+        |row| {
+            let tags_str: Option<String> = row.get(2)?;
+            let tags = tags_str.filter(|s| !s.is_empty()).map(|s| {
+                s.split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            });
+            let distance: f32 = row.get(4)?;
+
+            Ok(RelatedDocument {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                tags,
+                status: row.get(3)?,
+                similarity_score: 1.0 - distance,
+            })
+        },
+    )
+}
+
+/// Returns (document_id, distance) between the vector of `document_id` and the
+/// vectors of the given documents, for those that have one of the same model.
+pub fn find_document_vector_distances(
+    conn: &Connection,
+    document_id: &str,
+    model_id: &str,
+    document_ids: &[String],
+) -> Result<Vec<(String, f32)>> {
+    let ids_json = serde_json::to_string(document_ids).unwrap_or_else(|_| "[]".to_string());
+
+    query_all(
+        conn,
+        &format!(
+            "WITH {own}
+             SELECT dv.document_id, vec_distance_l2(own.embedding, other.embedding)
+             FROM own
+             JOIN document_vectors dv
+               ON dv.document_id IN (SELECT value FROM json_each(?3))
+              AND dv.model_id = own.model_id
+             JOIN vec_documents other ON other.rowid = dv.id",
+            own = OWN_DOCUMENT_VECTOR
+        ),
+        rusqlite::params![document_id, model_id, &ids_json],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+}
+
+/// The given documents that still have block vectors of a model other than
+/// `model_id`, i.e. blocks not re-embedded since the model changed.
+pub fn find_documents_with_previous_vectors(
+    conn: &Connection,
+    model_id: &str,
+    document_ids: &[String],
+) -> Result<Vec<String>> {
+    let ids_json = serde_json::to_string(document_ids).unwrap_or_else(|_| "[]".to_string());
+
+    query_all(
+        conn,
+        &format!(
+            "SELECT DISTINCT b.document_id
+             FROM block_chunks c
+             JOIN blocks b ON b.id = c.block_id
+             WHERE c.model_id != ?1
+               AND {}
+               AND b.document_id IN (SELECT value FROM json_each(?2))",
+            LIVE_CHUNK
+        ),
+        rusqlite::params![model_id, &ids_json],
+        |row| row.get(0),
+    )
+}
+
 // ============================================
 // Edge Repository
 // ============================================
@@ -306,14 +1069,13 @@ pub fn upsert_edge(
     conn: &Connection,
     source_id: &str,
     target_id: &str,
-    relation_type: Option<&str>,
+    relation_type: &str,
     weight: f64,
 ) -> Result<()> {
     conn.execute(
         "INSERT INTO edges (source_id, target_id, relation_type, weight)
          VALUES (?1, ?2, ?3, ?4)
-         ON CONFLICT(source_id, target_id) DO UPDATE SET
-             relation_type = excluded.relation_type,
+         ON CONFLICT(source_id, target_id, relation_type) DO UPDATE SET
              weight = excluded.weight",
         (source_id, target_id, relation_type, weight),
     )?;
@@ -341,19 +1103,21 @@ pub fn find_edges_by_source_id(conn: &Connection, source_id: &str) -> Result<Vec
 
 /// Find related documents in both directions (bidirectional)
 /// Returns (document_id, max_weight) for documents connected either as source or target
+/// by an edge of `relation_type`
 pub fn find_related_documents_bidirectional(
     conn: &Connection,
     document_id: &str,
+    relation_type: &str,
 ) -> Result<Vec<(String, f64)>> {
     query_all(
         conn,
         "SELECT DISTINCT doc_id, weight FROM (
-            SELECT target_id as doc_id, weight FROM edges WHERE source_id = ?
+            SELECT target_id as doc_id, weight FROM edges WHERE source_id = ?1 AND relation_type = ?2
             UNION
-            SELECT source_id as doc_id, weight FROM edges WHERE target_id = ?
+            SELECT source_id as doc_id, weight FROM edges WHERE target_id = ?1 AND relation_type = ?2
         )
         ORDER BY weight DESC",
-        rusqlite::params![document_id, document_id],
+        rusqlite::params![document_id, relation_type],
         |row| Ok((row.get(0)?, row.get(1)?)),
     )
 }
@@ -366,38 +1130,820 @@ pub fn delete_edge(conn: &Connection, source_id: &str, target_id: &str) -> Resul
     Ok(())
 }
 
-/// Delete edge in both directions if it exists
+/// Delete edge of `relation_type` in both directions if it exists
 pub fn delete_edge_bidirectional(
     conn: &Connection,
     source_id: &str,
     target_id: &str,
+    relation_type: &str,
 ) -> Result<()> {
     conn.execute(
-        "DELETE FROM edges WHERE (source_id = ? AND target_id = ?) OR (source_id = ? AND target_id = ?)",
-        rusqlite::params![source_id, target_id, target_id, source_id],
+        "DELETE FROM edges
+         WHERE ((source_id = ?1 AND target_id = ?2) OR (source_id = ?2 AND target_id = ?1))
+           AND relation_type = ?3",
+        rusqlite::params![source_id, target_id, relation_type],
     )?;
     Ok(())
 }
 
-// ============================================
-// Reindexing Repository
-// ============================================
-
-pub fn reset_all_indexing_status(conn: &Connection) -> Result<u64> {
-    let count = conn.execute("UPDATE blocks SET indexing_status = 0", [])?;
-    Ok(count as u64)
-}
-
-pub fn delete_all_edges(conn: &Connection) -> Result<u64> {
-    let count = conn.execute("DELETE FROM edges", [])?;
-    Ok(count as u64)
+/// Pins or unpins the similar edge between two documents, whichever its direction,
+/// and returns how many edges changed.
+pub fn set_edge_pinned(conn: &Connection, source_id: &str, target_id: &str, pinned: bool) -> Result<usize> {
+    conn.execute(
+        "UPDATE edges SET pinned = ?3
+         WHERE ((source_id = ?1 AND target_id = ?2) OR (source_id = ?2 AND target_id = ?1))
+           AND relation_type = 'similar'",
+        rusqlite::params![source_id, target_id, pinned],
+    )
 }
 
-pub fn delete_all_vectors(conn: &Connection) -> Result<u64> {
-    let count = conn.execute("DELETE FROM vec_blocks", [])?;
+/// Sets the label and note of an edge and returns how many edges changed.
+pub fn annotate_edge(
+    conn: &Connection,
+    source_id: &str,
+    target_id: &str,
+    relation_type: &str,
+    label: Option<&str>,
+    note: Option<&str>,
+) -> Result<usize> {
+    conn.execute(
+        "UPDATE edges SET label = ?4, note = ?5
+         WHERE source_id = ?1 AND target_id = ?2 AND relation_type = ?3",
+        rusqlite::params![source_id, target_id, relation_type, label, note],
+    )
+}
+
+/// Documents joined to `document_id` by a similar edge someone curated, pinned or
+/// given a label or note, in either direction.
+pub fn find_curated_documents(conn: &Connection, document_id: &str) -> Result<Vec<String>> {
+    query_all(
+        conn,
+        "SELECT target_id FROM edges
+         WHERE source_id = ?1 AND relation_type = 'similar'
+           AND (pinned = 1 OR label IS NOT NULL OR note IS NOT NULL)
+         UNION
+         SELECT source_id FROM edges
+         WHERE target_id = ?1 AND relation_type = 'similar'
+           AND (pinned = 1 OR label IS NOT NULL OR note IS NOT NULL)",
+        [document_id],
+        |row| row.get(0),
+    )
+}
+
+/// Weight and pinned flag of the similar edge between two documents, whichever its direction.
+pub fn find_similar_edge(conn: &Connection, source_id: &str, target_id: &str) -> Result<Option<(f64, bool)>> {
+    query_one(
+        conn,
+        "SELECT weight, pinned FROM edges
+         WHERE ((source_id = ?1 AND target_id = ?2) OR (source_id = ?2 AND target_id = ?1))
+           AND relation_type = 'similar'
+         ORDER BY pinned DESC, weight DESC
+         LIMIT 1",
+        [source_id, target_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+}
+
+/// Documents, other than its own, holding a block that `block_id` was recorded as
+/// similar to, from either side.
+pub fn find_block_similarity_documents(conn: &Connection, block_id: &str) -> Result<Vec<String>> {
+    query_all(
+        conn,
+        "SELECT b.document_id FROM block_similarities s
+         JOIN blocks b ON b.id = s.similar_block_id
+         WHERE s.block_id = ?1
+         UNION
+         SELECT b.document_id FROM block_similarities s
+         JOIN blocks b ON b.id = s.block_id
+         WHERE s.similar_block_id = ?1",
+        [block_id],
+        |row| row.get(0),
+    )
+}
+
+/// Deletes the similar edges between `document_id` and each of `other_ids` that no
+/// recorded block pair supports anymore, unless someone curated them, and returns
+/// the documents whose edge was removed.
+pub fn delete_unsupported_similar_edges(
+    conn: &Connection,
+    document_id: &str,
+    other_ids: &[String],
+) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "DELETE FROM edges
+         WHERE ((source_id = ?1 AND target_id = ?2) OR (source_id = ?2 AND target_id = ?1))
+           AND relation_type = 'similar'
+           AND pinned = 0 AND label IS NULL AND note IS NULL
+           AND NOT EXISTS (
+               SELECT 1 FROM block_similarities s
+               JOIN blocks a ON a.id = s.block_id
+               JOIN blocks b ON b.id = s.similar_block_id
+               WHERE (a.document_id = ?1 AND b.document_id = ?2)
+                  OR (a.document_id = ?2 AND b.document_id = ?1)
+           )",
+    )?;
+
+    let mut removed = Vec::new();
+    for other_id in other_ids {
+        if stmt.execute([document_id, other_id.as_str()])? > 0 {
+            removed.push(other_id.clone());
+        }
+    }
+    Ok(removed)
+}
+
+// ============================================
+// Blocked Edge Repository
+// ============================================
+
+/// A pair is stored once, with the smaller id first.
+fn ordered_pair<'a>(a: &'a str, b: &'a str) -> (&'a str, &'a str) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+pub fn insert_blocked_edge(conn: &Connection, source_id: &str, target_id: &str) -> Result<()> {
+    let (first, second) = ordered_pair(source_id, target_id);
+    conn.execute(
+        "INSERT OR IGNORE INTO blocked_edges (source_id, target_id) VALUES (?1, ?2)",
+        [first, second],
+    )?;
+    Ok(())
+}
+
+pub fn delete_blocked_edge(conn: &Connection, source_id: &str, target_id: &str) -> Result<()> {
+    let (first, second) = ordered_pair(source_id, target_id);
+    conn.execute(
+        "DELETE FROM blocked_edges WHERE source_id = ?1 AND target_id = ?2",
+        [first, second],
+    )?;
+    Ok(())
+}
+
+/// Documents the scheduler must not link to `document_id`.
+pub fn find_blocked_documents(conn: &Connection, document_id: &str) -> Result<Vec<String>> {
+    query_all(
+        conn,
+        "SELECT target_id FROM blocked_edges WHERE source_id = ?1
+         UNION
+         SELECT source_id FROM blocked_edges WHERE target_id = ?1",
+        [document_id],
+        |row| row.get(0),
+    )
+}
+
+// ============================================
+// Edge Evidence Repository
+// ============================================
+
+/// A block of another document an indexed block was found similar to, as
+/// (similar block id, similarity, indexed block's chunk, similar block's chunk),
+/// the chunks being the pair that matched best.
+pub type BlockSimilarity = (String, f32, i64, i64);
+
+/// Records the blocks of other documents `block_id` was found similar to, replacing
+/// what was recorded for it before, including pairs recorded from the other side,
+/// since its content changed.
+pub fn replace_block_similarities(conn: &Connection, block_id: &str, similar: &[BlockSimilarity]) -> Result<()> {
+    conn.execute(
+        "DELETE FROM block_similarities WHERE block_id = ?1 OR similar_block_id = ?1",
+        [block_id],
+    )?;
+
+    let mut stmt = conn.prepare(
+        "INSERT OR REPLACE INTO block_similarities
+            (block_id, similar_block_id, similarity, chunk_index, similar_chunk_index)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    for (similar_block_id, similarity, chunk_index, similar_chunk_index) in similar {
+        stmt.execute(rusqlite::params![
+            block_id,
+            similar_block_id,
+            similarity,
+            chunk_index,
+            similar_chunk_index
+        ])?;
+    }
+    Ok(())
+}
+
+/// The most similar block pairs between two documents, recorded from either side,
+/// with the text of the chunks that matched (the opening text of each block for
+/// pairs recorded without their chunks).
+pub fn find_edge_evidence(
+    conn: &Connection,
+    source_id: &str,
+    target_id: &str,
+    limit: i64,
+) -> Result<Vec<BlockPairEvidence>> {
+    query_all(
+        conn,
+        "WITH pairs AS (
+            SELECT s.block_id AS source_block_id, s.similar_block_id AS target_block_id,
+                   s.chunk_index AS source_chunk_index, s.similar_chunk_index AS target_chunk_index,
+                   s.similarity
+            FROM block_similarities s
+            JOIN blocks a ON a.id = s.block_id
+            JOIN blocks b ON b.id = s.similar_block_id
+            WHERE a.document_id = ?1 AND b.document_id = ?2
+            UNION ALL
+            SELECT s.similar_block_id, s.block_id, s.similar_chunk_index, s.chunk_index, s.similarity
+            FROM block_similarities s
+            JOIN blocks a ON a.id = s.similar_block_id
+            JOIN blocks b ON b.id = s.block_id
+            WHERE a.document_id = ?1 AND b.document_id = ?2
+        ),
+        best AS (
+            SELECT source_block_id, target_block_id, source_chunk_index, target_chunk_index,
+                   MAX(similarity) AS similarity
+            FROM pairs
+            GROUP BY source_block_id, target_block_id
+        )
+        SELECT p.source_block_id, COALESCE(
+                   (SELECT substr(a.content, c.start_offset + 1, c.end_offset - c.start_offset)
+                    FROM block_chunks c
+                    WHERE c.block_id = a.id AND c.chunk_index = p.source_chunk_index
+                    ORDER BY c.id DESC LIMIT 1),
+                   substr(a.content, 1, 160)),
+               p.target_block_id, COALESCE(
+                   (SELECT substr(b.content, c.start_offset + 1, c.end_offset - c.start_offset)
+                    FROM block_chunks c
+                    WHERE c.block_id = b.id AND c.chunk_index = p.target_chunk_index
+                    ORDER BY c.id DESC LIMIT 1),
+                   substr(b.content, 1, 160)),
+               p.similarity
+        FROM best p
+        JOIN blocks a ON a.id = p.source_block_id
+        JOIN blocks b ON b.id = p.target_block_id
+        ORDER BY p.similarity DESC
+        LIMIT ?3",
+        rusqlite::params![source_id, target_id, limit],
+        |row| {
+            Ok(BlockPairEvidence {
+                source_block_id: row.get(0)?,
+                source_snippet: row.get(1)?,
+                target_block_id: row.get(2)?,
+                target_snippet: row.get(3)?,
+                similarity: row.get(4)?,
+            })
+        },
+    )
+}
+
+/// (block id, chunk text, stored vector, full-precision copy) of a chunk.
+type ChunkTextRow = (String, Option<String>, Vec<u8>, Option<Vec<u8>>);
+/// (block id, chunk text, normalized vector) of a chunk.
+type ChunkText = (String, Option<String>, Vec<f32>);
+
+/// The closest chunk pairs between two documents' `model_id` vectors, best pair per
+/// block pair, for edges the scheduler drew from the document vectors rather than
+/// from similar blocks. Quantized vectors are compared by their direction.
+pub fn find_closest_chunk_pairs(
+    conn: &Connection,
+    source_id: &str,
+    target_id: &str,
+    model_id: &str,
+    limit: i64,
+) -> Result<Vec<BlockPairEvidence>> {
+    let layout = vector_layout(conn)?;
+    let chunks = |document_id: &str| -> Result<Vec<ChunkText>> {
+        let rows: Vec<ChunkTextRow> = query_all(
+            conn,
+            &format!(
+                "SELECT b.id, substr(b.content, c.start_offset + 1, c.end_offset - c.start_offset),
+                        v.embedding, c.embedding
+                 FROM block_chunks c
+                 JOIN vec_blocks v ON v.rowid = c.id
+                 JOIN blocks b ON b.id = c.block_id
+                 WHERE b.document_id = ?1 AND c.model_id = ?2 AND {}",
+                LIVE_CHUNK
+            ),
+            [document_id, model_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+        Ok(rows
+            .into_iter()
+            .map(|(block_id, text, stored, full)| {
+                let mut vector = match full {
+                    Some(full) => decode_vector(&full, VectorStorage::Float, layout.dimension),
+                    None => decode_vector(&stored, layout.storage, layout.dimension),
+                };
+                let norm = vector.iter().map(|value| value * value).sum::<f32>().sqrt();
+                if norm > 0.0 {
+                    vector.iter_mut().for_each(|value| *value /= norm);
+                }
+                (block_id, text, vector)
+            })
+            .collect())
+    };
+    let source_chunks = chunks(source_id)?;
+    let target_chunks = chunks(target_id)?;
+
+    let mut pairs: Vec<BlockPairEvidence> = Vec::new();
+    for (source_block_id, source_text, source_vector) in &source_chunks {
+        for (target_block_id, target_text, target_vector) in &target_chunks {
+            let distance = source_vector
+                .iter()
+                .zip(target_vector)
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum::<f32>()
+                .sqrt();
+            let similarity = (1.0 - distance).clamp(0.0, 1.0);
+
+            let existing = pairs.iter_mut().find(|pair| {
+                pair.source_block_id == *source_block_id && pair.target_block_id == *target_block_id
+            });
+            match existing {
+                Some(pair) if pair.similarity >= similarity => {}
+                Some(pair) => {
+                    pair.source_snippet = source_text.clone();
+                    pair.target_snippet = target_text.clone();
+                    pair.similarity = similarity;
+                }
+                None => pairs.push(BlockPairEvidence {
+                    source_block_id: source_block_id.clone(),
+                    source_snippet: source_text.clone(),
+                    target_block_id: target_block_id.clone(),
+                    target_snippet: target_text.clone(),
+                    similarity,
+                }),
+            }
+        }
+    }
+
+    pairs.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    pairs.truncate(limit.max(0) as usize);
+    Ok(pairs)
+}
+
+// ============================================
+// Link Repository
+// ============================================
+
+/// The document a `[[...]]` reference points to: the document with that id, or
+/// else the most recently updated one with that title (ignoring ASCII case).
+/// Archived documents can't be linked to.
+pub fn resolve_link_target(conn: &Connection, reference: &str) -> Result<Option<String>> {
+    query_one(
+        conn,
+        "SELECT id FROM documents
+         WHERE (id = ?1 OR title = ?1 COLLATE NOCASE) AND status != 99
+         ORDER BY id = ?1 DESC, updated_at DESC
+         LIMIT 1",
+        [reference],
+        |row| row.get(0),
+    )
+}
+
+pub fn find_link_targets(conn: &Connection, source_id: &str) -> Result<Vec<String>> {
+    query_all(
+        conn,
+        "SELECT target_id FROM edges WHERE source_id = ? AND relation_type = 'link'",
+        [source_id],
+        |row| row.get(0),
+    )
+}
+
+pub fn delete_links_by_source(conn: &Connection, source_id: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM edges WHERE source_id = ? AND relation_type = 'link'",
+        [source_id],
+    )?;
+    Ok(())
+}
+
+/// Link targets (added, removed) of a document after its links were re-resolved.
+pub type LinkChanges = (Vec<String>, Vec<String>);
+
+/// Replaces the `link` edges from a document with the documents its blocks reference,
+/// and returns the targets (added, removed). References that resolve to nothing, or
+/// to the document itself, make no edge.
+pub fn sync_document_links(conn: &Connection, document_id: &str) -> Result<LinkChanges> {
+    let mut targets: Vec<String> = Vec::new();
+    for block in find_blocks_by_document_id(conn, document_id)? {
+        for reference in find_references(block.content.as_deref().unwrap_or("")) {
+            if let Some(target) = resolve_link_target(conn, reference.target)? {
+                if target != document_id && !targets.contains(&target) {
+                    targets.push(target);
+                }
+            }
+        }
+    }
+
+    let previous = find_link_targets(conn, document_id)?;
+    delete_links_by_source(conn, document_id)?;
+    for target in &targets {
+        upsert_edge(conn, document_id, target, "link", 1.0)?;
+    }
+
+    let added = targets.iter().filter(|t| !previous.contains(t)).cloned().collect();
+    let removed = previous.into_iter().filter(|t| !targets.contains(t)).collect();
+    Ok((added, removed))
+}
+
+/// Title and status of a document, which decide the references that resolve to it.
+pub fn find_link_identity(conn: &Connection, document_id: &str) -> Result<Option<(Option<String>, i16)>> {
+    query_one(
+        conn,
+        "SELECT title, status FROM documents WHERE id = ?",
+        [document_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+}
+
+/// Re-resolves the links of every document that may reference `document_id` by one
+/// of `names` (its id, or a title it has or had), or that links to it now, after it
+/// was created, renamed or (un)archived. Returns each document whose links changed
+/// with its targets (added, removed).
+pub fn relink_references_to(
+    conn: &Connection,
+    document_id: &str,
+    names: &[String],
+) -> Result<Vec<(String, LinkChanges)>> {
+    let names_json = serde_json::to_string(names).unwrap_or_else(|_| "[]".to_string());
+    let sources: Vec<String> = query_all(
+        conn,
+        "SELECT source_id FROM edges WHERE target_id = ?1 AND relation_type = 'link'
+         UNION
+         SELECT DISTINCT b.document_id FROM blocks b
+         WHERE b.content LIKE '%[[%'
+           AND EXISTS (
+               SELECT 1 FROM json_each(?2) name
+               WHERE name.value != '' AND instr(LOWER(b.content), LOWER(name.value)) > 0
+           )",
+        rusqlite::params![document_id, names_json],
+        |row| row.get(0),
+    )?;
+
+    let mut changed = Vec::new();
+    for source_id in sources.into_iter().filter(|id| id != document_id) {
+        let changes = sync_document_links(conn, &source_id)?;
+        if !changes.0.is_empty() || !changes.1.is_empty() {
+            changed.push((source_id, changes));
+        }
+    }
+    Ok(changed)
+}
+
+/// (document without blocks, block id, order index, content) of a block that may
+/// hold a link; the block is missing when none of the document's blocks has one.
+pub type LinkSourceBlockRow = (Backlink, Option<String>, Option<f64>, Option<String>);
+
+/// Blocks of the documents that link to `document_id`, archived documents excepted,
+/// ordered by document then block. A linking document is listed even when none of
+/// its blocks holds a reference any more.
+pub fn find_link_source_blocks(conn: &Connection, document_id: &str) -> Result<Vec<LinkSourceBlockRow>> {
+    query_all(
+        conn,
+        "SELECT d.id, d.title, d.tags, d.status, b.id, b.order_index, b.content
+         FROM edges e
+         JOIN documents d ON d.id = e.source_id
+         LEFT JOIN blocks b ON b.document_id = d.id AND b.content LIKE '%[[%'
+         WHERE e.target_id = ?1
+           AND e.relation_type = 'link'
+           AND d.status != 99
+         ORDER BY d.updated_at DESC, d.id, b.order_index",
+        [document_id],
+        |row| {
+            let tags_str: Option<String> = row.get(2)?;
+            let tags = tags_str.filter(|s| !s.is_empty()).map(|s| {
+                s.split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            });
+
+            let document = Backlink {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                tags,
+                status: row.get(3)?,
+                blocks: Vec::new(),
+            };
+
+            Ok((document, row.get(4)?, row.get(5)?, row.get(6)?))
+        },
+    )
+}
+
+// ============================================
+// Reindexing Repository
+// ============================================
+
+/// Queues FAILED blocks for indexing again with a fresh attempt count: all of
+/// them, or only those in `block_ids`.
+pub fn reset_failed_indexing_status(conn: &Connection, block_ids: Option<&[String]>) -> Result<u64> {
+    let ids_json = block_ids.map(|ids| serde_json::to_string(ids).unwrap_or_else(|_| "[]".to_string()));
+
+    let count = conn.execute(
+        "UPDATE blocks
+         SET indexing_status = 0, indexing_attempts = 0, indexing_error = NULL, next_attempt_at = NULL,
+             last_error_at = NULL
+         WHERE indexing_status = 2
+           AND (?1 IS NULL OR id IN (SELECT value FROM json_each(?1)))",
+        [ids_json],
+    )?;
     Ok(count as u64)
 }
 
+/// Condition on blocks `b` and their document `d` selecting the blocks in `scope`,
+/// with its single parameter.
+fn reindex_scope_filter(scope: &ReindexScope) -> (&'static str, Option<String>) {
+    match scope {
+        ReindexScope::All => ("?1 IS NULL", None),
+        ReindexScope::Document { document_id } => ("b.document_id = ?1", Some(document_id.clone())),
+        ReindexScope::Tag { tag } => (
+            "instr(',' || LOWER(REPLACE(COALESCE(d.tags, ''), ', ', ',')) || ',', ?1) > 0",
+            Some(format!(",{},", tag.trim().to_lowercase())),
+        ),
+    }
+}
+
+/// Drops the reindex job, its queue and the vectors it built so far.
+pub fn delete_reindex_job(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "DELETE FROM shadow_block_chunks;
+         DELETE FROM reindex_queue;
+         DELETE FROM reindex_job;",
+    )
+}
+
+/// Creates the reindex job and queues every block in `scope` for it. Returns
+/// the number of blocks queued.
+pub fn insert_reindex_job(conn: &Connection, scope: &ReindexScope, model_id: &str) -> Result<i64> {
+    let (condition, value) = reindex_scope_filter(scope);
+    let total = conn.execute(
+        &format!(
+            "INSERT INTO reindex_queue (block_id)
+             SELECT b.id
+             FROM blocks b
+             JOIN documents d ON d.id = b.document_id
+             WHERE b.source_block_id IS NULL AND {}",
+            condition
+        ),
+        [&value],
+    )?;
+
+    let (scope_kind, scope_value) = match scope {
+        ReindexScope::All => ("all", None),
+        ReindexScope::Document { document_id } => ("document", Some(document_id.as_str())),
+        ReindexScope::Tag { tag } => ("tag", Some(tag.as_str())),
+    };
+    conn.execute(
+        "INSERT INTO reindex_job (id, scope_kind, scope_value, model_id, total)
+         VALUES (1, ?1, ?2, ?3, ?4)",
+        rusqlite::params![scope_kind, scope_value, model_id, total as i64],
+    )?;
+
+    Ok(total as i64)
+}
+
+/// Model the reindex job embeds with, or `None` if there is no job.
+pub fn find_reindex_job_model(conn: &Connection) -> Result<Option<String>> {
+    query_one(
+        conn,
+        "SELECT model_id FROM reindex_job WHERE id = 1",
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// Progress of the reindex job; it is paused while `model_id` isn't the model it embeds with.
+pub fn find_reindex_progress(conn: &Connection, model_id: &str) -> Result<Option<ReindexProgress>> {
+    query_one(
+        conn,
+        "SELECT scope_kind, scope_value, total,
+                (SELECT COUNT(*) FROM reindex_queue WHERE status IN (1, 3, 4)),
+                (SELECT COUNT(*) FROM reindex_queue WHERE status = 2),
+                started_at, model_id != ?1
+         FROM reindex_job
+         WHERE id = 1",
+        [model_id],
+        |row| {
+            let scope_kind: String = row.get(0)?;
+            let scope = match (scope_kind.as_str(), row.get::<_, Option<String>>(1)?) {
+                ("document", Some(document_id)) => ReindexScope::Document { document_id },
+                ("tag", Some(tag)) => ReindexScope::Tag { tag },
+                _ => ReindexScope::All,
+            };
+            Ok(ReindexProgress {
+                scope,
+                total: row.get(2)?,
+                embedded: row.get(3)?,
+                failed: row.get(4)?,
+                started_at: row.get(5)?,
+                paused: row.get(6)?,
+            })
+        },
+    )
+}
+
+/// Blocks of the reindex job in queue `status`, none while the job is paused
+/// because `model_id` isn't the model it embeds with.
+fn find_reindex_blocks(conn: &Connection, status: i64, model_id: &str, limit: i64) -> Result<Vec<Block>> {
+    query_all(
+        conn,
+        "SELECT b.id, b.document_id, b.content, b.order_index,
+                b.source_document_id, b.indexing_status, b.created_at, b.updated_at,
+                b.source_block_id, b.reference_kind
+         FROM reindex_queue q
+         JOIN blocks b ON b.id = q.block_id
+         JOIN reindex_job j ON j.id = 1
+         WHERE q.status = ?1 AND j.model_id = ?2
+         LIMIT ?3",
+        rusqlite::params![status, model_id, limit],
+        |row| {
+            Ok(Block {
+                id: row.get(0)?,
+                document_id: row.get(1)?,
+                content: row.get(2)?,
+                order_index: row.get(3)?,
+                source_document_id: row.get(4)?,
+                source_block_id: row.get(8)?,
+                reference_kind: row.get(9)?,
+                source_changed: false,
+                indexing_status: row.get(5)?,
+                created_at: row.get(6)?,
+                updated_at: row.get(7)?,
+            })
+        },
+    )
+}
+
+/// Blocks of the reindex job that haven't been embedded yet.
+pub fn find_pending_reindex_blocks(conn: &Connection, model_id: &str, limit: i64) -> Result<Vec<Block>> {
+    find_reindex_blocks(conn, 0, model_id, limit)
+}
+
+/// Blocks of the reindex job swapped into the live index whose edges haven't
+/// been synced yet, with the vectors they were swapped in with.
+pub fn find_swapped_reindex_blocks(
+    conn: &Connection,
+    model_id: &str,
+    limit: i64,
+) -> Result<Vec<(Block, Vec<ChunkEmbedding>)>> {
+    let mut swapped = Vec::new();
+    for block in find_reindex_blocks(conn, 3, model_id, limit)? {
+        let chunks = query_all(
+            conn,
+            "SELECT start_offset, end_offset, embedding
+             FROM shadow_block_chunks
+             WHERE block_id = ?1
+             ORDER BY chunk_index",
+            [&block.id],
+            |row| {
+                let embedding_bytes: Vec<u8> = row.get(2)?;
+                Ok(ChunkEmbedding {
+                    start_offset: row.get::<_, i64>(0)? as usize,
+                    end_offset: row.get::<_, i64>(1)? as usize,
+                    embedding: embedding_bytes
+                        .chunks_exact(4)
+                        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                        .collect(),
+                })
+            },
+        )?;
+        swapped.push((block, chunks));
+    }
+    Ok(swapped)
+}
+
+/// Marks a swapped-in block of the reindex job as having its edges synced, and
+/// drops its shadow vectors.
+pub fn mark_reindex_block_synced(conn: &Connection, block_id: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE reindex_queue SET status = 4 WHERE block_id = ?1",
+        [block_id],
+    )?;
+    conn.execute(
+        "DELETE FROM shadow_block_chunks WHERE block_id = ?1",
+        [block_id],
+    )?;
+    Ok(())
+}
+
+/// Number of swapped-in blocks of the reindex job whose edges haven't been synced yet.
+pub fn count_unsynced_reindex_blocks(conn: &Connection) -> Result<i64> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM reindex_queue WHERE status = 3",
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// Stores the vectors of a block in the shadow tables, replacing any from an earlier attempt.
+pub fn replace_shadow_vectors(
+    conn: &Connection,
+    block_id: &str,
+    chunks: &[ChunkEmbedding],
+    model_id: &str,
+) -> Result<()> {
+    conn.execute(
+        "DELETE FROM shadow_block_chunks WHERE block_id = ?1",
+        [block_id],
+    )?;
+
+    for (chunk_index, chunk) in chunks.iter().enumerate() {
+        let embedding_bytes: Vec<u8> =
+            chunk.embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+
+        conn.execute(
+            "INSERT INTO shadow_block_chunks
+                (block_id, chunk_index, start_offset, end_offset, model_id, dimension, embedding)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                block_id,
+                chunk_index as i64,
+                chunk.start_offset as i64,
+                chunk.end_offset as i64,
+                model_id,
+                chunk.embedding.len() as i64,
+                &embedding_bytes
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Marks a block of the reindex job embedded, from content with hash `indexed_hash`.
+pub fn mark_reindex_block_embedded(conn: &Connection, block_id: &str, indexed_hash: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE reindex_queue SET status = 1, indexed_hash = ?1, error = NULL WHERE block_id = ?2",
+        [indexed_hash, block_id],
+    )?;
+    Ok(())
+}
+
+/// Sets a block of the reindex job aside. It keeps its current vectors when the job is swapped in.
+pub fn mark_reindex_block_failed(conn: &Connection, block_id: &str, error: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE reindex_queue SET status = 2, error = ?1 WHERE block_id = ?2",
+        [error, block_id],
+    )?;
+    Ok(())
+}
+
+/// Replaces the live vectors of every block the reindex job embedded with its
+/// shadow vectors, and marks those blocks INDEXED. A block edited after it was
+/// embedded is skipped: its shadow vectors are outdated and the regular queue
+/// indexes it instead. The vectors of their documents are recomputed. The shadow
+/// vectors are kept, and the blocks queued as swapped, until their edges are
+/// synced. Returns the number of blocks swapped in.
+pub fn swap_in_shadow_vectors(conn: &Connection, model_id: &str) -> Result<u64> {
+    let layout = vector_layout(conn)?;
+    let blocks: Vec<(String, String, String)> = query_all(
+        conn,
+        "SELECT q.block_id, q.indexed_hash, b.document_id
+         FROM reindex_queue q
+         JOIN blocks b ON b.id = q.block_id
+         WHERE q.status = 1 AND b.content_hash IS q.indexed_hash",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+
+    for (block_id, indexed_hash, _) in &blocks {
+        let chunks: Vec<(i64, i64, i64, Vec<u8>)> = query_all(
+            conn,
+            "SELECT chunk_index, start_offset, end_offset, embedding
+             FROM shadow_block_chunks
+             WHERE block_id = ?1
+             ORDER BY chunk_index",
+            [block_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+
+        delete_block_vector(conn, block_id)?;
+        for (chunk_index, start_offset, end_offset, embedding_bytes) in &chunks {
+            insert_block_chunk(
+                conn,
+                &layout,
+                block_id,
+                *chunk_index,
+                (*start_offset, *end_offset),
+                model_id,
+                embedding_bytes,
+            )?;
+        }
+        mark_block_indexed(conn, block_id, indexed_hash, model_id)?;
+        conn.execute(
+            "UPDATE reindex_queue SET status = 3 WHERE block_id = ?1",
+            [block_id],
+        )?;
+    }
+
+    let document_ids: std::collections::HashSet<&String> =
+        blocks.iter().map(|(_, _, document_id)| document_id).collect();
+    for document_id in document_ids {
+        refresh_document_vector(conn, document_id)?;
+    }
+
+    Ok(blocks.len() as u64)
+}
+
 // ============================================
 // Graph Repository
 // ============================================
@@ -413,88 +1959,201 @@ pub fn find_all_documents_for_graph(
     )
 }
 
-pub fn find_all_edges(conn: &Connection) -> Result<Vec<(String, String, Option<String>, f64)>> {
+pub fn find_all_edges(conn: &Connection) -> Result<Vec<GraphEdge>> {
     query_all(
         conn,
-        "SELECT source_id, target_id, relation_type, weight FROM edges",
+        "SELECT source_id, target_id, relation_type, weight, pinned, label, note FROM edges",
         [],
+        |row| {
+            Ok(GraphEdge {
+                source: row.get(0)?,
+                target: row.get(1)?,
+                edge_type: "document-document".to_string(),
+                relation_type: row.get(2)?,
+                weight: Some(row.get(3)?),
+                pinned: row.get(4)?,
+                label: row.get(5)?,
+                note: row.get(6)?,
+            })
+        },
+    )
+}
+
+/// (id, title, tags, hop) of a document in a neighborhood.
+pub type NeighborhoodDocumentRow = (String, Option<String>, Option<String>, u32);
+
+/// Documents within `hops` of `document_id`, each with its shortest distance. An
+/// edge of at least `min_weight`, in either direction, or a shared tag is one hop;
+/// archived documents are neither reached nor walked through.
+pub fn find_document_neighborhood(
+    conn: &Connection,
+    document_id: &str,
+    hops: u32,
+    min_weight: f64,
+) -> Result<Vec<NeighborhoodDocumentRow>> {
+    query_all(
+        conn,
+        "WITH RECURSIVE
+         tag_split(document_id, tag, rest) AS (
+             SELECT id, '', COALESCE(tags, '') || ',' FROM documents WHERE status != 99
+             UNION ALL
+             SELECT document_id,
+                    TRIM(substr(rest, 1, instr(rest, ',') - 1)),
+                    substr(rest, instr(rest, ',') + 1)
+             FROM tag_split WHERE rest != ''
+         ),
+         document_tags(document_id, tag) AS (
+             SELECT document_id, tag FROM tag_split WHERE tag != ''
+         ),
+         reach(document_id, hop) AS (
+             SELECT id, 0 FROM documents WHERE id = ?1 AND status != 99
+             UNION
+             SELECT e.target_id, r.hop + 1 FROM reach r
+             JOIN edges e ON e.source_id = r.document_id
+             JOIN documents d ON d.id = e.target_id AND d.status != 99
+             WHERE r.hop < ?2 AND e.weight >= ?3
+             UNION
+             SELECT e.source_id, r.hop + 1 FROM reach r
+             JOIN edges e ON e.target_id = r.document_id
+             JOIN documents d ON d.id = e.source_id AND d.status != 99
+             WHERE r.hop < ?2 AND e.weight >= ?3
+             UNION
+             SELECT other.document_id, r.hop + 1 FROM reach r
+             JOIN document_tags own ON own.document_id = r.document_id
+             JOIN document_tags other ON other.tag = own.tag
+             WHERE r.hop < ?2
+         )
+         SELECT d.id, d.title, d.tags, MIN(r.hop) FROM reach r
+         JOIN documents d ON d.id = r.document_id
+         GROUP BY d.id
+         ORDER BY MIN(r.hop), d.id",
+        rusqlite::params![document_id, hops, min_weight],
         |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
     )
 }
 
+/// Edges of at least `min_weight` whose ends are both among `document_ids`.
+pub fn find_edges_between(
+    conn: &Connection,
+    document_ids: &[String],
+    min_weight: f64,
+) -> Result<Vec<GraphEdge>> {
+    let ids_json = serde_json::to_string(document_ids).unwrap_or_else(|_| "[]".to_string());
+    query_all(
+        conn,
+        "SELECT source_id, target_id, relation_type, weight, pinned, label, note FROM edges
+         WHERE weight >= ?2
+           AND source_id IN (SELECT value FROM json_each(?1))
+           AND target_id IN (SELECT value FROM json_each(?1))",
+        rusqlite::params![ids_json, min_weight],
+        |row| {
+            Ok(GraphEdge {
+                source: row.get(0)?,
+                target: row.get(1)?,
+                edge_type: "document-document".to_string(),
+                relation_type: row.get(2)?,
+                weight: Some(row.get(3)?),
+                pinned: row.get(4)?,
+                label: row.get(5)?,
+                note: row.get(6)?,
+            })
+        },
+    )
+}
+
 // ============================================
 // Search Repository
 // ============================================
 
-pub fn search_documents(conn: &Connection, query: &str, limit: i64) -> Result<Vec<SearchResult>> {
-    let search_pattern = format!("%{}%", query);
+/// Full-text search over titles, tags and block content using the FTS5 indexes.
+/// `match_expression` must already be a valid FTS5 query (see `search::fts`).
+/// A document is reported once per match type, with its best (lowest BM25) hit,
+/// and rows are ordered by BM25 so each match type forms its own ranked list.
+/// BM25 scores of the title, tag and content indexes aren't comparable, so
+/// `limit` applies to each match type separately. Only documents passing the
+/// filters of `query` are considered.
+pub fn search_documents(
+    conn: &Connection,
+    match_expression: &str,
+    query: &ParsedQuery,
+    limit: i64,
+) -> Result<Vec<SearchResult>> {
+    let filter = query.to_sql_filter(5);
 
-    query_all(
-        conn,
-        "WITH ranked_results AS (
-            -- Priority 1: Title match
+    let sql = format!(
+        "WITH hits AS (
             SELECT
                 d.id,
-                d.title,
-                d.tags,
-                d.status,
-                'title' as match_type,
-                d.title as match_snippet,
-                1 as priority,
-                d.updated_at
-            FROM documents d
-            WHERE d.status != 99 AND d.title LIKE ?1
+                'title' AS match_type,
+                snippet(fts_documents, 0, '<mark>', '</mark>', '…', 16) AS match_snippet,
+                bm25(fts_documents) AS score
+            FROM fts_documents
+            JOIN documents d ON d.seq = fts_documents.rowid
+            WHERE fts_documents MATCH ?1 AND {filter}
 
             UNION ALL
 
-            -- Priority 2: Tags match
             SELECT
                 d.id,
-                d.title,
-                d.tags,
-                d.status,
-                'tag' as match_type,
-                d.tags as match_snippet,
-                2 as priority,
-                d.updated_at
-            FROM documents d
-            WHERE d.status != 99 AND d.tags LIKE ?1
+                'tag' AS match_type,
+                snippet(fts_documents, 1, '<mark>', '</mark>', '…', 16) AS match_snippet,
+                bm25(fts_documents) AS score
+            FROM fts_documents
+            JOIN documents d ON d.seq = fts_documents.rowid
+            WHERE fts_documents MATCH ?2 AND {filter}
 
             UNION ALL
 
-            -- Priority 3: Block content match
             SELECT
                 d.id,
-                d.title,
-                d.tags,
-                d.status,
-                'content' as match_type,
-                SUBSTR(b.content, 1, 100) as match_snippet,
-                3 as priority,
-                d.updated_at
-            FROM documents d
-            JOIN blocks b ON b.document_id = d.id
-            WHERE d.status != 99 AND b.content LIKE ?1
+                'content' AS match_type,
+                snippet(fts_blocks, 0, '<mark>', '</mark>', '…', 16) AS match_snippet,
+                bm25(fts_blocks) AS score
+            FROM fts_blocks
+            JOIN blocks b ON b.seq = fts_blocks.rowid
+            JOIN documents d ON d.id = b.document_id
+            WHERE fts_blocks MATCH ?3 AND {filter}
         ),
         best_match AS (
             SELECT
                 id,
-                MIN(priority) as best_priority,
-                MAX(updated_at) as latest_update
-            FROM ranked_results
-            GROUP BY id
+                match_type,
+                match_snippet,
+                score,
+                ROW_NUMBER() OVER (PARTITION BY id, match_type ORDER BY score ASC) AS match_rank
+            FROM hits
+        ),
+        ranked AS (
+            SELECT
+                bm.*,
+                ROW_NUMBER() OVER (
+                    PARTITION BY bm.match_type
+                    ORDER BY bm.score ASC, d.updated_at DESC
+                ) AS type_rank
+            FROM best_match bm
+            JOIN documents d ON d.id = bm.id
+            WHERE bm.match_rank = 1
         )
-        SELECT
-            bm.id,
-            (SELECT title FROM ranked_results r WHERE r.id = bm.id LIMIT 1) as title,
-            (SELECT tags FROM ranked_results r WHERE r.id = bm.id LIMIT 1) as tags,
-            (SELECT status FROM ranked_results r WHERE r.id = bm.id LIMIT 1) as status,
-            (SELECT match_type FROM ranked_results r WHERE r.id = bm.id ORDER BY priority ASC LIMIT 1) as match_type,
-            (SELECT match_snippet FROM ranked_results r WHERE r.id = bm.id ORDER BY priority ASC LIMIT 1) as match_snippet
-        FROM best_match bm
-        ORDER BY bm.best_priority ASC, bm.latest_update DESC
-        LIMIT ?2",
-        rusqlite::params![&search_pattern, limit],
+        SELECT d.id, d.title, d.tags, d.status, r.match_type, r.match_snippet
+        FROM ranked r
+        JOIN documents d ON d.id = r.id
+        WHERE r.type_rank <= ?4
+        ORDER BY r.match_type, r.type_rank",
+        filter = filter.clause
+    );
+
+    let mut params: Vec<Value> = vec![
+        Value::Text(column_filter("{title title_tokens}", match_expression)),
+        Value::Text(column_filter("{tags tag_tokens}", match_expression)),
+        Value::Text(match_expression.to_string()),
+        Value::Integer(limit),
+    ];
+    params.extend(filter.params);
+
+    query_all(
+        conn,
+        &sql,
+        rusqlite::params_from_iter(params),
         |row| {
             let tags_str: Option<String> = row.get(2)?;
             let tags = tags_str.filter(|s| !s.is_empty()).map(|s| {
@@ -512,37 +2171,118 @@ pub fn search_documents(conn: &Connection, query: &str, limit: i64) -> Result<Ve
                 match_type: row.get(4)?,
                 match_snippet: row.get(5)?,
                 similarity_score: None,
+                score: 0.0,
+                matched_signals: Vec::new(),
+                block_hits: Vec::new(),
             })
         },
     )
 }
 
+/// Number of chunks of `model_id` in documents passing the filters of `query`,
+/// and of all chunks of `model_id`.
+fn count_filtered_chunks(conn: &Connection, query: &ParsedQuery, model_id: &str) -> Result<(i64, i64)> {
+    let filter = query.to_sql_filter(2);
+
+    let mut params: Vec<Value> = vec![Value::Text(model_id.to_string())];
+    params.extend(filter.params);
+
+    conn.query_row(
+        &format!(
+            "SELECT COALESCE(SUM(CASE WHEN {filter} THEN 1 ELSE 0 END), 0), COUNT(*)
+             FROM block_chunks c
+             JOIN blocks b ON b.id = c.block_id
+             JOIN documents d ON d.id = b.document_id
+             WHERE c.model_id = ?1 AND {live}",
+            filter = filter.clause,
+            live = LIVE_CHUNK
+        ),
+        rusqlite::params_from_iter(params),
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+}
+
+/// Nearest blocks to `embedding`, grouped by document in order of each document's
+/// closest block. Every matching block is kept as a block hit with the similarity
+/// and text of its closest chunk. Only vectors of `model_id`, the model that
+/// embedded the query, are compared.
+///
+/// vec0 picks the `k` nearest chunks before the filters of `query` apply, so `k`
+/// is scaled up by how selective they are. When that would exceed what a KNN query
+/// allows, the chunks passing the filters are scanned directly instead.
 pub fn search_by_vector(
     conn: &Connection,
     embedding: &[f32],
+    model_id: &str,
     threshold: f32,
+    query: &ParsedQuery,
     limit: i64,
 ) -> Result<Vec<SearchResult>> {
     let embedding_bytes: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+    let layout = vector_layout(conn)?;
+    let filter = query.to_sql_filter(5);
 
-    query_all(
-        conn,
-        "SELECT DISTINCT
+    let (matching, total) = count_filtered_chunks(conn, query, model_id)?;
+    if matching == 0 {
+        return Ok(Vec::new());
+    }
+    let k = (limit * total + matching - 1) / matching;
+    let (nearest, k) = if k <= layout.max_k() {
+        (layout.knn_sql("?1", "?2", "?4"), k)
+    } else {
+        let scan = format!(
+            "SELECT c.id AS chunk_id, {distance} AS distance
+             FROM block_chunks c
+             JOIN vec_blocks v ON v.rowid = c.id
+             JOIN blocks b ON b.id = c.block_id
+             JOIN documents d ON d.id = b.document_id
+             WHERE c.model_id = ?4
+               AND {live}
+               AND {filter}
+             ORDER BY distance
+             LIMIT ?2",
+            distance = layout.distance_sql("?1"),
+            live = LIVE_CHUNK,
+            filter = filter.clause
+        );
+        (scan, limit)
+    };
+
+    let sql = format!(
+        "SELECT
             d.id,
             d.title,
             d.tags,
             d.status,
-            MIN(v.distance) as distance
-         FROM vec_blocks v
-         JOIN blocks b ON b.rowid = v.rowid
+            b.id,
+            b.order_index,
+            substr(b.content, c.start_offset + 1, 160) AS excerpt,
+            knn.distance
+         FROM ({knn}) knn
+         JOIN block_chunks c ON c.id = knn.chunk_id
+         JOIN blocks b ON b.id = c.block_id
          JOIN documents d ON d.id = b.document_id
-         WHERE v.embedding MATCH ?1
-           AND k = ?2
-           AND v.distance < ?3
-           AND d.status != 99
-         GROUP BY d.id
-         ORDER BY distance ASC",
-        rusqlite::params![&embedding_bytes, limit, threshold],
+         WHERE knn.distance < ?3
+           AND {live}
+           AND {filter}
+         ORDER BY knn.distance ASC",
+        knn = nearest,
+        live = LIVE_CHUNK,
+        filter = filter.clause
+    );
+
+    let mut params: Vec<Value> = vec![
+        Value::Blob(embedding_bytes),
+        Value::Integer(k),
+        Value::Real(layout.native_distance(threshold)),
+        Value::Text(model_id.to_string()),
+    ];
+    params.extend(filter.params);
+
+    let rows = query_all(
+        conn,
+        &sql,
+        rusqlite::params_from_iter(params),
         |row| {
             let tags_str: Option<String> = row.get(2)?;
             let tags = tags_str.filter(|s| !s.is_empty()).map(|s| {
@@ -551,9 +2291,9 @@ pub fn search_by_vector(
                     .filter(|s| !s.is_empty())
                     .collect()
             });
-            let distance: f32 = row.get(4)?;
+            let distance = layout.to_l2(row.get(7)?);
 
-            Ok(SearchResult {
+            let result = SearchResult {
                 id: row.get(0)?,
                 title: row.get(1)?,
                 tags,
@@ -561,11 +2301,306 @@ pub fn search_by_vector(
                 match_type: "similar".to_string(),
                 match_snippet: None,
                 similarity_score: Some(1.0 - distance),
+                score: 0.0,
+                matched_signals: Vec::new(),
+                block_hits: Vec::new(),
+            };
+            let hit = BlockHit {
+                block_id: row.get(4)?,
+                order_index: row.get(5)?,
+                snippet: row.get(6)?,
+                similarity_score: Some(1.0 - distance),
+            };
+
+            Ok((result, hit))
+        },
+    )?;
+
+    // Rows are ordered by distance, so the first row of a document is its closest
+    // block and the first row of a block its closest chunk
+    let mut results: Vec<SearchResult> = Vec::new();
+    for (result, hit) in rows {
+        match results.iter_mut().find(|r| r.id == result.id) {
+            Some(existing) => {
+                if !existing.block_hits.iter().any(|h| h.block_id == hit.block_id) {
+                    existing.block_hits.push(hit);
+                }
+            }
+            None => {
+                let mut result = result;
+                result.block_hits.push(hit);
+                results.push(result);
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Documents matching the filters of a query without free text, most recently updated first.
+pub fn search_documents_by_filter(
+    conn: &Connection,
+    query: &ParsedQuery,
+    limit: i64,
+) -> Result<Vec<SearchResult>> {
+    let filter = query.to_sql_filter(2);
+
+    let sql = format!(
+        "SELECT d.id, d.title, d.tags, d.status
+         FROM documents d
+         WHERE {filter}
+         ORDER BY d.updated_at DESC
+         LIMIT ?1",
+        filter = filter.clause
+    );
+
+    let mut params: Vec<Value> = vec![Value::Integer(limit)];
+    params.extend(filter.params);
+
+    query_all(
+        conn,
+        &sql,
+        rusqlite::params_from_iter(params),
+        |row| {
+            let tags_str: Option<String> = row.get(2)?;
+            let tags = tags_str.filter(|s| !s.is_empty()).map(|s| {
+                s.split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            });
+
+            Ok(SearchResult {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                tags,
+                status: row.get(3)?,
+                match_type: "filter".to_string(),
+                match_snippet: None,
+                similarity_score: None,
+                score: 0.0,
+                matched_signals: Vec::new(),
+                block_hits: Vec::new(),
             })
         },
     )
 }
 
+/// Returns (document_id, distance) of each document's closest block to `embedding`.
+/// Used to report similarity for documents found by keyword search only.
+pub fn find_document_distances(
+    conn: &Connection,
+    embedding: &[f32],
+    model_id: &str,
+    document_ids: &[String],
+) -> Result<Vec<(String, f32)>> {
+    let embedding_bytes: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+    let ids_json = serde_json::to_string(document_ids).unwrap_or_else(|_| "[]".to_string());
+    let layout = vector_layout(conn)?;
+
+    query_all(
+        conn,
+        &format!(
+            "SELECT b.document_id, MIN({}) AS distance
+             FROM vec_blocks v
+             JOIN block_chunks c ON c.id = v.rowid
+             JOIN blocks b ON b.id = c.block_id
+             WHERE b.document_id IN (SELECT value FROM json_each(?2))
+               AND c.model_id = ?3
+               AND {}
+             GROUP BY b.document_id
+             HAVING distance IS NOT NULL",
+            layout.distance_sql("?1"),
+            LIVE_CHUNK
+        ),
+        rusqlite::params![&embedding_bytes, &ids_json, model_id],
+        |row| Ok((row.get(0)?, layout.to_l2(row.get(1)?))),
+    )
+}
+
+/// Blocks of the given documents matching `match_expression`, at most
+/// `per_document` per document, best BM25 first within each document.
+/// Returns (document_id, hit) pairs with highlighted snippets.
+pub fn find_matching_blocks(
+    conn: &Connection,
+    match_expression: &str,
+    document_ids: &[String],
+    per_document: i64,
+) -> Result<Vec<(String, BlockHit)>> {
+    let ids_json = serde_json::to_string(document_ids).unwrap_or_else(|_| "[]".to_string());
+
+    query_all(
+        conn,
+        "WITH matches AS (
+            SELECT
+                b.document_id,
+                b.id,
+                b.order_index,
+                snippet(fts_blocks, 0, '<mark>', '</mark>', '…', 16) AS match_snippet,
+                bm25(fts_blocks) AS score
+            FROM fts_blocks
+            JOIN blocks b ON b.seq = fts_blocks.rowid
+            WHERE fts_blocks MATCH ?1
+              AND b.document_id IN (SELECT value FROM json_each(?2))
+        ),
+        ranked AS (
+            SELECT
+                *,
+                ROW_NUMBER() OVER (PARTITION BY document_id ORDER BY score ASC) AS block_rank
+            FROM matches
+        )
+        SELECT document_id, id, order_index, match_snippet
+        FROM ranked
+        WHERE block_rank <= ?3
+        ORDER BY document_id, block_rank",
+        rusqlite::params![match_expression, &ids_json, per_document],
+        |row| {
+            Ok((
+                row.get(0)?,
+                BlockHit {
+                    block_id: row.get(1)?,
+                    order_index: row.get(2)?,
+                    snippet: row.get(3)?,
+                    similarity_score: None,
+                },
+            ))
+        },
+    )
+}
+
+/// Returns (block_id, distance of the closest chunk) for each of the given blocks
+/// that has an embedding.
+pub fn find_block_distances(
+    conn: &Connection,
+    embedding: &[f32],
+    model_id: &str,
+    block_ids: &[String],
+) -> Result<Vec<(String, f32)>> {
+    let embedding_bytes: Vec<u8> = embedding.iter().flat_map(|f| f.to_le_bytes()).collect();
+    let ids_json = serde_json::to_string(block_ids).unwrap_or_else(|_| "[]".to_string());
+    let layout = vector_layout(conn)?;
+
+    query_all(
+        conn,
+        &format!(
+            "SELECT b.id, MIN({}) AS distance
+             FROM vec_blocks v
+             JOIN block_chunks c ON c.id = v.rowid
+             JOIN blocks b ON b.id = c.block_id
+             WHERE b.id IN (SELECT value FROM json_each(?2))
+               AND c.model_id = ?3
+               AND {}
+             GROUP BY b.id
+             HAVING distance IS NOT NULL",
+            layout.distance_sql("?1"),
+            LIVE_CHUNK
+        ),
+        rusqlite::params![&embedding_bytes, &ids_json, model_id],
+        |row| Ok((row.get(0)?, layout.to_l2(row.get(1)?))),
+    )
+}
+
+// ============================================
+// Vector Benchmark Repository
+// ============================================
+
+/// Expression for the full-precision vector of chunk `c` / vector `v`, if the
+/// index has them: the float vectors themselves, or the copies kept for re-scoring.
+pub fn full_vector_source(layout: &VectorLayout) -> Option<&'static str> {
+    if layout.storage == VectorStorage::Float {
+        Some("v.embedding")
+    } else if layout.keeps_full_vectors() {
+        Some("c.embedding")
+    } else {
+        None
+    }
+}
+
+pub fn count_model_vectors(conn: &Connection, model_id: &str, source: &str) -> Result<i64> {
+    conn.query_row(
+        &format!(
+            "SELECT COUNT(*)
+             FROM vec_blocks v
+             JOIN block_chunks c ON c.id = v.rowid
+             WHERE c.model_id = ?1 AND {source} IS NOT NULL"
+        ),
+        [model_id],
+        |row| row.get(0),
+    )
+}
+
+/// Random chunks of `model_id` with their full-precision vectors, used as benchmark queries.
+pub fn sample_full_vectors(
+    conn: &Connection,
+    model_id: &str,
+    source: &str,
+    limit: i64,
+) -> Result<Vec<(i64, Vec<u8>)>> {
+    query_all(
+        conn,
+        &format!(
+            "SELECT c.id, {source}
+             FROM vec_blocks v
+             JOIN block_chunks c ON c.id = v.rowid
+             WHERE c.model_id = ?1 AND {source} IS NOT NULL
+             ORDER BY random()
+             LIMIT ?2"
+        ),
+        rusqlite::params![model_id, limit],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+}
+
+/// Ids of the `k` chunks nearest to `embedding` by brute force over the
+/// full-precision vectors quantized to `storage`, optionally re-scored with the
+/// full vectors the way `VectorLayout::knn_sql` does. The query chunk itself is
+/// left out.
+pub fn find_nearest_chunks_as(
+    conn: &Connection,
+    model_id: &str,
+    source: &str,
+    (query_chunk_id, embedding): (i64, &[u8]),
+    k: i64,
+    (storage, rescore): (VectorStorage, bool),
+) -> Result<Vec<i64>> {
+    let quantized = VectorLayout::new(storage, false, 0);
+    let distance = match storage {
+        VectorStorage::Binary => "vec_distance_hamming",
+        _ => "vec_distance_l2",
+    };
+    let coarse = format!(
+        "SELECT c.id, {source} AS embedding
+         FROM vec_blocks v
+         JOIN block_chunks c ON c.id = v.rowid
+         WHERE c.model_id = ?2 AND c.id != ?3 AND {source} IS NOT NULL
+         ORDER BY {distance}({stored}, {query})
+         LIMIT {candidates}",
+        stored = quantized.vector_sql(source),
+        query = quantized.vector_sql("?1"),
+        candidates = if rescore {
+            format!("?4 * {}", RESCORE_OVERSAMPLING)
+        } else {
+            "?4".to_string()
+        },
+    );
+    let sql = if rescore {
+        format!(
+            "SELECT id FROM ({coarse})
+             ORDER BY vec_distance_l2(embedding, ?1)
+             LIMIT ?4"
+        )
+    } else {
+        format!("SELECT id FROM ({coarse})")
+    };
+
+    query_all(
+        conn,
+        &sql,
+        rusqlite::params![embedding, model_id, query_chunk_id, k],
+        |row| row.get(0),
+    )
+}
+
 // ============================================
 // Legacy aliases (for backward compatibility)
 // ============================================
@@ -577,3 +2612,523 @@ pub fn get_document_by_id(conn: &Connection, document_id: &str) -> Result<Option
 pub fn get_documents(conn: &Connection, limit: i64, offset: i64) -> Result<Vec<Document>> {
     find_documents(conn, limit, offset)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::document::search::query::parse_query;
+    use crate::infrastructure::database::schema::{apply_vector_layout, sync_embedding_metadata};
+    use crate::infrastructure::database::test_support::{test_database, TEST_MODEL_ID};
+
+    fn insert_document(conn: &Connection, id: &str, title: &str, content: &str) {
+        conn.execute(
+            "INSERT INTO documents (id, title) VALUES (?1, ?2)",
+            [id, title],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO blocks (id, document_id, content, order_index) VALUES (?1, ?2, ?3, 1)",
+            [&format!("{}-b1", id), id, content],
+        )
+        .unwrap();
+    }
+
+    fn embed_block(conn: &Connection, block_id: &str, embedding: [f32; 8]) {
+        embed_block_with(conn, block_id, embedding, TEST_MODEL_ID);
+    }
+
+    fn embed_block_with(conn: &Connection, block_id: &str, embedding: [f32; 8], model_id: &str) {
+        let chunk = ChunkEmbedding {
+            start_offset: 0,
+            end_offset: 1,
+            embedding: embedding.to_vec(),
+        };
+        replace_block_vectors(conn, block_id, &[chunk], model_id).unwrap();
+    }
+
+    /// 30 untagged documents right next to the query and one tagged `rare` further away.
+    fn vault_with_rare_tag(conn: &Connection) {
+        for i in 0..30 {
+            let id = format!("common-{}", i);
+            insert_document(conn, &id, "common", "common");
+            embed_block(conn, &format!("{}-b1", id), [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        }
+        insert_document(conn, "rare", "rare", "rare");
+        conn.execute("UPDATE documents SET tags = 'rare' WHERE id = 'rare'", [])
+            .unwrap();
+        embed_block(conn, "rare-b1", [0.8, 0.6, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn search_by_vector_finds_filtered_documents_beyond_k() {
+        let (_dir, conn) = test_database();
+        vault_with_rare_tag(&conn);
+
+        let query = parse_query("tag:rare note").unwrap();
+        let query_vector = [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let results = search_by_vector(&conn, &query_vector, TEST_MODEL_ID, 1.0, &query, 1).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "rare");
+    }
+
+    #[test]
+    fn search_by_vector_scans_filtered_chunks_past_the_knn_limit() {
+        let (_dir, conn) = test_database();
+        vault_with_rare_tag(&conn);
+
+        // 31 chunks, 1 matching: a limit of 200 would need k = 6200
+        let query = parse_query("tag:rare note").unwrap();
+        let query_vector = [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let results = search_by_vector(&conn, &query_vector, TEST_MODEL_ID, 1.0, &query, 200).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "rare");
+        let similarity = results[0].similarity_score.unwrap();
+        assert!((similarity - (1.0 - 0.4f32.sqrt())).abs() < 1e-4);
+    }
+
+    #[test]
+    fn search_documents_limits_each_match_type() {
+        let (_dir, conn) = test_database();
+        for i in 0..3 {
+            insert_document(&conn, &format!("title-{}", i), "apple notes", "nothing here");
+            insert_document(&conn, &format!("content-{}", i), "other", "apple pie recipe");
+        }
+
+        let query = parse_query("apple").unwrap();
+        let expression = build_match_expression(&query.text).unwrap();
+        let results = search_documents(&conn, &expression, &query, 2).unwrap();
+
+        let count = |match_type: &str| results.iter().filter(|r| r.match_type == match_type).count();
+        assert_eq!(count("title"), 2);
+        assert_eq!(count("content"), 2);
+    }
+
+    fn page_ids(
+        conn: &Connection,
+        sort_by: DocumentSortKey,
+        direction: SortDirection,
+        limit: i64,
+    ) -> Vec<String> {
+        let query = ParsedQuery::default();
+        let mut ids = Vec::new();
+        let mut after = None;
+        loop {
+            let page = find_document_page(conn, &query, sort_by, direction, after, limit).unwrap();
+            let Some((last, sort_value)) = page.last() else {
+                return ids;
+            };
+            after = Some((sort_value.clone(), last.id.clone()));
+            ids.extend(page.iter().map(|(document, _)| document.id.clone()));
+        }
+    }
+
+    #[test]
+    fn document_pages_follow_the_cursor() {
+        let (_dir, conn) = test_database();
+        for (id, title) in [("a", "beta"), ("b", "Alpha"), ("c", "alpha"), ("d", "gamma")] {
+            insert_document(&conn, id, title, "");
+        }
+        conn.execute_batch(
+            "INSERT INTO edges (source_id, target_id, relation_type) VALUES
+                ('a', 'b', 'link'), ('a', 'c', 'link'), ('a', 'd', 'link'), ('b', 'c', 'link'),
+                ('c', 'd', 'similar'), ('a', 'b', 'similar'), ('b', 'd', 'similar'),
+                ('a', 'c', 'similar'), ('d', 'a', 'link'), ('d', 'b', 'link');
+             DELETE FROM edges WHERE source_id = 'd';",
+        )
+        .unwrap();
+
+        // Connections (the deleted edges no longer count): a 5, b 4, c 4, d 3, ties by id
+        assert_eq!(
+            page_ids(&conn, DocumentSortKey::Connections, SortDirection::Desc, 1),
+            vec!["a", "c", "b", "d"]
+        );
+        assert_eq!(
+            page_ids(&conn, DocumentSortKey::Title, SortDirection::Asc, 3),
+            vec!["b", "c", "a", "d"]
+        );
+    }
+
+    #[test]
+    fn neighborhoods_stop_at_the_hop_limit() {
+        let (_dir, conn) = test_database();
+        for id in ["a", "b", "c", "d", "e", "f", "x", "y"] {
+            insert_document(&conn, id, id, "");
+        }
+        conn.execute_batch(
+            "UPDATE documents SET tags = 'rust, graph' WHERE id = 'b';
+             UPDATE documents SET tags = 'graph' WHERE id = 'e';
+             UPDATE documents SET status = 99 WHERE id = 'x';
+             INSERT INTO edges (source_id, target_id, relation_type, weight) VALUES
+                ('a', 'b', 'similar', 0.9), ('c', 'b', 'similar', 0.8), ('c', 'd', 'similar', 0.3),
+                ('e', 'f', 'similar', 0.9), ('a', 'x', 'similar', 0.9), ('x', 'y', 'similar', 0.9);",
+        )
+        .unwrap();
+
+        // b by its edge, c by an edge pointing at b, e by b's tag; d's edge is too
+        // weak, f is three hops away and y only behind the archived x
+        let documents = find_document_neighborhood(&conn, "a", 2, 0.5).unwrap();
+        let hops: Vec<(&str, u32)> = documents
+            .iter()
+            .map(|(id, _, _, hop)| (id.as_str(), *hop))
+            .collect();
+        assert_eq!(hops, vec![("a", 0), ("b", 1), ("c", 2), ("e", 2)]);
+
+        let ids: Vec<String> = documents.into_iter().map(|(id, ..)| id).collect();
+        let mut edges: Vec<(String, String)> = find_edges_between(&conn, &ids, 0.5)
+            .unwrap()
+            .into_iter()
+            .map(|edge| (edge.source, edge.target))
+            .collect();
+        edges.sort();
+        assert_eq!(
+            edges,
+            vec![("a".to_string(), "b".to_string()), ("c".to_string(), "b".to_string())]
+        );
+        assert!(find_document_neighborhood(&conn, "x", 2, 0.5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn references_follow_created_renamed_and_archived_documents() {
+        let (_dir, conn) = test_database();
+        insert_document(&conn, "a", "Alpha", "see [[Beta]]");
+        sync_document_links(&conn, "a").unwrap();
+        assert!(find_link_targets(&conn, "a").unwrap().is_empty());
+
+        insert_document(&conn, "b", "Beta", "");
+        let changed = relink_references_to(&conn, "b", &["b".to_string(), "Beta".to_string()]).unwrap();
+        assert_eq!(changed, vec![("a".to_string(), (vec!["b".to_string()], vec![]))]);
+
+        conn.execute("UPDATE documents SET status = 99 WHERE id = 'b'", []).unwrap();
+        assert_eq!(resolve_link_target(&conn, "b").unwrap(), None);
+        conn.execute("UPDATE documents SET status = 0, title = 'Gamma' WHERE id = 'b'", []).unwrap();
+        let names = ["b".to_string(), "Beta".to_string(), "Gamma".to_string()];
+        let changed = relink_references_to(&conn, "b", &names).unwrap();
+        assert_eq!(changed, vec![("a".to_string(), (vec![], vec!["b".to_string()]))]);
+    }
+
+    #[test]
+    fn embeds_are_left_out_of_the_index() {
+        let (_dir, conn) = test_database();
+        insert_document(&conn, "src", "Source", "shared text");
+        insert_document(&conn, "copy", "Copy", "own text");
+        embed_block(&conn, "copy-b1", [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        conn.execute("UPDATE documents SET updated_at = '2000-01-01 00:00:00'", []).unwrap();
+
+        let mut block = find_blocks_by_document_id(&conn, "copy").unwrap().remove(0);
+        block.content = Some("old text".to_string());
+        block.source_block_id = Some("src-b1".to_string());
+        block.source_document_id = Some("src".to_string());
+        block.reference_kind = Some("embed".to_string());
+        upsert_block(&conn, &block).unwrap();
+        assert!(delete_reference_copy_vectors(&conn, "copy-b1").unwrap());
+        assert!(!delete_reference_copy_vectors(&conn, "copy-b1").unwrap());
+
+        let pending: Vec<String> = find_oldest_pending_blocks(&conn, TEST_MODEL_ID, 10)
+            .unwrap()
+            .into_iter()
+            .map(|block| block.id)
+            .collect();
+        assert_eq!(pending, vec!["src-b1"]);
+        assert_eq!(count_indexing_stats(&conn, TEST_MODEL_ID).unwrap().total, 1);
+
+        // Following the source counts as an update of the embedding document
+        assert_eq!(propagate_block_content(&conn, "src-b1").unwrap(), vec!["copy"]);
+        let updated_at: String = conn
+            .query_row("SELECT updated_at FROM documents WHERE id = 'copy'", [], |row| row.get(0))
+            .unwrap();
+        assert!(updated_at.as_str() > "2000-01-01 00:00:00");
+    }
+
+    #[test]
+    fn annotated_edges_are_curated_like_pinned_ones() {
+        let (_dir, conn) = test_database();
+        for id in ["a", "b", "c", "d"] {
+            insert_document(&conn, id, id, "");
+        }
+        for target in ["b", "c", "d"] {
+            upsert_edge(&conn, "a", target, "similar", 0.5).unwrap();
+        }
+        assert_eq!(set_edge_pinned(&conn, "b", "a", true).unwrap(), 1);
+        assert_eq!(annotate_edge(&conn, "a", "c", "similar", Some("same topic"), None).unwrap(), 1);
+        assert_eq!(set_edge_pinned(&conn, "a", "missing", false).unwrap(), 0);
+
+        let mut curated = find_curated_documents(&conn, "a").unwrap();
+        curated.sort();
+        assert_eq!(curated, vec!["b", "c"]);
+        assert_eq!(find_curated_documents(&conn, "c").unwrap(), vec!["a"]);
+    }
+
+    #[test]
+    fn similar_edges_go_with_the_blocks_behind_them() {
+        let (_dir, conn) = test_database();
+        for id in ["a", "b", "c", "d"] {
+            insert_document(&conn, id, id, "");
+            if id != "a" {
+                upsert_edge(&conn, "a", id, "similar", 0.5).unwrap();
+            }
+        }
+        conn.execute(
+            "INSERT INTO blocks (id, document_id, content, order_index) VALUES ('a-b2', 'a', '', 2)",
+            [],
+        )
+        .unwrap();
+        replace_block_similarities(
+            &conn,
+            "a-b1",
+            &[
+                ("b-b1".to_string(), 0.9, 0, 0),
+                ("c-b1".to_string(), 0.9, 0, 0),
+                ("d-b1".to_string(), 0.9, 0, 0),
+            ],
+        )
+        .unwrap();
+        replace_block_similarities(&conn, "a-b2", &[("c-b1".to_string(), 0.8, 0, 0)]).unwrap();
+        set_edge_pinned(&conn, "a", "d", true).unwrap();
+
+        let mut similar_ids = find_block_similarity_documents(&conn, "a-b1").unwrap();
+        similar_ids.sort();
+        assert_eq!(similar_ids, vec!["b", "c", "d"]);
+
+        delete_block(&conn, "a-b1").unwrap();
+        assert_eq!(delete_unsupported_similar_edges(&conn, "a", &similar_ids).unwrap(), vec!["b"]);
+        assert!(find_similar_edge(&conn, "a", "b").unwrap().is_none());
+        assert!(find_similar_edge(&conn, "c", "a").unwrap().is_some());
+        assert!(find_similar_edge(&conn, "a", "d").unwrap().is_some());
+    }
+
+    #[test]
+    fn edge_evidence_shows_the_chunks_that_matched() {
+        let (_dir, conn) = test_database();
+        insert_document(&conn, "a", "A", "opening words. rust borrow checker");
+        insert_document(&conn, "b", "B", "borrow checking in rust");
+        let chunk = |start_offset, end_offset, axis: usize| {
+            let mut embedding = vec![0.0f32; 8];
+            embedding[axis] = 1.0;
+            ChunkEmbedding {
+                start_offset,
+                end_offset,
+                embedding,
+            }
+        };
+        replace_block_vectors(&conn, "a-b1", &[chunk(0, 14, 0), chunk(15, 34, 1)], TEST_MODEL_ID).unwrap();
+        replace_block_vectors(&conn, "b-b1", &[chunk(0, 23, 1)], TEST_MODEL_ID).unwrap();
+
+        replace_block_similarities(&conn, "a-b1", &[("b-b1".to_string(), 0.9, 1, 0)]).unwrap();
+        let recorded = find_edge_evidence(&conn, "b", "a", 5).unwrap();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].source_snippet.as_deref(), Some("borrow checking in rust"));
+        assert_eq!(recorded[0].target_snippet.as_deref(), Some("rust borrow checker"));
+
+        // Without recorded pairs the closest chunks are measured
+        let measured = find_closest_chunk_pairs(&conn, "a", "b", TEST_MODEL_ID, 5).unwrap();
+        assert_eq!(measured.len(), 1);
+        assert_eq!(measured[0].source_snippet.as_deref(), Some("rust borrow checker"));
+        assert!((measured[0].similarity - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn document_pages_walk_the_sort_index() {
+        let (_dir, conn) = test_database();
+        let query = ParsedQuery::default();
+        let filter = document_list_filter(&query, 1);
+
+        for (sort_column, index) in [
+            ("d.updated_at", "idx_documents_updated_at"),
+            ("COALESCE(d.title, '') COLLATE NOCASE", "idx_documents_title"),
+            ("d.connection_count", "idx_documents_connection_count"),
+        ] {
+            let sql = format!(
+                "EXPLAIN QUERY PLAN SELECT d.id FROM documents d
+                 WHERE {} AND ({c}, d.id) < ('x', 'y')
+                 ORDER BY {c} DESC, d.id DESC LIMIT 10",
+                filter.clause,
+                c = sort_column
+            );
+            let plan: Vec<String> = query_all(&conn, &sql, [], |row| row.get(3)).unwrap();
+            let plan = plan.join("\n");
+
+            assert!(plan.contains(index), "{}", plan);
+            assert!(!plan.contains("TEMP B-TREE"), "{}", plan);
+        }
+    }
+
+    #[test]
+    fn documents_not_yet_re_embedded_are_compared_in_their_previous_model() {
+        let (_dir, conn) = test_database();
+        for (id, embedding) in [
+            ("a", [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+            ("b", [0.8, 0.6, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+            ("c", [0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+        ] {
+            insert_document(&conn, id, id, id);
+            embed_block_with(&conn, &format!("{}-b1", id), embedding, "previous-model");
+            refresh_document_vector(&conn, id).unwrap();
+        }
+        embed_block(&conn, "c-b1", [0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        refresh_document_vector(&conn, "c").unwrap();
+
+        let similar = find_similar_documents(&conn, "a", TEST_MODEL_ID, 5).unwrap();
+        let similar_ids: Vec<&str> = similar.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(similar_ids, vec!["b"]);
+
+        let distances = find_document_vector_distances(
+            &conn,
+            "a",
+            TEST_MODEL_ID,
+            &["b".to_string(), "c".to_string()],
+        )
+        .unwrap();
+        assert_eq!(distances.len(), 1);
+        assert_eq!(distances[0].0, "b");
+
+        let ids = ["a".to_string(), "b".to_string(), "c".to_string()];
+        let mut unmigrated = find_documents_with_previous_vectors(&conn, TEST_MODEL_ID, &ids).unwrap();
+        unmigrated.sort();
+        assert_eq!(unmigrated, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn reindexed_blocks_wait_for_their_edges_after_the_swap() {
+        let (_dir, conn) = test_database();
+        insert_document(&conn, "a", "a", "a");
+        embed_block(&conn, "a-b1", [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        insert_reindex_job(&conn, &ReindexScope::All, TEST_MODEL_ID).unwrap();
+
+        // Paused while another model stands in
+        assert!(find_pending_reindex_blocks(&conn, "fallback-model", 10).unwrap().is_empty());
+        assert!(find_reindex_progress(&conn, "fallback-model").unwrap().unwrap().paused);
+        assert_eq!(find_pending_reindex_blocks(&conn, TEST_MODEL_ID, 10).unwrap().len(), 1);
+
+        let chunk = ChunkEmbedding {
+            start_offset: 0,
+            end_offset: 1,
+            embedding: vec![0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        };
+        replace_shadow_vectors(&conn, "a-b1", std::slice::from_ref(&chunk), TEST_MODEL_ID).unwrap();
+        conn.execute("UPDATE blocks SET content_hash = 'hash' WHERE id = 'a-b1'", [])
+            .unwrap();
+        mark_reindex_block_embedded(&conn, "a-b1", "hash").unwrap();
+        assert_eq!(swap_in_shadow_vectors(&conn, TEST_MODEL_ID).unwrap(), 1);
+
+        let swapped = find_swapped_reindex_blocks(&conn, TEST_MODEL_ID, 10).unwrap();
+        assert_eq!(swapped.len(), 1);
+        assert_eq!(swapped[0].0.id, "a-b1");
+        assert_eq!(swapped[0].1[0].embedding, chunk.embedding);
+        assert_eq!(count_unsynced_reindex_blocks(&conn).unwrap(), 1);
+
+        mark_reindex_block_synced(&conn, "a-b1").unwrap();
+        assert_eq!(count_unsynced_reindex_blocks(&conn).unwrap(), 0);
+        assert!(find_swapped_reindex_blocks(&conn, TEST_MODEL_ID, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rescoring_measures_chunks_without_a_full_copy_by_their_quantized_vector() {
+        for storage in [VectorStorage::Int8, VectorStorage::Binary] {
+            let (_dir, mut conn) = test_database();
+            insert_document(&conn, "a", "a", "a");
+            embed_block(&conn, "a-b1", [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+            insert_document(&conn, "b", "b", "b");
+            embed_block(&conn, "b-b1", [0.8, 0.6, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+            sync_embedding_metadata(&conn, TEST_MODEL_ID, 8).unwrap();
+            apply_vector_layout(&mut conn, VectorLayout::new(storage, true, 8)).unwrap();
+            // As if b was indexed before re-scoring was turned on
+            conn.execute("UPDATE block_chunks SET embedding = NULL WHERE block_id = 'b-b1'", [])
+                .unwrap();
+
+            let query = parse_query("").unwrap();
+            let query_vector = [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+            let results = search_by_vector(&conn, &query_vector, TEST_MODEL_ID, 1.5, &query, 10).unwrap();
+            let result_ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+            assert_eq!(result_ids, vec!["a", "b"], "{:?}", storage);
+
+            let similar =
+                find_similar_blocks_with_document(&conn, &query_vector, TEST_MODEL_ID, 1.5, 10).unwrap();
+            assert_eq!(similar.len(), 2, "{:?}", storage);
+        }
+    }
+
+    #[test]
+    fn failed_blocks_are_listed_by_when_they_last_failed() {
+        let (_dir, conn) = test_database();
+        for id in ["a", "b", "c"] {
+            insert_document(&conn, id, id, id);
+            record_indexing_failure(&conn, &format!("{}-b1", id), "boom", 1, 1, 1).unwrap();
+        }
+        // b failed first but retries back off longest, a failed before the column existed
+        conn.execute_batch(
+            "UPDATE blocks SET last_error_at = '2026-01-01 10:00:00', next_attempt_at = '2026-01-02 10:00:00'
+             WHERE id = 'b-b1';
+             UPDATE blocks SET last_error_at = '2026-01-01 11:00:00', next_attempt_at = '2026-01-01 11:01:00'
+             WHERE id = 'c-b1';
+             UPDATE blocks SET last_error_at = NULL WHERE id = 'a-b1';",
+        )
+        .unwrap();
+
+        let failed = find_failed_blocks(&conn, 10).unwrap();
+        let failed_ids: Vec<&str> = failed.iter().map(|block| block.block_id.as_str()).collect();
+        assert_eq!(failed_ids, vec!["c-b1", "b-b1", "a-b1"]);
+    }
+
+    #[test]
+    fn documents_keep_a_vector_per_model() {
+        let (_dir, conn) = test_database();
+        insert_document(&conn, "a", "a", "a");
+        conn.execute(
+            "INSERT INTO blocks (id, document_id, content, order_index) VALUES ('a-b2', 'a', 'a', 2)",
+            [],
+        )
+        .unwrap();
+        embed_block_with(&conn, "a-b1", [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], "previous-model");
+        embed_block(&conn, "a-b2", [0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+
+        refresh_document_vector(&conn, "a").unwrap();
+        let models: Vec<String> = query_all(
+            &conn,
+            "SELECT model_id FROM document_vectors WHERE document_id = 'a' ORDER BY model_id",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+        assert_eq!(models, vec!["previous-model", TEST_MODEL_ID]);
+        assert!(find_documents_without_vector(&conn, TEST_MODEL_ID, 10).unwrap().is_empty());
+
+        // Re-embedding the last previous-model block drops that model's vector
+        embed_block(&conn, "a-b1", [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        refresh_document_vector(&conn, "a").unwrap();
+        let vectors: i64 = conn
+            .query_row("SELECT COUNT(*) FROM vec_documents", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(vectors, 1);
+    }
+
+    #[test]
+    fn re_embedding_keeps_other_models_vectors_until_purged() {
+        let (_dir, conn) = test_database();
+        insert_document(&conn, "a", "a", "a");
+        embed_block_with(&conn, "a-b1", [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], "previous-model");
+        insert_document(&conn, "b", "b", "b");
+        embed_block_with(&conn, "b-b1", [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], "previous-model");
+        embed_block(&conn, "b-b1", [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        let chunk_count = || -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM block_chunks", [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(chunk_count(), 3);
+
+        // The superseded chunk of b no longer answers for the previous model
+        let query = parse_query("").unwrap();
+        let query_vector = [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let results = search_by_vector(&conn, &query_vector, "previous-model", 1.0, &query, 10).unwrap();
+        let result_ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(result_ids, vec!["a"]);
+
+        assert_eq!(delete_superseded_vectors(&conn).unwrap(), 1);
+        assert_eq!(chunk_count(), 2);
+        let vectors: i64 = conn
+            .query_row("SELECT COUNT(*) FROM vec_blocks", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(vectors, 2);
+    }
+}
@@ -1,22 +1,73 @@
-use crate::domains::config::service::load_config;
-use crate::domains::document::embedding::calculate_text_embedding;
-use crate::domains::document::model::{Block, EdgeChangeInfo, GraphEdge};
+use crate::domains::config::error::ConfigError;
+use crate::domains::config::service::{apply_vector_settings, init_database, load_config};
+use crate::domains::document::embedding::{
+    current_model_id, is_provider_degraded, provider_generation, ChunkEmbedding,
+};
+use crate::domains::document::embedding_worker::{embed_chunks, EmbeddingPriority};
+use crate::domains::document::model::{
+    Block, EdgeChangeInfo, GraphEdge, IndexingProgress, IndexingState,
+};
+use crate::domains::document::repository::BlockSimilarity;
 use crate::domains::document::service;
+use crate::utils::hash::content_hash;
 use once_cell::sync::Lazy;
 use std::collections::{HashSet, HashMap};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Notify};
 use tokio::time::sleep;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.5;
 const SIMILARITY_SEARCH_LIMIT: i64 = 100;
+const INDEXING_BATCH_SIZE: i64 = 16;
+/// Document vectors computed per idle pass; they only average stored vectors.
+const DOCUMENT_VECTOR_BATCH_SIZE: i64 = 256;
+/// How often to try setting up a configured provider again while the fallback stands in for it.
+const PROVIDER_RETRY_INTERVAL: Duration = Duration::from_secs(300);
+const DEFAULT_IDLE_INTERVAL_SECS: u64 = 5;
+const MIN_IDLE_INTERVAL_SECS: u64 = 1;
+const MAX_IDLE_INTERVAL_SECS: u64 = 3600;
+/// Weight of the latest batch in the smoothed throughput.
+const THROUGHPUT_SMOOTHING: f64 = 0.3;
+/// Least time between two progress events while batches keep coming; each one counts the queue.
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What a batch did: how many blocks it took from the queue, and how many of them
+/// were indexed. A batch that failed or was cancelled attempted blocks but indexed none.
+#[derive(Debug, Clone, Copy)]
+struct BatchOutcome {
+    attempted: usize,
+    indexed: usize,
+}
+
+/// Where the vectors of a batch go: straight into the live index, or into the
+/// shadow tables of a reindex until it is swapped in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IndexTarget {
+    Live,
+    Shadow,
+}
+
+fn similarity_threshold(app_handle: &AppHandle) -> f32 {
+    load_config(app_handle)
+        .map(|config| config.vector_settings.similarity_threshold)
+        .unwrap_or(DEFAULT_SIMILARITY_THRESHOLD)
+}
 
 pub struct IndexingScheduler {
     is_running: AtomicBool,
+    is_paused: AtomicBool,
+    is_busy: AtomicBool,
+    cancel_requested: AtomicBool,
+    idle_interval_secs: AtomicU64,
+    indexed_this_session: AtomicU64,
+    blocks_per_second: StdMutex<Option<f64>>,
+    last_progress_emit: StdMutex<Option<Instant>>,
+    /// Wakes the loop early from an idle or paused wait.
+    wake: Notify,
     app_handle: Arc<Mutex<Option<AppHandle>>>,
 }
 
@@ -24,6 +75,14 @@ impl IndexingScheduler {
     pub fn new() -> Self {
         Self {
             is_running: AtomicBool::new(false),
+            is_paused: AtomicBool::new(false),
+            is_busy: AtomicBool::new(false),
+            cancel_requested: AtomicBool::new(false),
+            idle_interval_secs: AtomicU64::new(DEFAULT_IDLE_INTERVAL_SECS),
+            indexed_this_session: AtomicU64::new(0),
+            blocks_per_second: StdMutex::new(None),
+            last_progress_emit: StdMutex::new(None),
+            wake: Notify::new(),
             app_handle: Arc::new(Mutex::new(None)),
         }
     }
@@ -42,10 +101,147 @@ impl IndexingScheduler {
 
     pub fn stop(&self) {
         self.is_running.store(false, Ordering::SeqCst);
+        self.wake.notify_one();
         info!("Indexing scheduler stopped");
     }
 
+    /// Stops picking up new batches once the current one is saved.
+    pub fn pause(&self) {
+        if !self.is_paused.swap(true, Ordering::SeqCst) {
+            self.wake.notify_one();
+            info!("Indexing scheduler paused");
+        }
+    }
+
+    pub fn resume(&self) {
+        self.cancel_requested.store(false, Ordering::SeqCst);
+        if self.is_paused.swap(false, Ordering::SeqCst) {
+            self.wake.notify_one();
+            info!("Indexing scheduler resumed");
+        }
+    }
+
+    /// Wakes the loop early from an idle wait, e.g. when there is new work for it.
+    pub fn wake_up(&self) {
+        self.wake.notify_one();
+    }
+
+    /// Pauses and discards the batch in flight instead of saving it. Its blocks stay
+    /// pending and are indexed again after `resume`.
+    pub fn cancel(&self) {
+        self.cancel_requested.store(true, Ordering::SeqCst);
+        self.pause();
+    }
+
+    /// Sets how long the loop waits before checking for pending blocks again when
+    /// the queue is empty, clamped to `MIN_IDLE_INTERVAL_SECS..=MAX_IDLE_INTERVAL_SECS`.
+    pub fn set_idle_interval(&self, secs: u64) -> u64 {
+        let secs = secs.clamp(MIN_IDLE_INTERVAL_SECS, MAX_IDLE_INTERVAL_SECS);
+        self.idle_interval_secs.store(secs, Ordering::SeqCst);
+        self.wake.notify_one();
+        info!("Indexing idle interval set to {}s", secs);
+        secs
+    }
+
+    pub fn state(&self) -> IndexingState {
+        if self.is_paused.load(Ordering::SeqCst) {
+            IndexingState::Paused
+        } else if is_provider_degraded() {
+            IndexingState::WaitingForProvider
+        } else if self.is_busy.load(Ordering::SeqCst) {
+            IndexingState::Running
+        } else {
+            IndexingState::Idle
+        }
+    }
+
+    /// Current queue length, throughput and ETA. The ETA is only known once a batch
+    /// has been timed.
+    pub fn progress(&self, app_handle: &AppHandle) -> anyhow::Result<IndexingProgress> {
+        let stats = service::get_indexing_stats(app_handle)?;
+        let reindex = service::get_reindex_progress(app_handle)?;
+        let queue_length = stats.pending
+            + reindex
+                .as_ref()
+                .map_or(0, |job| (job.total - job.embedded - job.failed).max(0));
+        let blocks_per_second = *self
+            .blocks_per_second
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let eta_seconds = blocks_per_second
+            .filter(|rate| *rate > 0.0)
+            .map(|rate| (queue_length as f64 / rate).ceil() as u64);
+
+        Ok(IndexingProgress {
+            state: self.state(),
+            queue_length,
+            failed: stats.failed,
+            indexed_this_session: self.indexed_this_session.load(Ordering::SeqCst),
+            blocks_per_second,
+            eta_seconds,
+            idle_interval_secs: self.idle_interval_secs.load(Ordering::SeqCst),
+            reindex,
+        })
+    }
+
+    fn emit_progress(&self, app_handle: &AppHandle) {
+        *self
+            .last_progress_emit
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Instant::now());
+        match self.progress(app_handle) {
+            Ok(progress) => {
+                let _ = app_handle.emit("indexing-progress", progress);
+            }
+            Err(e) => error!("Failed to read indexing progress: {:?}", e),
+        }
+    }
+
+    /// Emits progress unless it was emitted less than `PROGRESS_EMIT_INTERVAL` ago.
+    fn emit_progress_throttled(&self, app_handle: &AppHandle) {
+        let recently_emitted = self
+            .last_progress_emit
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .is_some_and(|emitted| emitted.elapsed() < PROGRESS_EMIT_INTERVAL);
+        if !recently_emitted {
+            self.emit_progress(app_handle);
+        }
+    }
+
+    fn record_throughput(&self, count: usize, elapsed: Duration) {
+        self.indexed_this_session.fetch_add(count as u64, Ordering::SeqCst);
+
+        let seconds = elapsed.as_secs_f64();
+        if seconds <= 0.0 {
+            return;
+        }
+        let rate = count as f64 / seconds;
+        let mut blocks_per_second = self
+            .blocks_per_second
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *blocks_per_second = Some(match *blocks_per_second {
+            Some(previous) => THROUGHPUT_SMOOTHING * rate + (1.0 - THROUGHPUT_SMOOTHING) * previous,
+            None => rate,
+        });
+    }
+
+    /// Sleeps for `duration`, or until `pause`, `resume`, `stop` or an interval change.
+    async fn wait(&self, duration: Duration) {
+        tokio::select! {
+            _ = sleep(duration) => {}
+            _ = self.wake.notified() => {}
+        }
+    }
+
     async fn run_loop(&self) {
+        // Starting from 0 also retries blocks that failed in an earlier session
+        let mut seen_generation = 0;
+        let mut last_provider_retry = Instant::now();
+        // State last reported to the frontend, so an idle or paused loop doesn't repeat itself
+        let mut reported_state: Option<IndexingState> = None;
+
         while self.is_running.load(Ordering::SeqCst) {
             let app_handle = {
                 let handle = self.app_handle.lock().await;
@@ -53,12 +249,74 @@ impl IndexingScheduler {
             };
 
             if let Some(ref handle) = app_handle {
-                match self.process_next_block(handle) {
-                    Ok(true) => continue,
-                    Ok(false) => sleep(Duration::from_secs(5)).await,
+                if self.is_paused.load(Ordering::SeqCst) {
+                    if reported_state != Some(IndexingState::Paused) {
+                        reported_state = Some(IndexingState::Paused);
+                        self.emit_progress(handle);
+                    }
+                    self.wake.notified().await;
+                    continue;
+                }
+
+                if is_provider_degraded() && last_provider_retry.elapsed() >= PROVIDER_RETRY_INTERVAL {
+                    last_provider_retry = Instant::now();
+                    self.retry_provider(handle).await;
+                }
+
+                // The fallback's vectors would replace the configured model's and sync
+                // edges from them, so indexing waits for the provider to come back
+                if is_provider_degraded() {
+                    if reported_state != Some(IndexingState::WaitingForProvider) {
+                        reported_state = Some(IndexingState::WaitingForProvider);
+                        self.emit_progress(handle);
+                    }
+                    self.wait(PROVIDER_RETRY_INTERVAL.saturating_sub(last_provider_retry.elapsed()))
+                        .await;
+                    continue;
+                }
+
+                // Blocks that failed with an earlier provider get another chance with this one
+                let generation = provider_generation();
+                if generation != seen_generation {
+                    seen_generation = generation;
+                    match service::retry_failed_blocks(handle, None) {
+                        Ok(0) => {}
+                        Ok(count) => info!("Retrying {} failed blocks with the new embedding provider", count),
+                        Err(e) => error!("Failed to requeue failed blocks: {:?}", e),
+                    }
+                }
+
+                // Busy from the first batch until the queue runs dry
+                let started = Instant::now();
+                self.is_busy.store(true, Ordering::SeqCst);
+                let result = self.process_next_batch(handle).await;
+                if !matches!(result, Ok(outcome) if outcome.attempted > 0) {
+                    self.is_busy.store(false, Ordering::SeqCst);
+                }
+
+                match result {
+                    Ok(outcome) if outcome.attempted == 0 => {
+                        if reported_state != Some(IndexingState::Idle) {
+                            reported_state = Some(IndexingState::Idle);
+                            self.emit_progress(handle);
+                        }
+                        let interval = self.idle_interval_secs.load(Ordering::SeqCst);
+                        self.wait(Duration::from_secs(interval)).await;
+                    }
+                    Ok(outcome) => {
+                        if outcome.indexed > 0 {
+                            self.record_throughput(outcome.indexed, started.elapsed());
+                        }
+                        if reported_state != Some(IndexingState::Running) {
+                            reported_state = Some(IndexingState::Running);
+                            self.emit_progress(handle);
+                        } else {
+                            self.emit_progress_throttled(handle);
+                        }
+                    }
                     Err(e) => {
                         error!("Error processing block: {:?}", e);
-                        sleep(Duration::from_secs(10)).await;
+                        self.wait(Duration::from_secs(10)).await;
                     }
                 }
             } else {
@@ -67,98 +325,370 @@ impl IndexingScheduler {
         }
     }
 
-    fn process_next_block(&self, app_handle: &AppHandle) -> anyhow::Result<bool> {
-        let block = match service::get_oldest_pending_block(app_handle)? {
-            Some(block) => block,
-            None => return Ok(false),
+    /// Converts the vector index to the configured storage if that changed, off the
+    /// async runtime since it rewrites every vector.
+    async fn apply_vector_settings(&self, app_handle: &AppHandle) -> anyhow::Result<()> {
+        let app_handle = app_handle.clone();
+        if tokio::task::spawn_blocking(move || apply_vector_settings(&app_handle)).await?? {
+            info!("Vector index converted to the configured storage");
+        }
+        Ok(())
+    }
+
+    /// Sets up the configured provider again, off the async runtime since loading a
+    /// model or probing an endpoint blocks.
+    async fn retry_provider(&self, app_handle: &AppHandle) {
+        let app_handle = app_handle.clone();
+        match tokio::task::spawn_blocking(move || init_database(&app_handle)).await {
+            Ok(Ok(())) => info!("Embedding provider is available again"),
+            Ok(Err(ConfigError::EmbeddingProviderUnavailable(_))) => {}
+            Ok(Err(e)) => error!("Failed to reinitialize database: {:?}", e),
+            Err(e) => error!("Embedding provider retry panicked: {:?}", e),
+        }
+    }
+
+    /// Indexes the next pending blocks. Nothing is attempted only when the queue is
+    /// empty, so a batch that was cancelled or had every block fail still counts as
+    /// work and the loop moves on to the next one. If the batch fails as a whole, each
+    /// of its blocks not already charged for its own failure is charged a failed
+    /// attempt and backs off, so the rest of the queue moves on instead of the same
+    /// blocks being picked up again. Only failing to record that is an error.
+    async fn process_next_batch(&self, app_handle: &AppHandle) -> anyhow::Result<BatchOutcome> {
+        self.apply_vector_settings(app_handle).await?;
+
+        let mut target = IndexTarget::Live;
+        let mut blocks = service::get_oldest_pending_blocks(app_handle, INDEXING_BATCH_SIZE)?;
+
+        // A reindex only moves on while no edited block is waiting
+        if blocks.is_empty() {
+            if let Some(swapped) = service::complete_reindex(app_handle)? {
+                info!("Reindex swapped in {} blocks, syncing their edges", swapped);
+            }
+            target = IndexTarget::Shadow;
+            blocks = service::get_pending_reindex_blocks(app_handle, INDEXING_BATCH_SIZE)?;
+        }
+        if blocks.is_empty() {
+            let synced = self.sync_reindexed_edges(app_handle)?;
+            if synced > 0 {
+                return Ok(BatchOutcome { attempted: synced, indexed: 0 });
+            }
+        }
+        if blocks.is_empty() {
+            service::release_previous_provider(app_handle)?;
+            let computed = service::backfill_document_vectors(app_handle, DOCUMENT_VECTOR_BATCH_SIZE)?;
+            if computed > 0 {
+                info!("Document vectors computed: {}", computed);
+            }
+            return Ok(BatchOutcome { attempted: 0, indexed: 0 });
+        }
+
+        let mut charged: HashSet<String> = HashSet::new();
+        let indexed = match self.index_blocks(app_handle, blocks.clone(), target, &mut charged).await {
+            Ok(indexed) => indexed,
+            Err(e) => {
+                let error = e.to_string();
+                let failures: Vec<(&str, String)> = blocks
+                    .iter()
+                    .filter(|block| !charged.contains(&block.id))
+                    .map(|block| (block.id.as_str(), error.clone()))
+                    .collect();
+                let recorded = match target {
+                    IndexTarget::Live => service::record_indexing_failures(app_handle, &failures),
+                    IndexTarget::Shadow => service::record_reindex_failures(app_handle, &failures),
+                };
+                if let Err(record_error) = recorded {
+                    error!("Failed to record indexing failures: {:?}", record_error);
+                    return Err(e);
+                }
+                error!("Indexing batch failed: {:?}", e);
+                0
+            }
         };
 
-        let content = block.content.as_deref().unwrap_or("");
-        if content.trim().is_empty() {
-            service::update_block_indexing_status(app_handle, &block.id, 1)?;
+        Ok(BatchOutcome {
+            attempted: blocks.len(),
+            indexed,
+        })
+    }
+
+    /// Embeds a batch of blocks in one pass: a single batched model run for all
+    /// their chunks and a single transaction for the vectors. Returns how many blocks
+    /// were marked indexed. Edges are only synced for the live index: a reindex's
+    /// vectors wait in the shadow tables, and its edges are synced from them once
+    /// they are swapped in.
+    /// Blocks whose own failure was recorded are added to `charged`.
+    async fn index_blocks(
+        &self,
+        app_handle: &AppHandle,
+        blocks: Vec<Block>,
+        target: IndexTarget,
+        charged: &mut HashSet<String>,
+    ) -> anyhow::Result<usize> {
+        let model_id = current_model_id();
+        let indexed_hash =
+            |block: &Block| (block.id.clone(), content_hash(block.content.as_deref().unwrap_or("")));
+
+        let (empty_blocks, blocks): (Vec<Block>, Vec<Block>) = blocks
+            .into_iter()
+            .partition(|block| block.content.as_deref().unwrap_or("").trim().is_empty());
+        for block in &empty_blocks {
             info!("Block skipped (empty content): {}", block.id);
-            return Ok(true);
+            if target == IndexTarget::Live {
+                service::record_block_similarities(app_handle, &block.id, &[])?;
+            }
         }
+        let mut indexed: Vec<(String, String)> = empty_blocks.iter().map(indexed_hash).collect();
+
+        if !blocks.is_empty() {
+            info!("Processing {} blocks", blocks.len());
+
+            // 1. Calculate and save one embedding per chunk, for all blocks at once.
+            // Inference runs on the embedding worker, behind any pending search query.
+            let contents: Vec<String> = blocks
+                .iter()
+                .map(|block| block.content.clone().unwrap_or_default())
+                .collect();
+            let results = embed_chunks(contents, EmbeddingPriority::Background)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?;
+
+            // The model run can't be interrupted, but its results can be dropped
+            if self.cancel_requested.swap(false, Ordering::SeqCst) {
+                info!("Indexing batch cancelled, {} blocks left pending", blocks.len() + empty_blocks.len());
+                return Ok(0);
+            }
+            // Vectors from a provider that was swapped out, or for the fallback now
+            // standing in, are never stored; the blocks wait for the next batch
+            if is_provider_degraded() || current_model_id() != model_id {
+                info!("Embedding provider changed during the batch, {} blocks left pending", blocks.len());
+                return Ok(0);
+            }
+
+            // A block the provider failed on keeps its previous vectors rather than being
+            // stored with vectors from another model, and is retried later
+            let mut embedded: Vec<(&Block, Vec<ChunkEmbedding>)> = Vec::new();
+            let mut failed: Vec<(&Block, String)> = Vec::new();
+            for (block, result) in blocks.iter().zip(results) {
+                match result {
+                    Ok(chunks) => embedded.push((block, chunks)),
+                    Err(e) => failed.push((block, e)),
+                }
+            }
+            if embedded.is_empty() {
+                if let Some((_, e)) = failed.first() {
+                    return Err(anyhow::anyhow!("Embedding failed for the whole batch: {}", e));
+                }
+            }
+            for (block, e) in &failed {
+                warn!("Block embedding failed: {}: {}", block.id, e);
+            }
+            let failures: Vec<(&str, String)> = failed
+                .into_iter()
+                .map(|(block, e)| (block.id.as_str(), e))
+                .collect();
+            let vectors: Vec<(&str, &[ChunkEmbedding])> = embedded
+                .iter()
+                .map(|(block, chunks)| (block.id.as_str(), chunks.as_slice()))
+                .collect();
+            match target {
+                IndexTarget::Live => {
+                    service::record_indexing_failures(app_handle, &failures)?;
+                    charged.extend(failures.iter().map(|(block_id, _)| block_id.to_string()));
+                    service::save_block_vectors(app_handle, &vectors, &model_id)?;
+
+                    let document_ids: Vec<String> = embedded
+                        .iter()
+                        .map(|(block, _)| block.document_id.clone())
+                        .collect::<HashSet<_>>()
+                        .into_iter()
+                        .collect();
+                    service::refresh_document_vectors(app_handle, &document_ids)?;
+                }
+                IndexTarget::Shadow => {
+                    service::record_reindex_failures(app_handle, &failures)?;
+                    charged.extend(failures.iter().map(|(block_id, _)| block_id.to_string()));
+                    service::save_shadow_vectors(app_handle, &vectors, &model_id)?;
+                }
+            }
 
-        info!("Processing block: {}", block.id);
+            // 2. Sync edges for each block's document
+            if target == IndexTarget::Live {
+                let threshold = similarity_threshold(app_handle);
+                for (block, chunks) in &embedded {
+                    self.sync_document_edges(app_handle, &block.id, &block.document_id, chunks, &model_id, threshold)?;
+                }
+            }
+            indexed.extend(embedded.iter().map(|(block, _)| indexed_hash(block)));
+        }
 
-        let threshold = load_config(app_handle)
-            .map(|config| config.vector_settings.similarity_threshold)
-            .unwrap_or(DEFAULT_SIMILARITY_THRESHOLD);
+        match target {
+            IndexTarget::Live => service::mark_blocks_indexed(app_handle, &indexed, &model_id)?,
+            IndexTarget::Shadow => service::mark_reindex_blocks_embedded(app_handle, &indexed)?,
+        }
 
-        // 1. Calculate and save embedding
-        let embedding = calculate_text_embedding(content);
-        service::save_block_vector(app_handle, &block.id, &embedding)?;
+        info!("Blocks indexed: {}", indexed.len());
+        Ok(indexed.len())
+    }
 
-        // 2. Sync edges for this document
-        self.sync_document_edges(app_handle, &block.document_id, &embedding, threshold)?;
+    /// Syncs the edges of the next blocks a finished reindex swapped in, from the
+    /// vectors they were swapped in with. Returns how many blocks were synced.
+    fn sync_reindexed_edges(&self, app_handle: &AppHandle) -> anyhow::Result<usize> {
+        let blocks = service::get_swapped_reindex_blocks(app_handle, INDEXING_BATCH_SIZE)?;
+        if blocks.is_empty() {
+            return Ok(0);
+        }
 
-        service::update_block_indexing_status(app_handle, &block.id, 1)?;
+        let model_id = current_model_id();
+        let threshold = similarity_threshold(app_handle);
+        for (block, chunks) in &blocks {
+            if chunks.is_empty() {
+                service::record_block_similarities(app_handle, &block.id, &[])?;
+            } else {
+                self.sync_document_edges(app_handle, &block.id, &block.document_id, chunks, &model_id, threshold)?;
+            }
+        }
 
-        info!("Block indexed: {}", block.id);
-        Ok(true)
+        let block_ids: Vec<String> = blocks.iter().map(|(block, _)| block.id.clone()).collect();
+        if service::mark_reindex_blocks_synced(app_handle, &block_ids)? {
+            info!("Reindex finished, edges synced");
+        }
+        Ok(blocks.len())
     }
 
     fn sync_document_edges(
         &self,
         app_handle: &AppHandle,
+        block_id: &str,
         document_id: &str,
-        embedding: &[f32],
+        chunks: &[ChunkEmbedding],
+        model_id: &str,
         threshold: f32,
     ) -> anyhow::Result<()> {
-        // A group: Find similar documents via vector search
-        let similar_blocks = service::find_similar_blocks_with_document(
-            app_handle,
-            embedding,
-            threshold,
-            SIMILARITY_SEARCH_LIMIT,
-        )?;
+        // A group: Find similar documents via vector search, for every chunk of the block
+        let mut similar_blocks_by_doc: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut block_similarity: HashMap<String, (f32, i64, i64)> = HashMap::new();
+        for (chunk_index, chunk) in chunks.iter().enumerate() {
+            let similar_blocks = service::find_similar_blocks_with_document(
+                app_handle,
+                &chunk.embedding,
+                model_id,
+                threshold,
+                SIMILARITY_SEARCH_LIMIT,
+            )?;
+
+            // Collect distinct similar blocks per document (excluding self-document)
+            for (similar_block_id, doc_id, similar_chunk_index, distance) in similar_blocks {
+                if doc_id != document_id {
+                    let best = block_similarity
+                        .entry(similar_block_id.clone())
+                        .or_insert((0.0, chunk_index as i64, similar_chunk_index));
+                    if 1.0 - distance > best.0 {
+                        *best = (1.0 - distance, chunk_index as i64, similar_chunk_index);
+                    }
+                    similar_blocks_by_doc.entry(doc_id).or_default().insert(similar_block_id);
+                }
+            }
+        }
+
+        // Keep the block pairs behind the edges, best chunk match per pair, for explain_edge
+        let block_similarity: Vec<BlockSimilarity> = block_similarity
+            .into_iter()
+            .map(|(similar_block_id, (similarity, chunk_index, similar_chunk_index))| {
+                (similar_block_id, similarity, chunk_index, similar_chunk_index)
+            })
+            .collect();
+        service::record_block_similarities(app_handle, block_id, &block_similarity)?;
+
+        let similar_blocks_per_doc: HashMap<String, usize> = similar_blocks_by_doc
+            .into_iter()
+            .map(|(doc_id, block_ids)| (doc_id, block_ids.len()))
+            .collect();
 
-        // Count similar blocks per document (excluding self-document)
-        let mut similar_blocks_per_doc: HashMap<String, usize> = HashMap::new();
-        for (_, doc_id, _) in similar_blocks.iter() {
-            if doc_id != document_id {
-                *similar_blocks_per_doc.entry(doc_id.clone()).or_insert(0) += 1;
+        // Documents close as a whole count too, so a short note doesn't need
+        // several similar blocks to get connected
+        let mut similar_doc_ids: HashSet<String> = similar_blocks_per_doc.keys().cloned().collect();
+        for related in service::find_similar_documents(app_handle, document_id, Some(SIMILARITY_SEARCH_LIMIT))? {
+            if 1.0 - related.similarity_score < threshold {
+                similar_doc_ids.insert(related.id);
             }
         }
 
-        let similar_doc_ids: HashSet<String> = similar_blocks_per_doc.keys().cloned().collect();
+        // Manual curation: pinned or annotated edges stay whatever the similarity,
+        // blocked pairs are never linked
+        let (curated_doc_ids, blocked_doc_ids) = service::find_edge_overrides(app_handle, document_id)?;
+        let curated_doc_ids: HashSet<String> = curated_doc_ids.into_iter().collect();
+        let blocked_doc_ids: HashSet<String> = blocked_doc_ids.into_iter().collect();
+        similar_doc_ids.retain(|doc_id| !blocked_doc_ids.contains(doc_id));
+
+        // Weight edges by document similarity where both documents have a vector
+        let candidate_ids: Vec<String> = similar_doc_ids.iter().cloned().collect();
+        let document_similarity: HashMap<String, f64> =
+            service::find_document_vector_distances(app_handle, document_id, model_id, &candidate_ids)?
+                .into_iter()
+                .map(|(doc_id, distance)| (doc_id, (1.0 - distance as f64).clamp(0.0, 1.0)))
+                .collect();
 
         // B group: Get existing edge documents (both directions)
-        let existing_edges = service::find_related_documents(app_handle, document_id)?;
+        let existing_edges = service::find_related_documents(app_handle, document_id, "similar")?;
         let existing_doc_ids: HashSet<String> =
             existing_edges.into_iter().map(|(doc_id, _)| doc_id).collect();
 
         let mut added_edges: Vec<GraphEdge> = Vec::new();
         let mut removed_edges: Vec<GraphEdge> = Vec::new();
 
-        // Add edges for documents in A but not in B, with weight based on similar block count
+        // Add edges for documents in A but not in B, weighted by document similarity,
+        // or by similar block count for a document without a vector yet
         for doc_id in similar_doc_ids.difference(&existing_doc_ids) {
-            if let Some(similar_count) = similar_blocks_per_doc.get(doc_id) {
-                let weight = *similar_count as f64 / 10.0; // Normalize to roughly 0.0-1.0 range
-                let weight = weight.min(1.0); // Cap at 1.0
-                service::create_edge(app_handle, document_id, doc_id, Some("similar"), weight)?;
+            let weight = document_similarity.get(doc_id).copied().or_else(|| {
+                similar_blocks_per_doc
+                    .get(doc_id)
+                    .map(|similar_count| (*similar_count as f64 / 10.0).min(1.0))
+            });
+            if let Some(weight) = weight {
+                service::create_edge(app_handle, document_id, doc_id, "similar", weight)?;
                 info!("Edge added: {} -> {} (weight: {:.2})", document_id, doc_id, weight);
 
                 added_edges.push(GraphEdge {
                     source: document_id.to_string(),
                     target: doc_id.clone(),
                     edge_type: "document-document".to_string(),
+                    relation_type: Some("similar".to_string()),
                     weight: Some(weight),
+                    pinned: false,
+                    label: None,
+                    note: None,
                 });
             }
         }
 
-        // Remove edges for documents in B but not in A
-        for doc_id in existing_doc_ids.difference(&similar_doc_ids) {
-            service::delete_edge_bidirectional(app_handle, document_id, doc_id)?;
+        // Remove edges for documents in B but not in A, unless curated. A document
+        // with blocks not yet re-embedded since a model change can't be compared
+        // with the new vectors, so its edges wait until it is
+        let unmatched_ids: Vec<String> = existing_doc_ids
+            .difference(&similar_doc_ids)
+            .filter(|doc_id| !curated_doc_ids.contains(*doc_id))
+            .cloned()
+            .collect();
+        let unmigrated_doc_ids: HashSet<String> =
+            service::find_documents_with_previous_vectors(app_handle, model_id, &unmatched_ids)?
+                .into_iter()
+                .collect();
+        for doc_id in unmatched_ids
+            .iter()
+            .filter(|doc_id| !unmigrated_doc_ids.contains(*doc_id))
+        {
+            service::delete_edge_bidirectional(app_handle, document_id, doc_id, "similar")?;
             info!("Edge removed: {} <-> {}", document_id, doc_id);
 
             removed_edges.push(GraphEdge {
                 source: document_id.to_string(),
                 target: doc_id.clone(),
                 edge_type: "document-document".to_string(),
+                relation_type: Some("similar".to_string()),
                 weight: None,
+                pinned: false,
+                label: None,
+                note: None,
             });
         }
 
@@ -0,0 +1,77 @@
+use crate::domains::document::search::tokenizer::{bigrams, contains_cjk, split_words, strip_particle};
+
+/// Builds an FTS5 `MATCH` expression from free-text input.
+///
+/// Double-quoted segments become phrase queries and every other word becomes a
+/// prefix query, so `rust "error handling"` turns into `"rust"* "error handling"`.
+/// Every token is quoted, which keeps FTS5 operators typed by the user literal.
+/// Korean words are matched by stem or bigrams against the `*_tokens` columns
+/// (see `tokenizer::index_text`).
+/// Returns `None` when the input contains no searchable text.
+pub fn build_match_expression(text: &str) -> Option<String> {
+    let mut clauses: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut in_phrase = false;
+
+    for c in text.chars() {
+        match c {
+            '"' => {
+                push_clause(&mut clauses, &current, in_phrase);
+                current.clear();
+                in_phrase = !in_phrase;
+            }
+            c if c.is_whitespace() && !in_phrase => {
+                push_clause(&mut clauses, &current, false);
+                current.clear();
+            }
+            c => current.push(c),
+        }
+    }
+    // An unterminated quote still counts as a phrase
+    push_clause(&mut clauses, &current, in_phrase);
+
+    if clauses.is_empty() {
+        None
+    } else {
+        Some(clauses.join(" "))
+    }
+}
+
+/// Restricts a match expression to an FTS5 column spec, e.g. `title` or `{title title_tokens}`.
+pub fn column_filter(column: &str, expression: &str) -> String {
+    format!("{} : ({})", column, expression)
+}
+
+fn push_clause(clauses: &mut Vec<String>, token: &str, is_phrase: bool) {
+    let token = token.trim().trim_end_matches('*');
+    if token.is_empty() || !token.chars().any(|c| c.is_alphanumeric()) {
+        return;
+    }
+
+    if is_phrase {
+        clauses.push(format!("\"{}\"", token));
+        return;
+    }
+
+    for word in split_words(token) {
+        clauses.push(word_clause(&word));
+    }
+}
+
+/// A prefix query for one word. CJK words are matched by their particle-stripped
+/// stem, or by all of their character bigrams so that a word inside a compound
+/// (e.g. "데이터베이스" in "관계형데이터베이스") is still found.
+fn word_clause(word: &str) -> String {
+    if !contains_cjk(word) {
+        return format!("\"{}\"*", word);
+    }
+
+    let stem = strip_particle(word);
+    let stem_bigrams = bigrams(stem);
+    if stem_bigrams.len() < 2 {
+        return format!("\"{}\"*", stem);
+    }
+
+    let bigram_group: Vec<String> = stem_bigrams.iter().map(|b| format!("\"{}\"", b)).collect();
+    format!("(\"{}\"* OR ({}))", stem, bigram_group.join(" "))
+}
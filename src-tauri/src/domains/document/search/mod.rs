@@ -0,0 +1,4 @@
+pub mod fts;
+pub mod query;
+pub mod ranking;
+pub mod tokenizer;
@@ -0,0 +1,319 @@
+use crate::domains::document::search::fts::build_match_expression;
+use rusqlite::types::Value;
+
+/// A search query split into free text and structured filters, e.g.
+/// `tag:rust status:permanent created:>2026-01-01 "exact phrase" -excluded`.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedQuery {
+    /// Free words and quoted phrases (quotes kept), for the keyword and vector backends.
+    pub text: String,
+    pub tags: Vec<String>,
+    pub statuses: Vec<i16>,
+    pub date_filters: Vec<DateFilter>,
+    pub excluded: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DateFilter {
+    pub column: &'static str,
+    pub operator: &'static str,
+    pub date: String,
+}
+
+/// A SQL predicate over `documents d` with numbered placeholders and their values.
+#[derive(Debug, Clone)]
+pub struct SqlFilter {
+    pub clause: String,
+    pub params: Vec<Value>,
+}
+
+impl ParsedQuery {
+    /// Text for the embedding model: phrases without their quotes.
+    pub fn semantic_text(&self) -> String {
+        self.text.replace('"', "")
+    }
+
+    pub fn has_text(&self) -> bool {
+        !self.text.trim().is_empty()
+    }
+
+    /// Builds the filter predicate. Placeholders start at `?{first_param}` so the
+    /// clause can be appended to a query that already binds `first_param - 1` values.
+    /// Archived documents are excluded unless a status filter asks for them.
+    pub fn to_sql_filter(&self, first_param: usize) -> SqlFilter {
+        let mut clauses: Vec<String> = Vec::new();
+        let mut params: Vec<Value> = Vec::new();
+        let next = |value: Value, params: &mut Vec<Value>| {
+            params.push(value);
+            format!("?{}", first_param + params.len() - 1)
+        };
+
+        if self.statuses.is_empty() {
+            clauses.push("d.status != 99".to_string());
+        } else {
+            let placeholders: Vec<String> = self
+                .statuses
+                .iter()
+                .map(|status| next(Value::Integer(*status as i64), &mut params))
+                .collect();
+            clauses.push(format!("d.status IN ({})", placeholders.join(", ")));
+        }
+
+        for tag in &self.tags {
+            let placeholder = next(Value::Text(format!(",{},", tag)), &mut params);
+            clauses.push(format!(
+                "instr(',' || LOWER(REPLACE(COALESCE(d.tags, ''), ', ', ',')) || ',', {}) > 0",
+                placeholder
+            ));
+        }
+
+        for filter in &self.date_filters {
+            let placeholder = next(Value::Text(filter.date.clone()), &mut params);
+            clauses.push(format!(
+                "date(d.{}) {} {}",
+                filter.column, filter.operator, placeholder
+            ));
+        }
+
+        for term in &self.excluded {
+            let Some(expression) = build_match_expression(&format!("\"{}\"", term)) else {
+                continue;
+            };
+            let document_placeholder = next(Value::Text(expression.clone()), &mut params);
+            let block_placeholder = next(Value::Text(expression), &mut params);
+            clauses.push(format!(
+                "d.seq NOT IN (SELECT rowid FROM fts_documents WHERE fts_documents MATCH {})
+                 AND d.id NOT IN (
+                     SELECT b.document_id FROM fts_blocks
+                     JOIN blocks b ON b.seq = fts_blocks.rowid
+                     WHERE fts_blocks MATCH {}
+                 )",
+                document_placeholder, block_placeholder
+            ));
+        }
+
+        SqlFilter {
+            clause: clauses.join(" AND "),
+            params,
+        }
+    }
+}
+
+/// Parses the search syntax:
+/// - `tag:<name>`: document has the tag (repeatable, all must match)
+/// - `status:fleeting|permanent|archived`: document status (repeatable, any may match)
+/// - `created:<op><date>` / `updated:<op><date>`: `op` is one of `>`, `>=`, `<`, `<=`, `=`
+///   (default `=`), `date` is `YYYY-MM-DD`
+/// - `"exact phrase"`: phrase match
+/// - `-word` / `-"phrase"`: exclude documents containing it
+///
+/// Anything else is free text.
+pub fn parse_query(input: &str) -> Result<ParsedQuery, String> {
+    let mut parsed = ParsedQuery::default();
+    let mut text_parts: Vec<String> = Vec::new();
+
+    for token in split_tokens(input)? {
+        if let Some(excluded) = token.strip_prefix('-') {
+            let excluded = excluded.trim_matches('"');
+            if excluded.trim().is_empty() {
+                return Err("Expected a word or phrase after '-'".to_string());
+            }
+            parsed.excluded.push(excluded.to_string());
+            continue;
+        }
+
+        if token.starts_with('"') {
+            text_parts.push(token);
+            continue;
+        }
+
+        let Some((key, value)) = token.split_once(':') else {
+            text_parts.push(token);
+            continue;
+        };
+
+        match key.to_lowercase().as_str() {
+            "tag" => parsed.tags.push(parse_tag(value)?),
+            "status" => parsed.statuses.push(parse_status(value)?),
+            "created" => parsed.date_filters.push(parse_date_filter("created_at", value)?),
+            "updated" => parsed.date_filters.push(parse_date_filter("updated_at", value)?),
+            // Not a filter key (e.g. a URL or "note:"), keep it as text
+            _ => text_parts.push(token),
+        }
+    }
+
+    parsed.text = text_parts.join(" ");
+    Ok(parsed)
+}
+
+/// Splits on whitespace, keeping double-quoted segments (and a `-` or `key:`
+/// directly in front of them) together with their quotes.
+fn split_tokens(input: &str) -> Result<Vec<String>, String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => {
+                current.push(c);
+                in_quotes = !in_quotes;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err("Unterminated quote".to_string());
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    Ok(tokens)
+}
+
+fn parse_tag(value: &str) -> Result<String, String> {
+    let tag = value.trim_matches('"').trim().to_lowercase();
+    if tag.is_empty() {
+        return Err("Expected a tag name after 'tag:'".to_string());
+    }
+    Ok(tag)
+}
+
+fn parse_status(value: &str) -> Result<i16, String> {
+    match value.to_lowercase().as_str() {
+        "fleeting" | "0" => Ok(0),
+        "permanent" | "1" => Ok(1),
+        "archived" | "99" => Ok(99),
+        other => Err(format!(
+            "Unknown status '{}', expected fleeting, permanent or archived",
+            other
+        )),
+    }
+}
+
+fn parse_date_filter(column: &'static str, value: &str) -> Result<DateFilter, String> {
+    let (operator, date) = [">=", "<=", ">", "<", "="]
+        .iter()
+        .find_map(|op| value.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("=", value));
+
+    if !is_valid_date(date) {
+        return Err(format!("Invalid date '{}', expected YYYY-MM-DD", date));
+    }
+
+    Ok(DateFilter {
+        column,
+        operator,
+        date: date.to_string(),
+    })
+}
+
+fn is_valid_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    let (year, month, day) = match parts.as_slice() {
+        [year, month, day] => (*year, *month, *day),
+        _ => return false,
+    };
+
+    let is_number = |s: &str, len: usize| s.len() == len && s.chars().all(|c| c.is_ascii_digit());
+    if !is_number(year, 4) || !is_number(month, 2) || !is_number(day, 2) {
+        return false;
+    }
+
+    let month: u32 = month.parse().unwrap_or(0);
+    let day: u32 = day.parse().unwrap_or(0);
+    (1..=12).contains(&month) && (1..=31).contains(&day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn free_text_keeps_phrases_with_their_quotes() {
+        let parsed = parse_query(r#"rust "error handling" tips"#).unwrap();
+
+        assert_eq!(parsed.text, r#"rust "error handling" tips"#);
+        assert_eq!(parsed.semantic_text(), "rust error handling tips");
+        assert!(parsed.tags.is_empty());
+    }
+
+    #[test]
+    fn filters_are_separated_from_text() {
+        let parsed =
+            parse_query("tag:Rust status:permanent created:>=2026-01-01 updated:2026-02-03 graph").unwrap();
+
+        assert_eq!(parsed.text, "graph");
+        assert_eq!(parsed.tags, vec!["rust"]);
+        assert_eq!(parsed.statuses, vec![1]);
+        assert_eq!(parsed.date_filters.len(), 2);
+        assert_eq!(parsed.date_filters[0].column, "created_at");
+        assert_eq!(parsed.date_filters[0].operator, ">=");
+        assert_eq!(parsed.date_filters[0].date, "2026-01-01");
+        assert_eq!(parsed.date_filters[1].column, "updated_at");
+        assert_eq!(parsed.date_filters[1].operator, "=");
+    }
+
+    #[test]
+    fn exclusions_accept_words_and_phrases() {
+        let parsed = parse_query(r#"notes -draft -"old ideas""#).unwrap();
+
+        assert_eq!(parsed.text, "notes");
+        assert_eq!(parsed.excluded, vec!["draft", "old ideas"]);
+    }
+
+    #[test]
+    fn unknown_keys_stay_text() {
+        let parsed = parse_query("https://example.com note:").unwrap();
+
+        assert_eq!(parsed.text, "https://example.com note:");
+        assert!(parsed.tags.is_empty());
+    }
+
+    #[test]
+    fn invalid_filters_are_rejected() {
+        assert!(parse_query("status:done").is_err());
+        assert!(parse_query("created:2026-13-01").is_err());
+        assert!(parse_query("created:26-01-01").is_err());
+        assert!(parse_query("tag:").is_err());
+        assert!(parse_query("-").is_err());
+        assert!(parse_query(r#""unterminated"#).is_err());
+    }
+
+    #[test]
+    fn sql_filter_numbers_placeholders_from_first_param() {
+        let filter = parse_query("tag:rust status:archived created:<2026-01-01")
+            .unwrap()
+            .to_sql_filter(3);
+
+        assert_eq!(
+            filter.clause,
+            "d.status IN (?3) \
+             AND instr(',' || LOWER(REPLACE(COALESCE(d.tags, ''), ', ', ',')) || ',', ?4) > 0 \
+             AND date(d.created_at) < ?5"
+        );
+        assert_eq!(
+            filter.params,
+            vec![
+                Value::Integer(99),
+                Value::Text(",rust,".to_string()),
+                Value::Text("2026-01-01".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn sql_filter_hides_archived_documents_by_default() {
+        let filter = ParsedQuery::default().to_sql_filter(1);
+
+        assert_eq!(filter.clause, "d.status != 99");
+        assert!(filter.params.is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+/// One ranked result list fed into [`reciprocal_rank_fusion`], best match first.
+pub struct RankedList {
+    pub signal: String,
+    pub weight: f32,
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FusedRank {
+    pub id: String,
+    pub score: f32,
+    /// Signals that ranked this id, strongest contribution first.
+    pub signals: Vec<String>,
+}
+
+/// Weighted reciprocal rank fusion: `score(d) = Σ weight / (k + rank)` over every
+/// list containing `d`, with 1-based ranks. Results are sorted by score, descending.
+pub fn reciprocal_rank_fusion(lists: &[RankedList], k: f32) -> Vec<FusedRank> {
+    let mut order: Vec<String> = Vec::new();
+    let mut contributions: HashMap<String, Vec<(String, f32)>> = HashMap::new();
+
+    for list in lists {
+        for (index, id) in list.ids.iter().enumerate() {
+            let contribution = list.weight / (k + (index + 1) as f32);
+            let entry = contributions.entry(id.clone()).or_insert_with(|| {
+                order.push(id.clone());
+                Vec::new()
+            });
+            entry.push((list.signal.clone(), contribution));
+        }
+    }
+
+    let mut fused: Vec<FusedRank> = order
+        .into_iter()
+        .map(|id| {
+            let mut parts = contributions.remove(&id).unwrap_or_default();
+            parts.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+            FusedRank {
+                score: parts.iter().map(|(_, c)| c).sum(),
+                signals: parts.into_iter().map(|(signal, _)| signal).collect(),
+                id,
+            }
+        })
+        .collect();
+
+    // Stable sort keeps first-seen order for ties
+    fused.sort_by(|a, b| b.score.total_cmp(&a.score));
+    fused
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(signal: &str, weight: f32, ids: &[&str]) -> RankedList {
+        RankedList {
+            signal: signal.to_string(),
+            weight,
+            ids: ids.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    fn ids(fused: &[FusedRank]) -> Vec<&str> {
+        fused.iter().map(|r| r.id.as_str()).collect()
+    }
+
+    #[test]
+    fn scores_sum_weighted_reciprocal_ranks() {
+        let fused = reciprocal_rank_fusion(
+            &[list("title", 1.0, &["a", "b"]), list("semantic", 2.0, &["b"])],
+            60.0,
+        );
+
+        assert_eq!(ids(&fused), vec!["b", "a"]);
+        assert!((fused[0].score - (1.0 / 62.0 + 2.0 / 61.0)).abs() < 1e-6);
+        assert!((fused[1].score - 1.0 / 61.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn signals_are_ordered_by_contribution() {
+        let fused = reciprocal_rank_fusion(
+            &[list("title", 1.0, &["x", "a"]), list("content", 1.0, &["a"])],
+            60.0,
+        );
+
+        let a = fused.iter().find(|r| r.id == "a").unwrap();
+        assert_eq!(a.signals, vec!["content", "title"]);
+    }
+
+    #[test]
+    fn ties_keep_first_seen_order() {
+        let fused = reciprocal_rank_fusion(
+            &[list("title", 1.0, &["a"]), list("tag", 1.0, &["b"])],
+            60.0,
+        );
+
+        assert_eq!(ids(&fused), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn empty_lists_fuse_to_nothing() {
+        assert!(reciprocal_rank_fusion(&[list("title", 1.0, &[])], 60.0).is_empty());
+    }
+}
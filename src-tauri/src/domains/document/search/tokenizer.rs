@@ -0,0 +1,146 @@
+/// Korean particles (josa) that attach to the end of nouns, longest first so
+/// that e.g. "에서는" is stripped before "는".
+const KOREAN_PARTICLES: &[&str] = &[
+    "에서부터", "으로부터", "이라도", "에게서", "한테서", "에서는", "으로는", "에서", "에게",
+    "한테", "께서", "으로", "부터", "까지", "마저", "조차", "처럼", "보다", "이나", "이랑",
+    "하고", "과", "와", "을", "를", "이", "가", "은", "는", "의", "에", "도", "만", "로",
+];
+
+/// Minimum number of characters a stem must keep after a particle is stripped,
+/// so short nouns such as "사과" are not cut down to a single syllable.
+const MIN_STEM_CHARS: usize = 2;
+
+/// Hangul, CJK ideographs and Japanese kana. These scripts either do not
+/// separate words with spaces or glue particles onto nouns, so whole-word
+/// matching is not enough for them.
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{1100}'..='\u{11FF}'   // Hangul Jamo
+        | '\u{3040}'..='\u{30FF}' // Hiragana, Katakana
+        | '\u{3130}'..='\u{318F}' // Hangul Compatibility Jamo
+        | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+        | '\u{AC00}'..='\u{D7AF}' // Hangul Syllables
+        | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+    )
+}
+
+pub fn contains_cjk(word: &str) -> bool {
+    word.chars().any(is_cjk)
+}
+
+/// Splits text into lowercase words on anything that is not alphanumeric.
+pub fn split_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Strips a trailing Korean particle, e.g. "노트를" -> "노트".
+/// Returns the word unchanged when no particle matches or the stem would be too short.
+pub fn strip_particle(word: &str) -> &str {
+    for particle in KOREAN_PARTICLES {
+        if let Some(stem) = word.strip_suffix(particle) {
+            if stem.chars().count() >= MIN_STEM_CHARS {
+                return stem;
+            }
+        }
+    }
+    word
+}
+
+/// Overlapping character bigrams, e.g. "책노트" -> ["책노", "노트"].
+pub fn bigrams(word: &str) -> Vec<String> {
+    let chars: Vec<char> = word.chars().collect();
+    if chars.len() < 2 {
+        return Vec::new();
+    }
+
+    chars.windows(2).map(|pair| pair.iter().collect()).collect()
+}
+
+/// Tokens for a single word: the word itself, and for CJK words also the
+/// particle-stripped stem and the character bigrams.
+fn word_tokens(word: &str) -> Vec<String> {
+    let mut tokens = vec![word.to_string()];
+
+    if contains_cjk(word) {
+        let stem = strip_particle(word);
+        if stem != word {
+            tokens.push(stem.to_string());
+        }
+        tokens.extend(bigrams(word));
+    }
+
+    tokens
+}
+
+/// Tokenizes text for bag-of-words use (e.g. the fallback embedding).
+pub fn tokenize(text: &str) -> Vec<String> {
+    split_words(text)
+        .into_iter()
+        .flat_map(|word| word_tokens(&word))
+        .collect()
+}
+
+/// Extra tokens stored next to the raw text in the FTS index.
+/// Only CJK words contribute, since unicode61 already handles other scripts.
+pub fn index_text(text: &str) -> String {
+    split_words(text)
+        .into_iter()
+        .filter(|word| contains_cjk(word))
+        .flat_map(|word| word_tokens(&word).into_iter().skip(1))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_words_lowercases_and_drops_punctuation() {
+        assert_eq!(split_words("Hello, World! 2024"), vec!["hello", "world", "2024"]);
+        assert!(split_words("  --  ").is_empty());
+    }
+
+    #[test]
+    fn strip_particle_removes_the_longest_particle() {
+        assert_eq!(strip_particle("노트를"), "노트");
+        assert_eq!(strip_particle("서울에서는"), "서울");
+        assert_eq!(strip_particle("노트"), "노트");
+    }
+
+    #[test]
+    fn strip_particle_keeps_short_stems() {
+        // "사과" ends in "과", but stripping it would leave a single syllable
+        assert_eq!(strip_particle("사과"), "사과");
+    }
+
+    #[test]
+    fn bigrams_overlap() {
+        assert_eq!(bigrams("책노트"), vec!["책노", "노트"]);
+        assert!(bigrams("책").is_empty());
+    }
+
+    #[test]
+    fn tokenize_adds_stems_and_bigrams_for_cjk_only() {
+        assert_eq!(tokenize("Rust notes"), vec!["rust", "notes"]);
+        assert_eq!(tokenize("노트를"), vec!["노트를", "노트", "노트", "트를"]);
+    }
+
+    #[test]
+    fn index_text_skips_the_raw_words() {
+        assert_eq!(index_text("Rust 노트를"), "노트 노트 트를");
+        assert_eq!(index_text("Rust only"), "");
+    }
+
+    #[test]
+    fn is_cjk_covers_hangul_kana_and_ideographs() {
+        assert!(contains_cjk("한글"));
+        assert!(contains_cjk("カナ"));
+        assert!(contains_cjk("漢字"));
+        assert!(!contains_cjk("latin"));
+    }
+}
@@ -1,28 +1,74 @@
 use crate::domains::config::service::load_config;
+use crate::domains::document::embedding::{
+    configure_previous_provider, current_dimension, current_model_id, previous_model_id, ChunkEmbedding,
+};
 use crate::domains::document::error::DocumentError;
 use crate::domains::document::model::{
-    Block, Document, DocumentDeletedEvent, DocumentGraphInfo, DocumentUpdatedEvent, GraphData,
-    GraphEdge, GraphNode, SearchResult,
+    Backlink, Block, BlockHit, Document, DocumentDeletedEvent, DocumentGraphInfo,
+    DocumentListRequest, DocumentPage, DocumentUpdatedEvent, EdgeChangeInfo, EdgeExplanation, FailedBlock,
+    GraphData, GraphEdge, GraphNode, IndexingStats, ReindexProgress, ReindexScope, RelatedDocument, SearchResult,
+    VectorStorageBenchmark,
 };
+use crate::domains::config::model::VectorStorage;
 use crate::domains::document::repository;
-use crate::infrastructure::database::connection::create_connection;
-use crate::utils::error_logger::ResultExt;
+use crate::infrastructure::database::schema::{previous_vector_model, vector_layout};
+use crate::infrastructure::database::pool::{DatabaseState, PooledConnection};
+use crate::utils::error_logger::{log_error_with_trace, ResultExt};
 use crate::utils::file_system::create_directory;
 use anyhow::anyhow;
-use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+const MAX_BLOCK_HITS: usize = 3;
+/// Failed attempts before a block is set aside as FAILED.
+const MAX_INDEXING_ATTEMPTS: i64 = 5;
+/// Delay before the first retry, doubled on every further failure up to the maximum.
+const RETRY_BASE_DELAY_SECS: i64 = 30;
+const RETRY_MAX_DELAY_SECS: i64 = 3600;
+const MAX_FAILED_BLOCKS: i64 = 500;
+const DEFAULT_EVIDENCE_LIMIT: i64 = 5;
+const MAX_EVIDENCE_LIMIT: i64 = 50;
+const MAX_NEIGHBORHOOD_HOPS: u32 = 5;
+const DEFAULT_RELATED_DOCUMENTS: i64 = 10;
+const MAX_RELATED_DOCUMENTS: i64 = 100;
+const DEFAULT_BENCHMARK_SAMPLES: i64 = 50;
+const MAX_BENCHMARK_SAMPLES: i64 = 500;
+/// Neighbours compared per query when measuring recall.
+const BENCHMARK_NEIGHBOURS: i64 = 10;
 
 // ============================================
 // Connection Helper
 // ============================================
 
-fn get_connection(app_handle: &AppHandle) -> Result<Connection, DocumentError> {
+fn get_storage_path(app_handle: &AppHandle) -> Result<PathBuf, DocumentError> {
     let config =
-        load_config(app_handle).map_err_log("get_connection::load_config", DocumentError::ConfigLoadingError)?;
+        load_config(app_handle).map_err_log("get_storage_path::load_config", DocumentError::ConfigLoadingError)?;
+
+    Ok(PathBuf::from(config.storage_path.unwrap_or_default()))
+}
+
+/// Pooled read connection for the configured storage.
+fn get_connection(app_handle: &AppHandle) -> Result<PooledConnection, DocumentError> {
+    let storage_path = get_storage_path(app_handle)?;
+
+    app_handle
+        .state::<DatabaseState>()
+        .reader(&storage_path)
+        .map_err_log("get_connection::reader", DocumentError::DatabaseConnectionCreationError)
+}
 
-    create_connection(&PathBuf::from(config.storage_path.unwrap_or_default()))
-        .map_err_log("get_connection::create_connection", DocumentError::DatabaseConnectionCreationError)
+/// The single writer connection for the configured storage.
+fn get_writer(app_handle: &AppHandle) -> Result<PooledConnection, DocumentError> {
+    let storage_path = get_storage_path(app_handle)?;
+
+    app_handle
+        .state::<DatabaseState>()
+        .writer(&storage_path)
+        .map_err_log("get_writer::writer", DocumentError::DatabaseConnectionCreationError)
 }
 
 // ============================================
@@ -60,21 +106,240 @@ pub fn ensure_storage(path: &str, name: &str) -> Result<String, DocumentError> {
 pub fn get_document(app_handle: &AppHandle, document_id: &str) -> Result<Option<Document>, DocumentError> {
     let conn = get_connection(app_handle)?;
 
-    repository::find_document_by_id(&conn, document_id)
-        .map_err_log("get_document::find_document_by_id", DocumentError::DatabaseQueryError)
+    let mut document = repository::find_document_by_id(&conn, document_id)
+        .map_err_log("get_document::find_document_by_id", DocumentError::DatabaseQueryError)?;
+
+    // Embeds show their source as it is now; quotes are flagged when it changed
+    if let Some(document) = &mut document {
+        let sources: HashMap<String, (Option<String>, bool)> =
+            repository::find_block_sources(&conn, &document.id)
+                .map_err_log("get_document::find_block_sources", DocumentError::DatabaseQueryError)?
+                .into_iter()
+                .map(|(block_id, content, changed)| (block_id, (content, changed)))
+                .collect();
+
+        for block in &mut document.blocks {
+            if let Some((content, changed)) = sources.get(&block.id) {
+                if block.reference_kind.as_deref() == Some("embed") {
+                    block.content = content.clone();
+                }
+                block.source_changed = *changed;
+            }
+        }
+    }
+
+    Ok(document)
+}
+
+/// Checks a block's reference against its source before the block is saved. A
+/// reference to a missing block, or back to the block itself, becomes a plain
+/// block; one to another reference points at that reference's source instead;
+/// an embed takes the source's content.
+fn resolve_block_reference(conn: &rusqlite::Connection, block: &mut Block) -> Result<(), DocumentError> {
+    let Some(source_block_id) = block.source_block_id.clone() else {
+        block.source_document_id = None;
+        block.reference_kind = None;
+        return Ok(());
+    };
+
+    let mut source = repository::find_block_by_id(conn, &source_block_id)
+        .map_err_log("resolve_block_reference::find_source", DocumentError::DatabaseQueryError)?;
+    if let Some(origin_id) = source.as_ref().and_then(|source| source.source_block_id.clone()) {
+        source = repository::find_block_by_id(conn, &origin_id)
+            .map_err_log("resolve_block_reference::find_origin", DocumentError::DatabaseQueryError)?;
+    }
+
+    match source.filter(|source| source.id != block.id) {
+        Some(source) => {
+            if block.reference_kind.as_deref() != Some("quote") {
+                block.reference_kind = Some("embed".to_string());
+                block.content = source.content;
+            }
+            block.source_block_id = Some(source.id);
+            block.source_document_id = Some(source.document_id);
+        }
+        None => {
+            block.source_block_id = None;
+            block.source_document_id = None;
+            block.reference_kind = None;
+        }
+    }
+
+    Ok(())
 }
 
 pub fn save_document(app_handle: &AppHandle, document: &Document) -> Result<(), DocumentError> {
-    let conn = get_connection(app_handle)?;
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("save_document::begin_transaction", DocumentError::DatabaseQueryError)?;
+
+    let existing_blocks: HashMap<String, Option<String>> =
+        repository::find_blocks_by_document_id(&tx, &document.id)
+            .map_err_log("save_document::find_blocks", DocumentError::DatabaseQueryError)?
+            .into_iter()
+            .map(|block| (block.id, block.content))
+            .collect();
+    let previous_identity = repository::find_link_identity(&tx, &document.id)
+        .map_err_log("save_document::find_link_identity", DocumentError::DatabaseQueryError)?;
 
     // Save document
-    repository::upsert_document(&conn, document)
+    repository::upsert_document(&tx, document)
         .map_err_log("save_document::upsert_document", DocumentError::DatabaseQueryError)?;
 
-    // Save blocks
+    // Remove blocks that are no longer part of the document, noting the documents
+    // their recorded similarities tied this one to
+    let payload_block_ids: HashSet<&str> = document.blocks.iter().map(|b| b.id.as_str()).collect();
+    let mut similar_document_ids: HashSet<String> = HashSet::new();
+    for block_id in existing_blocks
+        .keys()
+        .filter(|id| !payload_block_ids.contains(id.as_str()))
+    {
+        similar_document_ids.extend(
+            repository::find_block_similarity_documents(&tx, block_id).map_err_log(
+                "save_document::find_block_similarity_documents",
+                DocumentError::DatabaseQueryError,
+            )?,
+        );
+
+        repository::detach_block_references(&tx, block_id)
+            .map_err_log("save_document::detach_block_references", DocumentError::DatabaseQueryError)?;
+
+        repository::delete_block_vector(&tx, block_id)
+            .map_err_log("save_document::delete_block_vector", DocumentError::DatabaseQueryError)?;
+
+        repository::delete_block(&tx, block_id)
+            .map_err_log("save_document::delete_block", DocumentError::DatabaseQueryError)?;
+    }
+
+    // Save blocks; only blocks whose content hash changed go back to PENDING
+    let content_changed = existing_blocks.len() != document.blocks.len()
+        || document
+            .blocks
+            .iter()
+            .any(|block| existing_blocks.get(&block.id) != Some(&block.content));
+    let mut saved_blocks: Vec<Block> = Vec::with_capacity(document.blocks.len());
+    let mut vectors_dropped = false;
     for block in &document.blocks {
-        repository::upsert_block(&conn, block)
+        let mut block = Block {
+            document_id: document.id.clone(),
+            ..block.clone()
+        };
+        resolve_block_reference(&tx, &mut block)?;
+
+        repository::upsert_block(&tx, &block)
             .map_err_log("save_document::upsert_block", DocumentError::DatabaseQueryError)?;
+
+        // Embeds and quotes are left out of the index, their source stands for them
+        if block.source_block_id.is_some() {
+            vectors_dropped |= repository::delete_reference_copy_vectors(&tx, &block.id).map_err_log(
+                "save_document::delete_reference_copy_vectors",
+                DocumentError::DatabaseQueryError,
+            )?;
+        }
+        saved_blocks.push(block);
+    }
+
+    // Similar edges the removed blocks alone supported go with them
+    similar_document_ids.remove(&document.id);
+    let similar_document_ids: Vec<String> = similar_document_ids.into_iter().collect();
+    let removed_similar_ids = repository::delete_unsupported_similar_edges(&tx, &document.id, &similar_document_ids)
+        .map_err_log("save_document::delete_unsupported_similar_edges", DocumentError::DatabaseQueryError)?;
+
+    // The document vector follows its blocks: removed ones drop out at once, and
+    // edited ones count with their new vectors as they are re-embedded
+    if content_changed || vectors_dropped {
+        repository::refresh_document_vector(&tx, &document.id)
+            .map_err_log("save_document::refresh_document_vector", DocumentError::DatabaseQueryError)?;
+    }
+
+    // Embeds follow the blocks just saved; a block that became a reference hands
+    // its own referrers over to its source
+    let mut dependent_document_ids: HashSet<String> = HashSet::new();
+    for block in &saved_blocks {
+        match (&block.source_block_id, &block.source_document_id) {
+            (Some(source_block_id), Some(source_document_id)) => {
+                repository::repoint_block_references(&tx, &block.id, source_block_id, source_document_id)
+                    .map_err_log("save_document::repoint_block_references", DocumentError::DatabaseQueryError)?;
+            }
+            _ => {
+                dependent_document_ids.extend(
+                    repository::propagate_block_content(&tx, &block.id)
+                        .map_err_log("save_document::propagate_block_content", DocumentError::DatabaseQueryError)?,
+                );
+            }
+        }
+    }
+    dependent_document_ids.remove(&document.id);
+    let mut dependent_events = Vec::with_capacity(dependent_document_ids.len());
+    for dependent_id in &dependent_document_ids {
+        let dependent = repository::find_document_by_id(&tx, dependent_id)
+            .map_err_log("save_document::find_dependent_document", DocumentError::DatabaseQueryError)?;
+        if let Some(dependent) = dependent {
+            dependent_events.push(DocumentUpdatedEvent {
+                document_id: dependent.id,
+                title: dependent.title,
+                tags: dependent.tags,
+                updated_at: dependent.updated_at.unwrap_or_default(),
+            });
+        }
+    }
+
+    let link_changes = repository::sync_document_links(&tx, &document.id)
+        .map_err_log("save_document::sync_document_links", DocumentError::DatabaseQueryError)?;
+
+    // Embedded content may carry links of its own
+    let mut dependent_link_changes = Vec::with_capacity(dependent_document_ids.len());
+    for dependent_id in dependent_document_ids {
+        let changes = repository::sync_document_links(&tx, &dependent_id)
+            .map_err_log("save_document::sync_dependent_links", DocumentError::DatabaseQueryError)?;
+        dependent_link_changes.push((dependent_id, changes));
+    }
+
+    // A new, renamed or (un)archived document changes what other documents' references
+    // resolve to
+    let relink = match &previous_identity {
+        None => true,
+        Some((title, status)) => *title != document.title || (*status == 99) != (document.status == 99),
+    };
+    if relink {
+        let previous_title = previous_identity.and_then(|(title, _)| title);
+        let names: Vec<String> = std::iter::once(document.id.clone())
+            .chain(previous_title)
+            .chain(document.title.clone())
+            .collect();
+        dependent_link_changes.extend(
+            repository::relink_references_to(&tx, &document.id, &names)
+                .map_err_log("save_document::relink_references_to", DocumentError::DatabaseQueryError)?,
+        );
+    }
+
+    tx.commit()
+        .map_err_log("save_document::commit", DocumentError::DatabaseQueryError)?;
+
+    emit_link_changes(app_handle, &document.id, link_changes);
+    for (dependent_id, changes) in dependent_link_changes {
+        emit_link_changes(app_handle, &dependent_id, changes);
+    }
+
+    if !removed_similar_ids.is_empty() {
+        let change_info = EdgeChangeInfo {
+            added_edges: Vec::new(),
+            removed_edges: removed_similar_ids
+                .into_iter()
+                .map(|target| GraphEdge {
+                    source: document.id.clone(),
+                    target,
+                    edge_type: "document-document".to_string(),
+                    relation_type: Some("similar".to_string()),
+                    weight: None,
+                    pinned: false,
+                    label: None,
+                    note: None,
+                })
+                .collect(),
+        };
+        let _ = app_handle.emit("graph-edge-changed", change_info);
     }
 
     let _ = app_handle.emit(
@@ -86,60 +351,346 @@ pub fn save_document(app_handle: &AppHandle, document: &Document) -> Result<(),
             updated_at: document.updated_at.clone().unwrap_or_default(),
         },
     );
+    for event in dependent_events {
+        let _ = app_handle.emit("document-updated", event);
+    }
 
     Ok(())
 }
 
+/// Lists documents one page at a time, sorted and filtered as requested.
+/// `query` accepts the search syntax (see `search::query::parse_query`); the cursor
+/// is opaque to the client and encodes the sort value and id of the last item.
 pub fn retrieve_document(
     app_handle: &AppHandle,
-    _query: Option<String>,
-    page: i64,
-) -> Result<Vec<Document>, DocumentError> {
+    request: &DocumentListRequest,
+) -> Result<DocumentPage, DocumentError> {
+    use crate::domains::document::search::query::parse_query;
+
+    let mut query = parse_query(request.query.as_deref().unwrap_or(""))
+        .map_err_log("retrieve_document::parse_query", DocumentError::InvalidQueryError)?;
+    if let Some(tag) = request.tag.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        query.tags.push(tag.to_lowercase());
+    }
+    if let Some(statuses) = &request.statuses {
+        query.statuses.extend(statuses);
+    }
+
+    let after = match &request.cursor {
+        Some(cursor) => Some(
+            serde_json::from_str::<(String, String)>(cursor).map_err_log(
+                "retrieve_document::parse_cursor",
+                |e| DocumentError::InvalidQueryError(format!("Invalid cursor: {}", e)),
+            )?,
+        ),
+        None => None,
+    };
+    let limit = request
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
     let conn = get_connection(app_handle)?;
-    let limit = 50;
-    let offset = (page - 1) * limit;
 
-    repository::find_documents(&conn, limit, offset)
-        .map_err_log("retrieve_document::find_documents", DocumentError::DatabaseQueryError)
+    // Fetch one extra row to know whether another page follows
+    let mut rows = repository::find_document_page(
+        &conn,
+        &query,
+        request.sort_by,
+        request.direction,
+        after,
+        limit + 1,
+    )
+    .map_err_log("retrieve_document::find_document_page", DocumentError::DatabaseQueryError)?;
+
+    let next_cursor = if rows.len() as i64 > limit {
+        rows.truncate(limit as usize);
+        rows.last()
+            .map(|(document, sort_value)| serde_json::json!([sort_value, document.id]).to_string())
+    } else {
+        None
+    };
+
+    let total_count = repository::count_documents(&conn, &query)
+        .map_err_log("retrieve_document::count_documents", DocumentError::DatabaseQueryError)?;
+
+    Ok(DocumentPage {
+        items: rows.into_iter().map(|(document, _)| document).collect(),
+        next_cursor,
+        total_count,
+    })
 }
 
-pub fn search_documents(
+/// Hybrid search: full-text and vector hits are merged with weighted reciprocal
+/// rank fusion, treating title, tag, content and semantic matches as separate rankings.
+/// The query may contain filters (see `search::query::parse_query`); a query made of
+/// filters only lists the matching documents.
+pub async fn search_documents(
     app_handle: &AppHandle,
     query: &str,
 ) -> Result<Vec<SearchResult>, DocumentError> {
-    use crate::domains::document::embedding::calculate_text_embedding;
-    use std::collections::HashSet;
+    use crate::domains::document::embedding_worker::{embed_query, EmbeddingPriority};
+    use crate::domains::document::search::fts::build_match_expression;
+    use crate::domains::document::search::query::parse_query;
+    use crate::domains::document::search::ranking::{reciprocal_rank_fusion, RankedList};
+
+    let parsed_query =
+        parse_query(query).map_err_log("search_documents::parse_query", DocumentError::InvalidQueryError)?;
+
+    // Embed the query first, on the embedding worker, ahead of background indexing.
+    // Each embedding is only compared against vectors of its own model, so blocks
+    // not re-embedded since a model change are searched with the previous model.
+    // If a provider fails, its vectors are left out rather than compared with a
+    // vector from another space.
+    let query_embeddings: Vec<(String, Vec<f32>)> = if parsed_query.has_text() {
+        match embed_query(parsed_query.semantic_text(), EmbeddingPriority::Interactive).await {
+            Ok(embeddings) => embeddings
+                .into_iter()
+                .filter_map(|(model_id, embedding)| match embedding {
+                    Ok(embedding) => Some((model_id, embedding)),
+                    Err(e) => {
+                        warn!("Query embedding with {} failed, skipping its vectors: {}", model_id, e);
+                        None
+                    }
+                })
+                .collect(),
+            Err(e) => {
+                warn!("Query embedding failed, searching by keyword only: {}", e);
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
 
     let conn = get_connection(app_handle)?;
     let sql_limit = 30;
     let vector_limit = 20;
 
-    // 1. SQL text search (title, tags, content)
-    let mut results = repository::search_documents(&conn, query, sql_limit)
-        .map_err_log("search_documents::sql_search", DocumentError::DatabaseQueryError)?;
-
-    // Track IDs already in results
-    let mut seen_ids: HashSet<String> = results.iter().map(|r| r.id.clone()).collect();
-
-    // 2. Vector similarity search
     let config = load_config(app_handle).map_err_log("search_documents::load_config", DocumentError::ConfigLoadingError)?;
-    let threshold = config.vector_settings.similarity_threshold;
+    let settings = &config.vector_settings;
+
+    let mut ranked_lists: Vec<RankedList> = Vec::new();
+    let mut hits: HashMap<(String, String), SearchResult> = HashMap::new();
+    let mut similarities: HashMap<String, f32> = HashMap::new();
+    let mut semantic_blocks: HashMap<String, Vec<BlockHit>> = HashMap::new();
+    let match_expression = build_match_expression(&parsed_query.text);
+
+    if parsed_query.has_text() {
+        // 1. Full-text search (title, tags, content)
+        let keyword_results = match &match_expression {
+            Some(match_expression) => {
+                repository::search_documents(&conn, match_expression, &parsed_query, sql_limit)
+                    .map_err_log("search_documents::sql_search", DocumentError::DatabaseQueryError)?
+            }
+            None => Vec::new(),
+        };
+
+        // 2. Vector similarity search, in the vectors of each model
+        let mut vector_results: Vec<SearchResult> = Vec::new();
+        for (model_id, query_embedding) in &query_embeddings {
+            vector_results.extend(
+                repository::search_by_vector(
+                    &conn,
+                    query_embedding,
+                    model_id,
+                    settings.similarity_threshold,
+                    &parsed_query,
+                    vector_limit,
+                )
+                .map_err_log("search_documents::vector_search", DocumentError::DatabaseQueryError)?,
+            );
+        }
+        let vector_results = merge_vector_results(vector_results, vector_limit as usize);
+
+        for signal in ["title", "tag", "content"] {
+            ranked_lists.push(RankedList {
+                signal: signal.to_string(),
+                weight: settings.keyword_weight,
+                ids: keyword_results
+                    .iter()
+                    .filter(|r| r.match_type == signal)
+                    .map(|r| r.id.clone())
+                    .collect(),
+            });
+        }
+        ranked_lists.push(RankedList {
+            signal: "semantic".to_string(),
+            weight: settings.semantic_weight,
+            ids: vector_results.iter().map(|r| r.id.clone()).collect(),
+        });
 
-    let embedding = calculate_text_embedding(query);
-    let vector_results = repository::search_by_vector(&conn, &embedding, threshold, vector_limit)
-        .map_err_log("search_documents::vector_search", DocumentError::DatabaseQueryError)?;
+        for result in keyword_results {
+            hits.insert((result.id.clone(), result.match_type.clone()), result);
+        }
+        for result in vector_results {
+            if let Some(similarity) = result.similarity_score {
+                similarities.insert(result.id.clone(), similarity);
+            }
+            semantic_blocks.insert(result.id.clone(), result.block_hits.clone());
+            hits.insert((result.id.clone(), "semantic".to_string()), result);
+        }
+    } else {
+        // Filters only: list matching documents
+        let filter_results = repository::search_documents_by_filter(&conn, &parsed_query, sql_limit)
+            .map_err_log("search_documents::filter_search", DocumentError::DatabaseQueryError)?;
+
+        ranked_lists.push(RankedList {
+            signal: "filter".to_string(),
+            weight: 1.0,
+            ids: filter_results.iter().map(|r| r.id.clone()).collect(),
+        });
+        for result in filter_results {
+            hits.insert((result.id.clone(), "filter".to_string()), result);
+        }
+    }
 
-    // 3. Merge results: add vector results that aren't already in SQL results
-    for vector_result in vector_results {
-        if !seen_ids.contains(&vector_result.id) {
-            seen_ids.insert(vector_result.id.clone());
-            results.push(vector_result);
+    // 3. Fuse the per-signal rankings
+    let fused = reciprocal_rank_fusion(&ranked_lists, settings.rrf_k);
+
+    // 4. Report similarity for documents only found by keyword
+    let keyword_only_ids: Vec<String> = fused
+        .iter()
+        .filter(|rank| !similarities.contains_key(&rank.id))
+        .map(|rank| rank.id.clone())
+        .collect();
+    if !keyword_only_ids.is_empty() {
+        for (model_id, embedding) in &query_embeddings {
+            let distances = repository::find_document_distances(&conn, embedding, model_id, &keyword_only_ids)
+                .map_err_log("search_documents::find_document_distances", DocumentError::DatabaseQueryError)?;
+            for (document_id, distance) in distances {
+                let similarity = similarities.entry(document_id).or_insert(1.0 - distance);
+                *similarity = similarity.max(1.0 - distance);
+            }
         }
     }
 
+    // 5. Find the blocks to jump to
+    let document_ids: Vec<String> = fused.iter().map(|rank| rank.id.clone()).collect();
+    let mut block_hits = collect_block_hits(
+        &conn,
+        match_expression.as_deref(),
+        &query_embeddings,
+        &document_ids,
+        semantic_blocks,
+    )?;
+
+    // 6. Represent each document by the hit of its strongest signal
+    let results = fused
+        .into_iter()
+        .filter_map(|rank| {
+            let best_signal = rank.signals.first()?.clone();
+            let mut result = hits.remove(&(rank.id.clone(), best_signal))?;
+            result.similarity_score = similarities.get(&rank.id).copied();
+            result.score = rank.score;
+            result.block_hits = block_hits.remove(&rank.id).unwrap_or_default();
+            result.matched_signals = rank.signals;
+            Some(result)
+        })
+        .collect();
+
     Ok(results)
 }
 
+/// Keeps the closest vector match of each document, since a document being
+/// re-embedded after a model change can match in the vectors of both models.
+/// Returns at most `limit` results, most similar first.
+fn merge_vector_results(results: Vec<SearchResult>, limit: usize) -> Vec<SearchResult> {
+    let mut best: HashMap<String, SearchResult> = HashMap::new();
+    for result in results {
+        let similarity = result.similarity_score.unwrap_or(f32::MIN);
+        match best.get(&result.id) {
+            Some(kept) if kept.similarity_score.unwrap_or(f32::MIN) >= similarity => {}
+            _ => {
+                best.insert(result.id.clone(), result);
+            }
+        }
+    }
+
+    let mut merged: Vec<SearchResult> = best.into_values().collect();
+    merged.sort_by(|a, b| {
+        b.similarity_score
+            .unwrap_or(f32::MIN)
+            .total_cmp(&a.similarity_score.unwrap_or(f32::MIN))
+    });
+    merged.truncate(limit);
+    merged
+}
+
+/// Matching blocks per document: keyword matches first (best BM25 first), then
+/// semantically close blocks, at most `MAX_BLOCK_HITS` each. Keyword matches get
+/// their similarity from the query embedding of their vectors' model, when the
+/// block has vectors.
+fn collect_block_hits(
+    conn: &PooledConnection,
+    match_expression: Option<&str>,
+    query_embeddings: &[(String, Vec<f32>)],
+    document_ids: &[String],
+    mut semantic_blocks: HashMap<String, Vec<BlockHit>>,
+) -> Result<HashMap<String, Vec<BlockHit>>, DocumentError> {
+    let mut block_hits: HashMap<String, Vec<BlockHit>> = HashMap::new();
+    if document_ids.is_empty() {
+        return Ok(block_hits);
+    }
+
+    if let Some(match_expression) = match_expression {
+        let keyword_blocks = repository::find_matching_blocks(
+            conn,
+            match_expression,
+            document_ids,
+            MAX_BLOCK_HITS as i64,
+        )
+        .map_err_log("collect_block_hits::find_matching_blocks", DocumentError::DatabaseQueryError)?;
+        for (document_id, hit) in keyword_blocks {
+            block_hits.entry(document_id).or_default().push(hit);
+        }
+    }
+
+    // Similarity of keyword-matched blocks, reusing the vector search where possible
+    let known_similarities: HashMap<String, f32> = semantic_blocks
+        .values()
+        .flatten()
+        .filter_map(|hit| Some((hit.block_id.clone(), hit.similarity_score?)))
+        .collect();
+    let mut missing_ids: Vec<String> = Vec::new();
+    for hit in block_hits.values_mut().flatten() {
+        match known_similarities.get(&hit.block_id) {
+            Some(similarity) => hit.similarity_score = Some(*similarity),
+            None => missing_ids.push(hit.block_id.clone()),
+        }
+    }
+    if !missing_ids.is_empty() {
+        let mut distances: HashMap<String, f32> = HashMap::new();
+        for (model_id, embedding) in query_embeddings {
+            for (block_id, distance) in repository::find_block_distances(conn, embedding, model_id, &missing_ids)
+                .map_err_log("collect_block_hits::find_block_distances", DocumentError::DatabaseQueryError)?
+            {
+                let closest = distances.entry(block_id).or_insert(distance);
+                *closest = closest.min(distance);
+            }
+        }
+        for hit in block_hits.values_mut().flatten() {
+            if let Some(distance) = distances.get(&hit.block_id) {
+                hit.similarity_score = Some(1.0 - distance);
+            }
+        }
+    }
+
+    for document_id in document_ids {
+        let hits = block_hits.entry(document_id.clone()).or_default();
+        for hit in semantic_blocks.remove(document_id).unwrap_or_default() {
+            if hits.len() >= MAX_BLOCK_HITS {
+                break;
+            }
+            if !hits.iter().any(|h| h.block_id == hit.block_id) {
+                hits.push(hit);
+            }
+        }
+    }
+
+    Ok(block_hits)
+}
+
 // ============================================
 // Block Service
 // ============================================
@@ -152,50 +703,98 @@ pub fn get_block(app_handle: &AppHandle, block_id: &str) -> Result<Option<Block>
 }
 
 pub fn delete_block(app_handle: &AppHandle, block_id: &str) -> Result<(), DocumentError> {
-    let conn = get_connection(app_handle)?;
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("delete_block::begin_transaction", DocumentError::DatabaseQueryError)?;
+
+    let block = repository::find_block_by_id(&tx, block_id)
+        .map_err_log("delete_block::find_block_by_id", DocumentError::DatabaseQueryError)?;
 
     // Delete edges where this block is the source
-    repository::delete_edges_by_source(&conn, block_id)
+    repository::delete_edges_by_source(&tx, block_id)
         .map_err_log("delete_block::delete_edges_by_source", DocumentError::DatabaseQueryError)?;
 
     // Delete edges where this block is the target
-    repository::delete_edges_by_target(&conn, block_id)
+    repository::delete_edges_by_target(&tx, block_id)
         .map_err_log("delete_block::delete_edges_by_target", DocumentError::DatabaseQueryError)?;
 
+    // Blocks embedding or quoting this one keep their last content
+    repository::detach_block_references(&tx, block_id)
+        .map_err_log("delete_block::detach_block_references", DocumentError::DatabaseQueryError)?;
+
     // Delete block vector
-    repository::delete_block_vector(&conn, block_id)
+    repository::delete_block_vector(&tx, block_id)
         .map_err_log("delete_block::delete_block_vector", DocumentError::DatabaseQueryError)?;
 
     // Delete the block itself
-    repository::delete_block(&conn, block_id)
+    repository::delete_block(&tx, block_id)
         .map_err_log("delete_block::delete_block", DocumentError::DatabaseQueryError)?;
 
+    // The document vector and links no longer include the block
+    let mut link_changes = None;
+    if let Some(block) = &block {
+        repository::refresh_document_vector(&tx, &block.document_id)
+            .map_err_log("delete_block::refresh_document_vector", DocumentError::DatabaseQueryError)?;
+
+        link_changes = Some(
+            repository::sync_document_links(&tx, &block.document_id)
+                .map_err_log("delete_block::sync_document_links", DocumentError::DatabaseQueryError)?,
+        );
+    }
+
+    tx.commit()
+        .map_err_log("delete_block::commit", DocumentError::DatabaseQueryError)?;
+
+    if let (Some(block), Some(link_changes)) = (block, link_changes) {
+        emit_link_changes(app_handle, &block.document_id, link_changes);
+    }
+
     Ok(())
 }
 
 pub fn delete_document(app_handle: &AppHandle, document_id: &str) -> Result<(), DocumentError> {
-    let conn = get_connection(app_handle)?;
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("delete_document::begin_transaction", DocumentError::DatabaseQueryError)?;
 
-    let blocks = repository::find_blocks_by_document_id(&conn, document_id)
+    let blocks = repository::find_blocks_by_document_id(&tx, document_id)
         .map_err_log("delete_document::find_blocks", DocumentError::DatabaseQueryError)?;
 
     for block in &blocks {
-        repository::delete_edges_by_source(&conn, &block.id)
+        repository::delete_edges_by_source(&tx, &block.id)
             .map_err_log("delete_document::delete_edges_source", DocumentError::DatabaseQueryError)?;
 
-        repository::delete_edges_by_target(&conn, &block.id)
+        repository::delete_edges_by_target(&tx, &block.id)
             .map_err_log("delete_document::delete_edges_target", DocumentError::DatabaseQueryError)?;
 
-        repository::delete_block_vector(&conn, &block.id)
+        repository::detach_block_references(&tx, &block.id)
+            .map_err_log("delete_document::detach_block_references", DocumentError::DatabaseQueryError)?;
+
+        repository::delete_block_vector(&tx, &block.id)
             .map_err_log("delete_document::delete_vector", DocumentError::DatabaseQueryError)?;
     }
 
-    repository::delete_blocks_by_document_id(&conn, document_id)
+    // Document-level edges reference documents(id) and would block the delete
+    repository::delete_edges_by_source(&tx, document_id)
+        .map_err_log("delete_document::delete_document_edges_source", DocumentError::DatabaseQueryError)?;
+
+    repository::delete_edges_by_target(&tx, document_id)
+        .map_err_log("delete_document::delete_document_edges_target", DocumentError::DatabaseQueryError)?;
+
+    repository::delete_document_vector(&tx, document_id)
+        .map_err_log("delete_document::delete_document_vector", DocumentError::DatabaseQueryError)?;
+
+    repository::delete_blocks_by_document_id(&tx, document_id)
         .map_err_log("delete_document::delete_blocks", DocumentError::DatabaseQueryError)?;
 
-    repository::delete_document(&conn, document_id)
+    repository::delete_document(&tx, document_id)
         .map_err_log("delete_document::delete_document", DocumentError::DatabaseQueryError)?;
 
+    tx.commit()
+        .map_err_log("delete_document::commit", DocumentError::DatabaseQueryError)?;
+
     let _ = app_handle.emit(
         "document-deleted",
         DocumentDeletedEvent {
@@ -210,11 +809,41 @@ pub fn delete_document(app_handle: &AppHandle, document_id: &str) -> Result<(),
 // Indexing Service
 // ============================================
 
-pub fn get_oldest_pending_block(app_handle: &AppHandle) -> Result<Option<Block>, DocumentError> {
+pub fn get_oldest_pending_blocks(
+    app_handle: &AppHandle,
+    limit: i64,
+) -> Result<Vec<Block>, DocumentError> {
     let conn = get_connection(app_handle)?;
 
-    repository::find_oldest_pending_block(&conn)
-        .map_err_log("get_oldest_pending_block::find", DocumentError::DatabaseQueryError)
+    repository::find_oldest_pending_blocks(&conn, &current_model_id(), limit)
+        .map_err_log("get_oldest_pending_blocks::find", DocumentError::DatabaseQueryError)
+}
+
+/// Marks blocks as indexed, given (block_id, indexed_hash) pairs, in one transaction.
+pub fn mark_blocks_indexed(
+    app_handle: &AppHandle,
+    blocks: &[(String, String)],
+    model_id: &str,
+) -> Result<(), DocumentError> {
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("mark_blocks_indexed::begin_transaction", DocumentError::DatabaseQueryError)?;
+
+    for (block_id, indexed_hash) in blocks {
+        repository::mark_block_indexed(&tx, block_id, indexed_hash, model_id)
+            .map_err_log("mark_blocks_indexed::mark", DocumentError::DatabaseQueryError)?;
+    }
+
+    tx.commit()
+        .map_err_log("mark_blocks_indexed::commit", DocumentError::DatabaseQueryError)
+}
+
+pub fn get_indexing_stats(app_handle: &AppHandle) -> Result<IndexingStats, DocumentError> {
+    let conn = get_connection(app_handle)?;
+
+    repository::count_indexing_stats(&conn, &current_model_id())
+        .map_err_log("get_indexing_stats", DocumentError::DatabaseQueryError)
 }
 
 pub fn update_block_indexing_status(
@@ -222,44 +851,200 @@ pub fn update_block_indexing_status(
     block_id: &str,
     status: i16,
 ) -> Result<(), DocumentError> {
-    let conn = get_connection(app_handle)?;
+    let conn = get_writer(app_handle)?;
 
     repository::update_block_indexing_status(&conn, block_id, status)
         .map_err_log("update_block_indexing_status", DocumentError::DatabaseQueryError)
 }
 
-pub fn save_block_vector(
+/// Records a failed indexing attempt for each (block_id, error) pair, in one
+/// transaction. Blocks back off exponentially and become FAILED after
+/// `MAX_INDEXING_ATTEMPTS`, so one bad block can't hold up the queue.
+pub fn record_indexing_failures(
     app_handle: &AppHandle,
-    block_id: &str,
-    embedding: &[f32],
+    failures: &[(&str, String)],
+) -> Result<(), DocumentError> {
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("record_indexing_failures::begin_transaction", DocumentError::DatabaseQueryError)?;
+
+    for (block_id, error) in failures {
+        repository::record_indexing_failure(
+            &tx,
+            block_id,
+            error,
+            MAX_INDEXING_ATTEMPTS,
+            RETRY_BASE_DELAY_SECS,
+            RETRY_MAX_DELAY_SECS,
+        )
+        .map_err_log("record_indexing_failures::record", DocumentError::DatabaseQueryError)?;
+    }
+
+    tx.commit()
+        .map_err_log("record_indexing_failures::commit", DocumentError::DatabaseQueryError)
+}
+
+pub fn list_failed_blocks(app_handle: &AppHandle) -> Result<Vec<FailedBlock>, DocumentError> {
+    let conn = get_connection(app_handle)?;
+
+    repository::find_failed_blocks(&conn, MAX_FAILED_BLOCKS)
+        .map_err_log("list_failed_blocks", DocumentError::DatabaseQueryError)
+}
+
+/// Queues FAILED blocks for indexing again with a fresh attempt count: all of
+/// them, or only `block_ids`. Returns the number of blocks requeued.
+pub fn retry_failed_blocks(
+    app_handle: &AppHandle,
+    block_ids: Option<&[String]>,
+) -> Result<u64, DocumentError> {
+    let conn = get_writer(app_handle)?;
+
+    repository::reset_failed_indexing_status(&conn, block_ids)
+        .map_err_log("retry_failed_blocks", DocumentError::DatabaseQueryError)
+}
+
+/// Replaces the vectors of several blocks, given (block_id, chunks) pairs, in one transaction.
+pub fn save_block_vectors(
+    app_handle: &AppHandle,
+    blocks: &[(&str, &[ChunkEmbedding])],
+    model_id: &str,
 ) -> Result<(), DocumentError> {
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("save_block_vectors::begin_transaction", DocumentError::DatabaseQueryError)?;
+
+    for (block_id, chunks) in blocks {
+        repository::replace_block_vectors(&tx, block_id, chunks, model_id)
+            .map_err_log("save_block_vectors::replace", DocumentError::DatabaseQueryError)?;
+    }
+
+    tx.commit()
+        .map_err_log("save_block_vectors::commit", DocumentError::DatabaseQueryError)
+}
+
+/// Recomputes the vectors of the given documents from their chunk vectors, in one transaction.
+pub fn refresh_document_vectors(
+    app_handle: &AppHandle,
+    document_ids: &[String],
+) -> Result<(), DocumentError> {
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("refresh_document_vectors::begin_transaction", DocumentError::DatabaseQueryError)?;
+
+    for document_id in document_ids {
+        repository::refresh_document_vector(&tx, document_id)
+            .map_err_log("refresh_document_vectors::refresh", DocumentError::DatabaseQueryError)?;
+    }
+
+    tx.commit()
+        .map_err_log("refresh_document_vectors::commit", DocumentError::DatabaseQueryError)
+}
+
+/// Computes the vectors of up to `limit` documents that have block vectors of the
+/// current model but no document vector yet, and returns how many were computed.
+pub fn backfill_document_vectors(app_handle: &AppHandle, limit: i64) -> Result<usize, DocumentError> {
+    let model_id = current_model_id();
+    let document_ids = {
+        let conn = get_connection(app_handle)?;
+        repository::find_documents_without_vector(&conn, &model_id, limit)
+            .map_err_log("backfill_document_vectors::find", DocumentError::DatabaseQueryError)?
+    };
+    if document_ids.is_empty() {
+        return Ok(0);
+    }
+
+    refresh_document_vectors(app_handle, &document_ids)?;
+    Ok(document_ids.len())
+}
+
+/// The given documents that still have blocks to re-embed after a model change.
+pub fn find_documents_with_previous_vectors(
+    app_handle: &AppHandle,
+    model_id: &str,
+    document_ids: &[String],
+) -> Result<Vec<String>, DocumentError> {
+    let conn = get_connection(app_handle)?;
+    repository::find_documents_with_previous_vectors(&conn, model_id, document_ids)
+        .map_err_log("find_documents_with_previous_vectors", DocumentError::DatabaseQueryError)
+}
+
+/// Drops the provider kept for the previous model, and the vectors blocks were
+/// re-embedded over, once every block is re-embedded.
+pub fn release_previous_provider(app_handle: &AppHandle) -> Result<(), DocumentError> {
+    let Some(previous_model) = previous_model_id() else {
+        return Ok(());
+    };
+    let conn = get_writer(app_handle)?;
+    let remaining = previous_vector_model(&conn, &current_model_id())
+        .map_err_log("release_previous_provider::previous_vector_model", DocumentError::DatabaseQueryError)?;
+
+    if remaining.is_none() {
+        let deleted = repository::delete_superseded_vectors(&conn).map_err_log(
+            "release_previous_provider::delete_superseded_vectors",
+            DocumentError::DatabaseQueryError,
+        )?;
+        info!("Re-embedding finished, dropped {} vectors of {}", deleted, previous_model);
+    }
+    if remaining.as_deref() != Some(previous_model.as_str()) {
+        configure_previous_provider(app_handle, remaining.as_deref(), current_dimension());
+    }
+    Ok(())
+}
+
+/// Documents nearest to `document_id` by document vector, for "related notes".
+pub fn find_similar_documents(
+    app_handle: &AppHandle,
+    document_id: &str,
+    limit: Option<i64>,
+) -> Result<Vec<RelatedDocument>, DocumentError> {
+    let conn = get_connection(app_handle)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_RELATED_DOCUMENTS)
+        .clamp(1, MAX_RELATED_DOCUMENTS);
+
+    repository::find_similar_documents(&conn, document_id, &current_model_id(), limit)
+        .map_err_log("find_similar_documents", DocumentError::DatabaseQueryError)
+}
+
+/// Returns (document_id, distance) between the vector of `document_id` and those of the given documents.
+pub fn find_document_vector_distances(
+    app_handle: &AppHandle,
+    document_id: &str,
+    model_id: &str,
+    document_ids: &[String],
+) -> Result<Vec<(String, f32)>, DocumentError> {
     let conn = get_connection(app_handle)?;
 
-    repository::upsert_block_vector(&conn, block_id, embedding)
-        .map_err_log("save_block_vector::upsert", DocumentError::DatabaseQueryError)
+    repository::find_document_vector_distances(&conn, document_id, model_id, document_ids)
+        .map_err_log("find_document_vector_distances", DocumentError::DatabaseQueryError)
 }
 
 pub fn find_similar_blocks(
     app_handle: &AppHandle,
     embedding: &[f32],
+    model_id: &str,
     threshold: f32,
     limit: i64,
 ) -> Result<Vec<(String, f32)>, DocumentError> {
     let conn = get_connection(app_handle)?;
 
-    repository::find_similar_blocks(&conn, embedding, threshold, limit)
+    repository::find_similar_blocks(&conn, embedding, model_id, threshold, limit)
         .map_err_log("find_similar_blocks", DocumentError::DatabaseQueryError)
 }
 
 pub fn find_similar_blocks_with_document(
     app_handle: &AppHandle,
     embedding: &[f32],
+    model_id: &str,
     threshold: f32,
     limit: i64,
-) -> Result<Vec<(String, String, f32)>, DocumentError> {
+) -> Result<Vec<(String, String, i64, f32)>, DocumentError> {
     let conn = get_connection(app_handle)?;
 
-    repository::find_similar_blocks_with_document(&conn, embedding, threshold, limit)
+    repository::find_similar_blocks_with_document(&conn, embedding, model_id, threshold, limit)
         .map_err_log("find_similar_blocks_with_document", DocumentError::DatabaseQueryError)
 }
 
@@ -271,10 +1056,10 @@ pub fn create_edge(
     app_handle: &AppHandle,
     source_id: &str,
     target_id: &str,
-    relation_type: Option<&str>,
+    relation_type: &str,
     weight: f64,
 ) -> Result<(), DocumentError> {
-    let conn = get_connection(app_handle)?;
+    let conn = get_writer(app_handle)?;
 
     repository::upsert_edge(&conn, source_id, target_id, relation_type, weight)
         .map_err_log("create_edge::upsert_edge", DocumentError::DatabaseQueryError)
@@ -295,7 +1080,7 @@ pub fn delete_edge(
     source_id: &str,
     target_id: &str,
 ) -> Result<(), DocumentError> {
-    let conn = get_connection(app_handle)?;
+    let conn = get_writer(app_handle)?;
 
     repository::delete_edge(&conn, source_id, target_id)
         .map_err_log("delete_edge", DocumentError::DatabaseQueryError)
@@ -304,10 +1089,11 @@ pub fn delete_edge(
 pub fn find_related_documents(
     app_handle: &AppHandle,
     document_id: &str,
+    relation_type: &str,
 ) -> Result<Vec<(String, f64)>, DocumentError> {
     let conn = get_connection(app_handle)?;
 
-    repository::find_related_documents_bidirectional(&conn, document_id)
+    repository::find_related_documents_bidirectional(&conn, document_id, relation_type)
         .map_err_log("find_related_documents", DocumentError::DatabaseQueryError)
 }
 
@@ -315,36 +1101,627 @@ pub fn delete_edge_bidirectional(
     app_handle: &AppHandle,
     source_id: &str,
     target_id: &str,
+    relation_type: &str,
 ) -> Result<(), DocumentError> {
-    let conn = get_connection(app_handle)?;
+    let conn = get_writer(app_handle)?;
 
-    repository::delete_edge_bidirectional(&conn, source_id, target_id)
+    repository::delete_edge_bidirectional(&conn, source_id, target_id, relation_type)
         .map_err_log("delete_edge_bidirectional", DocumentError::DatabaseQueryError)
 }
 
+// ============================================
+// Edge Curation Service
+// ============================================
+
+/// Pins the similar edge between two documents so the scheduler never removes it,
+/// creating it (weighted by document similarity) if there is none, and unblocking
+/// the pair. Unpinning hands the edge back to the scheduler, unless it has a label
+/// or note, and fails with `EdgeNotFoundError` when there is no edge.
+pub fn pin_edge(
+    app_handle: &AppHandle,
+    source_id: &str,
+    target_id: &str,
+    pinned: bool,
+) -> Result<(), DocumentError> {
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("pin_edge::begin_transaction", DocumentError::DatabaseQueryError)?;
+
+    let mut added_edge = None;
+    let changed = repository::set_edge_pinned(&tx, source_id, target_id, pinned)
+        .map_err_log("pin_edge::set_edge_pinned", DocumentError::DatabaseQueryError)?;
+    if !pinned && changed == 0 {
+        let err = DocumentError::EdgeNotFoundError(format!("{} <-> {} (similar)", source_id, target_id));
+        log_error_with_trace("pin_edge::edge_exists", &err);
+        return Err(err);
+    }
+    if pinned {
+        repository::delete_blocked_edge(&tx, source_id, target_id)
+            .map_err_log("pin_edge::delete_blocked_edge", DocumentError::DatabaseQueryError)?;
+
+        if changed == 0 {
+            let weight = repository::find_document_vector_distances(
+                &tx,
+                source_id,
+                &current_model_id(),
+                &[target_id.to_string()],
+            )
+            .map_err_log("pin_edge::find_document_vector_distances", DocumentError::DatabaseQueryError)?
+            .first()
+            .map(|(_, distance)| (1.0 - *distance as f64).clamp(0.0, 1.0))
+            .unwrap_or(1.0);
+
+            repository::upsert_edge(&tx, source_id, target_id, "similar", weight)
+                .map_err_log("pin_edge::upsert_edge", DocumentError::DatabaseQueryError)?;
+            repository::set_edge_pinned(&tx, source_id, target_id, true)
+                .map_err_log("pin_edge::set_new_edge_pinned", DocumentError::DatabaseQueryError)?;
+            added_edge = Some(weight);
+        }
+    }
+
+    tx.commit()
+        .map_err_log("pin_edge::commit", DocumentError::DatabaseQueryError)?;
+
+    if let Some(weight) = added_edge {
+        let change_info = EdgeChangeInfo {
+            added_edges: vec![GraphEdge {
+                source: source_id.to_string(),
+                target: target_id.to_string(),
+                edge_type: "document-document".to_string(),
+                relation_type: Some("similar".to_string()),
+                weight: Some(weight),
+                pinned: true,
+                label: None,
+                note: None,
+            }],
+            removed_edges: Vec::new(),
+        };
+        let _ = app_handle.emit("graph-edge-changed", change_info);
+    }
+
+    Ok(())
+}
+
+/// Blocks a document pair: removes the similar edges between the two, pinned or not,
+/// and keeps the scheduler from linking them again. `[[links]]` are left alone.
+pub fn block_edge(app_handle: &AppHandle, source_id: &str, target_id: &str) -> Result<(), DocumentError> {
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("block_edge::begin_transaction", DocumentError::DatabaseQueryError)?;
+
+    repository::insert_blocked_edge(&tx, source_id, target_id)
+        .map_err_log("block_edge::insert_blocked_edge", DocumentError::DatabaseQueryError)?;
+
+    repository::delete_edge_bidirectional(&tx, source_id, target_id, "similar")
+        .map_err_log("block_edge::delete_edge_bidirectional", DocumentError::DatabaseQueryError)?;
+
+    tx.commit()
+        .map_err_log("block_edge::commit", DocumentError::DatabaseQueryError)?;
+
+    let removed_edge = |source: &str, target: &str| GraphEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        edge_type: "document-document".to_string(),
+        relation_type: Some("similar".to_string()),
+        weight: None,
+        pinned: false,
+        label: None,
+        note: None,
+    };
+    let change_info = EdgeChangeInfo {
+        added_edges: Vec::new(),
+        removed_edges: vec![removed_edge(source_id, target_id), removed_edge(target_id, source_id)],
+    };
+    let _ = app_handle.emit("graph-edge-changed", change_info);
+
+    Ok(())
+}
+
+/// Lets the scheduler link a blocked pair again, once either document is re-indexed.
+pub fn unblock_edge(app_handle: &AppHandle, source_id: &str, target_id: &str) -> Result<(), DocumentError> {
+    let conn = get_writer(app_handle)?;
+
+    repository::delete_blocked_edge(&conn, source_id, target_id)
+        .map_err_log("unblock_edge::delete_blocked_edge", DocumentError::DatabaseQueryError)
+}
+
+/// Sets the label and note of an edge; blank values clear them.
+pub fn annotate_edge(
+    app_handle: &AppHandle,
+    source_id: &str,
+    target_id: &str,
+    relation_type: &str,
+    label: Option<&str>,
+    note: Option<&str>,
+) -> Result<(), DocumentError> {
+    let conn = get_writer(app_handle)?;
+
+    let label = label.map(str::trim).filter(|s| !s.is_empty());
+    let note = note.map(str::trim).filter(|s| !s.is_empty());
+    let changed = repository::annotate_edge(&conn, source_id, target_id, relation_type, label, note)
+        .map_err_log("annotate_edge::annotate_edge", DocumentError::DatabaseQueryError)?;
+
+    if changed == 0 {
+        let err = DocumentError::EdgeNotFoundError(format!(
+            "{} -> {} ({})",
+            source_id, target_id, relation_type
+        ));
+        log_error_with_trace("annotate_edge::edge_exists", &err);
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+/// Manual overrides for the scheduler: the documents joined to `document_id` by a
+/// pinned or annotated edge, and those it must not be linked to.
+pub fn find_edge_overrides(
+    app_handle: &AppHandle,
+    document_id: &str,
+) -> Result<(Vec<String>, Vec<String>), DocumentError> {
+    let conn = get_connection(app_handle)?;
+
+    let curated = repository::find_curated_documents(&conn, document_id)
+        .map_err_log("find_edge_overrides::find_curated_documents", DocumentError::DatabaseQueryError)?;
+    let blocked = repository::find_blocked_documents(&conn, document_id)
+        .map_err_log("find_edge_overrides::find_blocked_documents", DocumentError::DatabaseQueryError)?;
+
+    Ok((curated, blocked))
+}
+
+// ============================================
+// Edge Evidence Service
+// ============================================
+
+/// Records the blocks of other documents an indexed block was found similar to,
+/// see `repository::BlockSimilarity`.
+pub fn record_block_similarities(
+    app_handle: &AppHandle,
+    block_id: &str,
+    similar: &[repository::BlockSimilarity],
+) -> Result<(), DocumentError> {
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("record_block_similarities::begin_transaction", DocumentError::DatabaseQueryError)?;
+
+    repository::replace_block_similarities(&tx, block_id, similar)
+        .map_err_log("record_block_similarities::replace_block_similarities", DocumentError::DatabaseQueryError)?;
+
+    tx.commit()
+        .map_err_log("record_block_similarities::commit", DocumentError::DatabaseQueryError)
+}
+
+/// Explains the connection between two documents with the block pairs behind it,
+/// or their closest chunks for an edge drawn from the document vectors, so the
+/// user can judge it and tune `similarity_threshold`.
+pub fn explain_edge(
+    app_handle: &AppHandle,
+    source_id: &str,
+    target_id: &str,
+    limit: Option<i64>,
+) -> Result<EdgeExplanation, DocumentError> {
+    let conn = get_connection(app_handle)?;
+    let limit = limit.unwrap_or(DEFAULT_EVIDENCE_LIMIT).clamp(1, MAX_EVIDENCE_LIMIT);
+
+    let config = load_config(app_handle)
+        .map_err_log("explain_edge::load_config", DocumentError::ConfigLoadingError)?;
+
+    let edge = repository::find_similar_edge(&conn, source_id, target_id)
+        .map_err_log("explain_edge::find_similar_edge", DocumentError::DatabaseQueryError)?;
+    let document_similarity = repository::find_document_vector_distances(
+        &conn,
+        source_id,
+        &current_model_id(),
+        &[target_id.to_string()],
+    )
+    .map_err_log("explain_edge::find_document_vector_distances", DocumentError::DatabaseQueryError)?
+    .first()
+    .map(|(_, distance)| (1.0 - *distance).clamp(0.0, 1.0));
+    let mut block_pairs = repository::find_edge_evidence(&conn, source_id, target_id, limit)
+        .map_err_log("explain_edge::find_edge_evidence", DocumentError::DatabaseQueryError)?;
+
+    // An edge drawn from the document vectors has no similar blocks behind it, so
+    // its closest chunks are measured instead
+    let block_pairs_measured = block_pairs.is_empty() && document_similarity.is_some();
+    if block_pairs_measured {
+        block_pairs = repository::find_closest_chunk_pairs(&conn, source_id, target_id, &current_model_id(), limit)
+            .map_err_log("explain_edge::find_closest_chunk_pairs", DocumentError::DatabaseQueryError)?;
+    }
+
+    Ok(EdgeExplanation {
+        source_id: source_id.to_string(),
+        target_id: target_id.to_string(),
+        weight: edge.map(|(weight, _)| weight),
+        pinned: edge.is_some_and(|(_, pinned)| pinned),
+        document_similarity,
+        similarity_threshold: config.vector_settings.similarity_threshold,
+        block_pairs,
+        block_pairs_measured,
+    })
+}
+
+// ============================================
+// Link Service
+// ============================================
+
+/// Tells the graph about `link` edges added and removed from a document.
+fn emit_link_changes(
+    app_handle: &AppHandle,
+    document_id: &str,
+    (added, removed): (Vec<String>, Vec<String>),
+) {
+    if added.is_empty() && removed.is_empty() {
+        return;
+    }
+
+    let link_edge = |target: String, weight: Option<f64>| GraphEdge {
+        source: document_id.to_string(),
+        target,
+        edge_type: "document-document".to_string(),
+        relation_type: Some("link".to_string()),
+        weight,
+        pinned: false,
+        label: None,
+        note: None,
+    };
+    let change_info = EdgeChangeInfo {
+        added_edges: added.into_iter().map(|target| link_edge(target, Some(1.0))).collect(),
+        removed_edges: removed.into_iter().map(|target| link_edge(target, None)).collect(),
+    };
+    let _ = app_handle.emit("graph-edge-changed", change_info);
+}
+
+/// Documents that link to `document_id` with `[[...]]` references, most recently
+/// updated first, each with its referencing blocks.
+pub fn get_backlinks(app_handle: &AppHandle, document_id: &str) -> Result<Vec<Backlink>, DocumentError> {
+    use crate::domains::document::link::reference_snippet;
+
+    let conn = get_connection(app_handle)?;
+
+    let Some(document) = repository::find_document_by_id(&conn, document_id)
+        .map_err_log("get_backlinks::find_document", DocumentError::DatabaseQueryError)?
+    else {
+        return Ok(Vec::new());
+    };
+    let title = document.title.unwrap_or_default();
+
+    let rows = repository::find_link_source_blocks(&conn, document_id)
+        .map_err_log("get_backlinks::find_link_source_blocks", DocumentError::DatabaseQueryError)?;
+
+    let mut backlinks: Vec<Backlink> = Vec::new();
+    for (source, block_id, order_index, content) in rows {
+        if backlinks.last().map(|b| b.id != source.id).unwrap_or(true) {
+            backlinks.push(source);
+        }
+        let (Some(block_id), Some(content)) = (block_id, content) else {
+            continue;
+        };
+
+        // Same matching as repository::resolve_link_target
+        let snippet = reference_snippet(&content, |target| {
+            target == document_id || (!title.is_empty() && target.eq_ignore_ascii_case(&title))
+        });
+        let Some(snippet) = snippet else {
+            continue;
+        };
+        if let Some(backlink) = backlinks.last_mut() {
+            backlink.blocks.push(BlockHit {
+                block_id,
+                order_index: order_index.unwrap_or_default(),
+                snippet: Some(snippet),
+                similarity_score: None,
+            });
+        }
+    }
+
+    // The link edge outlived the references it came from; the document is still
+    // listed, without snippets, until it is saved again
+    for backlink in backlinks.iter().filter(|backlink| backlink.blocks.is_empty()) {
+        warn!(
+            "Document {} links to {} but none of its blocks references it",
+            backlink.id, document_id
+        );
+    }
+
+    Ok(backlinks)
+}
+
 // ============================================
 // Reindexing Service
 // ============================================
 
-pub fn reset_all_indexing_status(app_handle: &AppHandle) -> Result<u64, DocumentError> {
+/// Starts reindexing the blocks in `scope`, replacing a reindex already in progress,
+/// and returns the number of blocks queued. Search and the graph keep using the
+/// current vectors meanwhile: the scheduler builds the new ones in shadow tables
+/// and `complete_reindex` swaps them in once all are done.
+pub fn start_reindex(app_handle: &AppHandle, scope: &ReindexScope) -> Result<i64, DocumentError> {
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("start_reindex::begin_transaction", DocumentError::DatabaseQueryError)?;
+
+    repository::delete_reindex_job(&tx)
+        .map_err_log("start_reindex::delete_job", DocumentError::DatabaseQueryError)?;
+    let total = repository::insert_reindex_job(&tx, scope, &current_model_id())
+        .map_err_log("start_reindex::insert_job", DocumentError::DatabaseQueryError)?;
+
+    tx.commit()
+        .map_err_log("start_reindex::commit", DocumentError::DatabaseQueryError)?;
+    Ok(total)
+}
+
+/// Abandons the reindex in progress. The current vectors are left as they are.
+pub fn cancel_reindex(app_handle: &AppHandle) -> Result<(), DocumentError> {
+    let conn = get_writer(app_handle)?;
+
+    repository::delete_reindex_job(&conn)
+        .map_err_log("cancel_reindex", DocumentError::DatabaseQueryError)
+}
+
+pub fn get_reindex_progress(app_handle: &AppHandle) -> Result<Option<ReindexProgress>, DocumentError> {
     let conn = get_connection(app_handle)?;
 
-    repository::reset_all_indexing_status(&conn)
-        .map_err_log("reset_all_indexing_status", DocumentError::DatabaseQueryError)
+    repository::find_reindex_progress(&conn, &current_model_id())
+        .map_err_log("get_reindex_progress", DocumentError::DatabaseQueryError)
 }
 
-pub fn clear_all_edges(app_handle: &AppHandle) -> Result<u64, DocumentError> {
+pub fn get_pending_reindex_blocks(
+    app_handle: &AppHandle,
+    limit: i64,
+) -> Result<Vec<Block>, DocumentError> {
     let conn = get_connection(app_handle)?;
 
-    repository::delete_all_edges(&conn)
-        .map_err_log("clear_all_edges", DocumentError::DatabaseQueryError)
+    repository::find_pending_reindex_blocks(&conn, &current_model_id(), limit)
+        .map_err_log("get_pending_reindex_blocks", DocumentError::DatabaseQueryError)
 }
 
-pub fn clear_all_vectors(app_handle: &AppHandle) -> Result<u64, DocumentError> {
+/// Blocks of a reindex swapped into the live index whose edges haven't been
+/// synced yet, with their new vectors.
+pub fn get_swapped_reindex_blocks(
+    app_handle: &AppHandle,
+    limit: i64,
+) -> Result<Vec<(Block, Vec<ChunkEmbedding>)>, DocumentError> {
     let conn = get_connection(app_handle)?;
 
-    repository::delete_all_vectors(&conn)
-        .map_err_log("clear_all_vectors", DocumentError::DatabaseQueryError)
+    repository::find_swapped_reindex_blocks(&conn, &current_model_id(), limit)
+        .map_err_log("get_swapped_reindex_blocks", DocumentError::DatabaseQueryError)
+}
+
+/// Records that the edges of swapped-in blocks were synced, in one transaction,
+/// and removes the reindex job once no block is left. Returns whether it was removed.
+pub fn mark_reindex_blocks_synced(
+    app_handle: &AppHandle,
+    block_ids: &[String],
+) -> Result<bool, DocumentError> {
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("mark_reindex_blocks_synced::begin_transaction", DocumentError::DatabaseQueryError)?;
+
+    for block_id in block_ids {
+        repository::mark_reindex_block_synced(&tx, block_id)
+            .map_err_log("mark_reindex_blocks_synced::mark", DocumentError::DatabaseQueryError)?;
+    }
+    let unsynced = repository::count_unsynced_reindex_blocks(&tx)
+        .map_err_log("mark_reindex_blocks_synced::count_unsynced", DocumentError::DatabaseQueryError)?;
+    if unsynced == 0 {
+        repository::delete_reindex_job(&tx)
+            .map_err_log("mark_reindex_blocks_synced::delete_job", DocumentError::DatabaseQueryError)?;
+    }
+
+    tx.commit()
+        .map_err_log("mark_reindex_blocks_synced::commit", DocumentError::DatabaseQueryError)?;
+    Ok(unsynced == 0)
+}
+
+/// Stores the shadow vectors of several blocks, given (block_id, chunks) pairs, in one transaction.
+pub fn save_shadow_vectors(
+    app_handle: &AppHandle,
+    blocks: &[(&str, &[ChunkEmbedding])],
+    model_id: &str,
+) -> Result<(), DocumentError> {
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("save_shadow_vectors::begin_transaction", DocumentError::DatabaseQueryError)?;
+
+    for (block_id, chunks) in blocks {
+        repository::replace_shadow_vectors(&tx, block_id, chunks, model_id)
+            .map_err_log("save_shadow_vectors::replace", DocumentError::DatabaseQueryError)?;
+    }
+
+    tx.commit()
+        .map_err_log("save_shadow_vectors::commit", DocumentError::DatabaseQueryError)
+}
+
+/// Marks blocks of the reindex job embedded, given (block_id, indexed_hash) pairs, in one transaction.
+pub fn mark_reindex_blocks_embedded(
+    app_handle: &AppHandle,
+    blocks: &[(String, String)],
+) -> Result<(), DocumentError> {
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("mark_reindex_blocks_embedded::begin_transaction", DocumentError::DatabaseQueryError)?;
+
+    for (block_id, indexed_hash) in blocks {
+        repository::mark_reindex_block_embedded(&tx, block_id, indexed_hash)
+            .map_err_log("mark_reindex_blocks_embedded::mark", DocumentError::DatabaseQueryError)?;
+    }
+
+    tx.commit()
+        .map_err_log("mark_reindex_blocks_embedded::commit", DocumentError::DatabaseQueryError)
+}
+
+/// Sets blocks of the reindex job aside, given (block_id, error) pairs. They are
+/// not retried within the job and keep their current vectors.
+pub fn record_reindex_failures(
+    app_handle: &AppHandle,
+    failures: &[(&str, String)],
+) -> Result<(), DocumentError> {
+    let mut conn = get_writer(app_handle)?;
+    let tx = conn
+        .transaction()
+        .map_err_log("record_reindex_failures::begin_transaction", DocumentError::DatabaseQueryError)?;
+
+    for (block_id, error) in failures {
+        repository::mark_reindex_block_failed(&tx, block_id, error)
+            .map_err_log("record_reindex_failures::mark", DocumentError::DatabaseQueryError)?;
+    }
+
+    tx.commit()
+        .map_err_log("record_reindex_failures::commit", DocumentError::DatabaseQueryError)
+}
+
+/// Swaps the vectors of a finished reindex into the live index in one transaction.
+/// Returns the number of blocks swapped in, or `None` while no job has finished.
+/// The job stays until the edges of the swapped blocks are synced, see
+/// `mark_reindex_blocks_synced`, or is removed here if no block was swapped. A job
+/// started with another model than the current one is paused rather than
+/// discarded, since the model may only be standing in, and resumes once the
+/// job's model is back.
+pub fn complete_reindex(app_handle: &AppHandle) -> Result<Option<u64>, DocumentError> {
+    let mut conn = get_writer(app_handle)?;
+
+    let Some(job_model_id) = repository::find_reindex_job_model(&conn)
+        .map_err_log("complete_reindex::find_job", DocumentError::DatabaseQueryError)?
+    else {
+        return Ok(None);
+    };
+
+    let model_id = current_model_id();
+    if job_model_id != model_id {
+        return Ok(None);
+    }
+
+    let pending = repository::find_pending_reindex_blocks(&conn, &model_id, 1)
+        .map_err_log("complete_reindex::find_pending", DocumentError::DatabaseQueryError)?;
+    if !pending.is_empty() {
+        return Ok(None);
+    }
+
+    let tx = conn
+        .transaction()
+        .map_err_log("complete_reindex::begin_transaction", DocumentError::DatabaseQueryError)?;
+    let swapped = repository::swap_in_shadow_vectors(&tx, &model_id)
+        .map_err_log("complete_reindex::swap", DocumentError::DatabaseQueryError)?;
+    let unsynced = repository::count_unsynced_reindex_blocks(&tx)
+        .map_err_log("complete_reindex::count_unsynced", DocumentError::DatabaseQueryError)?;
+    if unsynced == 0 {
+        repository::delete_reindex_job(&tx)
+            .map_err_log("complete_reindex::delete_job", DocumentError::DatabaseQueryError)?;
+    }
+    tx.commit()
+        .map_err_log("complete_reindex::commit", DocumentError::DatabaseQueryError)?;
+
+    Ok((swapped > 0 || unsynced == 0).then_some(swapped))
+}
+
+// ============================================
+// Vector Benchmark Service
+// ============================================
+
+/// Measures each vector storage mode against exact search on the vault's own
+/// vectors: random chunks are used as queries, and recall is the share of their
+/// `BENCHMARK_NEIGHBOURS` exact nearest chunks that the mode also finds. Needs the
+/// full-precision vectors, so quantized storage must keep copies for re-scoring.
+pub fn benchmark_vector_storage(
+    app_handle: &AppHandle,
+    sample_size: Option<i64>,
+) -> Result<Vec<VectorStorageBenchmark>, DocumentError> {
+    let conn = get_connection(app_handle)?;
+    let model_id = current_model_id();
+
+    let layout = vector_layout(&conn)
+        .map_err_log("benchmark_vector_storage::layout", DocumentError::DatabaseQueryError)?;
+    let source = repository::full_vector_source(&layout).ok_or_else(|| {
+        DocumentError::BenchmarkUnavailableError(
+            "quantized vectors are stored without full-precision copies; turn on re-scoring or switch to float storage"
+                .to_string(),
+        )
+    })?;
+
+    let vector_count = repository::count_model_vectors(&conn, &model_id, source)
+        .map_err_log("benchmark_vector_storage::count", DocumentError::DatabaseQueryError)?;
+    let samples = repository::sample_full_vectors(
+        &conn,
+        &model_id,
+        source,
+        sample_size
+            .unwrap_or(DEFAULT_BENCHMARK_SAMPLES)
+            .clamp(1, MAX_BENCHMARK_SAMPLES),
+    )
+    .map_err_log("benchmark_vector_storage::sample", DocumentError::DatabaseQueryError)?;
+    if samples.len() < 2 {
+        return Err(DocumentError::BenchmarkUnavailableError(
+            "not enough indexed blocks to compare".to_string(),
+        ));
+    }
+
+    let mut modes = vec![
+        (VectorStorage::Float, false),
+        (VectorStorage::Int8, false),
+        (VectorStorage::Int8, true),
+    ];
+    if layout.dimension.is_multiple_of(8) {
+        modes.extend([(VectorStorage::Binary, false), (VectorStorage::Binary, true)]);
+    }
+
+    // (neighbours found, neighbours expected) per mode
+    let mut found = vec![(0usize, 0usize); modes.len()];
+    for (chunk_id, embedding) in &samples {
+        let exact: std::collections::HashSet<i64> = repository::find_nearest_chunks_as(
+            &conn,
+            &model_id,
+            source,
+            (*chunk_id, embedding.as_slice()),
+            BENCHMARK_NEIGHBOURS,
+            (VectorStorage::Float, false),
+        )
+        .map_err_log("benchmark_vector_storage::exact", DocumentError::DatabaseQueryError)?
+        .into_iter()
+        .collect();
+
+        for (mode, (hits, expected)) in modes.iter().zip(found.iter_mut()) {
+            let approximate = repository::find_nearest_chunks_as(
+                &conn,
+                &model_id,
+                source,
+                (*chunk_id, embedding.as_slice()),
+                BENCHMARK_NEIGHBOURS,
+                *mode,
+            )
+            .map_err_log("benchmark_vector_storage::approximate", DocumentError::DatabaseQueryError)?;
+
+            *hits += approximate.iter().filter(|id| exact.contains(id)).count();
+            *expected += exact.len();
+        }
+    }
+
+    let dimension = layout.dimension as i64;
+    Ok(modes
+        .into_iter()
+        .zip(found)
+        .map(|((storage, rescore), (hits, expected))| {
+            let quantized_bytes = match storage {
+                VectorStorage::Float => dimension * 4,
+                VectorStorage::Int8 => dimension,
+                VectorStorage::Binary => dimension / 8,
+            };
+            let bytes_per_vector = quantized_bytes + if rescore { dimension * 4 } else { 0 };
+
+            VectorStorageBenchmark {
+                storage,
+                rescore,
+                recall: if expected == 0 { 1.0 } else { hits as f64 / expected as f64 },
+                bytes_per_vector,
+                total_bytes: bytes_per_vector * vector_count,
+            }
+        })
+        .collect())
 }
 
 // ============================================
@@ -371,6 +1748,7 @@ pub fn get_graph_data(app_handle: &AppHandle) -> Result<GraphData, DocumentError
             id: doc_id.clone(),
             label: title.clone().unwrap_or_else(|| "Untitled".to_string()),
             node_type: "document".to_string(),
+            hop: None,
         });
 
         if let Some(tags) = tags_str {
@@ -381,7 +1759,11 @@ pub fn get_graph_data(app_handle: &AppHandle) -> Result<GraphData, DocumentError
                     source: doc_id.clone(),
                     target: format!("tag:{}", tag),
                     edge_type: "document-tag".to_string(),
+                    relation_type: None,
                     weight: None,
+                    pinned: false,
+                    label: None,
+                    note: None,
                 });
             }
         }
@@ -392,18 +1774,91 @@ pub fn get_graph_data(app_handle: &AppHandle) -> Result<GraphData, DocumentError
             id: format!("tag:{}", tag),
             label: tag,
             node_type: "tag".to_string(),
+            hop: None,
         });
     }
 
-    for (source_id, target_id, _relation_type, weight) in db_edges {
-        edges.push(GraphEdge {
-            source: source_id,
-            target: target_id,
-            edge_type: "document-document".to_string(),
-            weight: Some(weight),
+    edges.extend(db_edges);
+
+    Ok(GraphData { nodes, edges })
+}
+
+/// The part of the graph within `hops` of a document: documents linked by an edge
+/// of at least `min_weight`, or sharing a tag, are one hop apart. Nodes carry their
+/// hop distance; a tag takes the distance of its nearest document. `hops` defaults
+/// to the configured multi-hop level. Archived documents are left out, as in
+/// `get_graph_data`.
+pub fn get_document_neighborhood(
+    app_handle: &AppHandle,
+    document_id: &str,
+    hops: Option<u32>,
+    min_weight: Option<f64>,
+) -> Result<GraphData, DocumentError> {
+    let hops = match hops {
+        Some(hops) => hops,
+        None => {
+            let config = load_config(app_handle)
+                .map_err_log("get_document_neighborhood::load_config", DocumentError::ConfigLoadingError)?;
+            config.graph_settings.multi_hop_level.max(0) as u32
+        }
+    }
+    .min(MAX_NEIGHBORHOOD_HOPS);
+    let min_weight = min_weight.unwrap_or(0.0);
+
+    let conn = get_connection(app_handle)?;
+
+    let documents = repository::find_document_neighborhood(&conn, document_id, hops, min_weight)
+        .map_err_log("get_document_neighborhood::find_neighborhood", DocumentError::DatabaseQueryError)?;
+    let document_ids: Vec<String> = documents.iter().map(|(doc_id, ..)| doc_id.clone()).collect();
+    let strong_edges = repository::find_edges_between(&conn, &document_ids, min_weight)
+        .map_err_log("get_document_neighborhood::find_edges", DocumentError::DatabaseQueryError)?;
+
+    let mut nodes: Vec<GraphNode> = Vec::new();
+    let mut edges: Vec<GraphEdge> = Vec::new();
+    let mut tag_hops: HashMap<&str, u32> = HashMap::new();
+
+    for (doc_id, title, tags_str, hop) in &documents {
+        nodes.push(GraphNode {
+            id: doc_id.clone(),
+            label: title.clone().unwrap_or_else(|| "Untitled".to_string()),
+            node_type: "document".to_string(),
+            hop: Some(*hop),
+        });
+
+        let tags = tags_str
+            .as_deref()
+            .unwrap_or("")
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty());
+        for tag in tags {
+            let tag_hop = tag_hops.entry(tag).or_insert(*hop);
+            *tag_hop = (*tag_hop).min(*hop);
+
+            edges.push(GraphEdge {
+                source: doc_id.clone(),
+                target: format!("tag:{}", tag),
+                edge_type: "document-tag".to_string(),
+                relation_type: None,
+                weight: None,
+                pinned: false,
+                label: None,
+                note: None,
+            });
+        }
+    }
+
+    for (tag, hop) in tag_hops {
+        nodes.push(GraphNode {
+            id: format!("tag:{}", tag),
+            label: tag.to_string(),
+            node_type: "tag".to_string(),
+            hop: Some(hop),
         });
     }
 
+    edges.extend(strong_edges);
+
     Ok(GraphData { nodes, edges })
 }
 
@@ -415,6 +1870,7 @@ pub fn get_document_graph_info(document: &Document) -> DocumentGraphInfo {
             .clone()
             .unwrap_or_else(|| "Untitled".to_string()),
         node_type: "document".to_string(),
+        hop: None,
     };
 
     let mut tag_nodes: Vec<GraphNode> = Vec::new();
@@ -428,13 +1884,18 @@ pub fn get_document_graph_info(document: &Document) -> DocumentGraphInfo {
                 id: tag_id.clone(),
                 label: tag.clone(),
                 node_type: "tag".to_string(),
+                hop: None,
             });
 
             tag_edges.push(GraphEdge {
                 source: document.id.clone(),
                 target: tag_id,
                 edge_type: "document-tag".to_string(),
+                relation_type: None,
                 weight: None,
+                pinned: false,
+                label: None,
+                note: None,
             });
         }
     }
@@ -1,6 +1,7 @@
 use rusqlite::{Connection, Result};
 use std::path::PathBuf;
 use std::sync::Once;
+use std::time::Duration;
 
 static SQLITE_VEC_INIT: Once = Once::new();
 
@@ -19,6 +20,7 @@ pub fn create_connection(path: &PathBuf) -> Result<Connection> {
 
     conn.pragma_update(None, "journal_mode", Some("WAL"))?;
     conn.pragma_update(None, "foreign_keys", Some(1))?;
+    conn.busy_timeout(Duration::from_secs(5))?;
 
     Ok(conn)
 }
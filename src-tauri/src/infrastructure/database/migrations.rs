@@ -1,3 +1,7 @@
+use crate::domains::config::model::VectorStorage;
+use crate::domains::document::search::tokenizer::index_text;
+use crate::infrastructure::database::schema::{vec_blocks_sql, vec_documents_sql, vector_dimension};
+use crate::utils::hash::content_hash;
 use rusqlite::Connection;
 
 fn create_migrations_table(conn: &Connection) -> rusqlite::Result<()> {
@@ -25,7 +29,9 @@ fn record_migration(conn: &Connection, version: &str) -> rusqlite::Result<()> {
     Ok(())
 }
 
-pub fn run_migrations(conn: &mut Connection) -> rusqlite::Result<()> {
+/// `model_id` is the model of the active embedding provider, which vectors
+/// stored before models were tracked are attributed to.
+pub fn run_migrations(conn: &mut Connection, model_id: &str) -> rusqlite::Result<()> {
     create_migrations_table(conn)?;
 
     if !is_migration_applied(conn, "v1")? {
@@ -37,6 +43,96 @@ pub fn run_migrations(conn: &mut Connection) -> rusqlite::Result<()> {
         record_migration(conn, "v2_migrate_to_numeric_types")?;
     }
 
+    if !is_migration_applied(conn, "v3_add_block_content_hash")? {
+        add_block_content_hash(conn, model_id)?;
+        record_migration(conn, "v3_add_block_content_hash")?;
+    }
+
+    if !is_migration_applied(conn, "v4_create_fts_index")? {
+        create_fts_index(conn)?;
+        record_migration(conn, "v4_create_fts_index")?;
+    }
+
+    if !is_migration_applied(conn, "v5_add_cjk_search_tokens")? {
+        add_cjk_search_tokens(conn)?;
+        record_migration(conn, "v5_add_cjk_search_tokens")?;
+    }
+
+    if !is_migration_applied(conn, "v6_create_block_chunks")? {
+        create_block_chunks(conn)?;
+        record_migration(conn, "v6_create_block_chunks")?;
+    }
+
+    if !is_migration_applied(conn, "v7_add_embedding_metadata")? {
+        add_embedding_metadata(conn)?;
+        record_migration(conn, "v7_add_embedding_metadata")?;
+    }
+
+    if !is_migration_applied(conn, "v8_partition_vectors_by_model")? {
+        partition_vectors_by_model(conn)?;
+        record_migration(conn, "v8_partition_vectors_by_model")?;
+    }
+
+    if !is_migration_applied(conn, "v9_add_indexing_attempts")? {
+        add_indexing_attempts(conn)?;
+        record_migration(conn, "v9_add_indexing_attempts")?;
+    }
+
+    if !is_migration_applied(conn, "v10_create_reindex_shadow")? {
+        create_reindex_shadow(conn)?;
+        record_migration(conn, "v10_create_reindex_shadow")?;
+    }
+
+    if !is_migration_applied(conn, "v11_add_vector_storage")? {
+        add_vector_storage(conn)?;
+        record_migration(conn, "v11_add_vector_storage")?;
+    }
+
+    if !is_migration_applied(conn, "v12_key_edges_by_relation")? {
+        key_edges_by_relation(conn)?;
+        record_migration(conn, "v12_key_edges_by_relation")?;
+    }
+
+    if !is_migration_applied(conn, "v13_add_block_references")? {
+        add_block_references(conn)?;
+        record_migration(conn, "v13_add_block_references")?;
+    }
+
+    if !is_migration_applied(conn, "v14_add_edge_curation")? {
+        add_edge_curation(conn)?;
+        record_migration(conn, "v14_add_edge_curation")?;
+    }
+
+    if !is_migration_applied(conn, "v15_create_block_similarities")? {
+        create_block_similarities(conn)?;
+        record_migration(conn, "v15_create_block_similarities")?;
+    }
+
+    if !is_migration_applied(conn, "v16_add_stable_rowids")? {
+        add_stable_rowids(conn)?;
+        record_migration(conn, "v16_add_stable_rowids")?;
+    }
+
+    if !is_migration_applied(conn, "v17_index_document_listing")? {
+        index_document_listing(conn)?;
+        record_migration(conn, "v17_index_document_listing")?;
+    }
+
+    if !is_migration_applied(conn, "v18_add_last_error_at")? {
+        add_last_error_at(conn)?;
+        record_migration(conn, "v18_add_last_error_at")?;
+    }
+
+    if !is_migration_applied(conn, "v19_key_document_vectors_by_model")? {
+        key_document_vectors_by_model(conn)?;
+        record_migration(conn, "v19_key_document_vectors_by_model")?;
+    }
+
+    if !is_migration_applied(conn, "v20_add_block_similarity_chunks")? {
+        add_block_similarity_chunks(conn)?;
+        record_migration(conn, "v20_add_block_similarity_chunks")?;
+    }
+
     Ok(())
 }
 
@@ -121,3 +217,820 @@ fn migrate_to_numeric_types(conn: &mut Connection) -> rusqlite::Result<()> {
     tx.commit()?;
     Ok(())
 }
+
+// blocks.content_hash: hash of the current content
+// blocks.indexed_hash / embedding_model: content hash and model the stored vector was built from
+fn add_block_content_hash(conn: &mut Connection, model_id: &str) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute("ALTER TABLE blocks ADD COLUMN content_hash TEXT", [])?;
+    tx.execute("ALTER TABLE blocks ADD COLUMN indexed_hash TEXT", [])?;
+    tx.execute("ALTER TABLE blocks ADD COLUMN embedding_model TEXT", [])?;
+
+    let blocks: Vec<(String, Option<String>)> = {
+        let mut stmt = tx.prepare("SELECT id, content FROM blocks")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+
+    for (id, content) in blocks {
+        tx.execute(
+            "UPDATE blocks SET content_hash = ?1 WHERE id = ?2",
+            (content_hash(content.as_deref().unwrap_or("")), &id),
+        )?;
+    }
+
+    // Vectors that already exist were built from the current content, presumably
+    // by the configured model; leaving their model unknown would re-embed everything
+    tx.execute(
+        "UPDATE blocks SET indexed_hash = content_hash, embedding_model = ?1
+         WHERE indexing_status = 1",
+        [model_id],
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// fts_documents / fts_blocks: external-content FTS5 indexes kept in sync by triggers
+fn create_fts_index(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS fts_documents USING fts5(
+            title,
+            tags,
+            content='documents',
+            content_rowid='rowid',
+            tokenize='unicode61 remove_diacritics 2',
+            prefix='2 3'
+        );
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS fts_blocks USING fts5(
+            content,
+            content='blocks',
+            content_rowid='rowid',
+            tokenize='unicode61 remove_diacritics 2',
+            prefix='2 3'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS documents_fts_insert AFTER INSERT ON documents BEGIN
+            INSERT INTO fts_documents (rowid, title, tags)
+            VALUES (new.rowid, new.title, new.tags);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS documents_fts_delete AFTER DELETE ON documents BEGIN
+            INSERT INTO fts_documents (fts_documents, rowid, title, tags)
+            VALUES ('delete', old.rowid, old.title, old.tags);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS documents_fts_update AFTER UPDATE OF title, tags ON documents BEGIN
+            INSERT INTO fts_documents (fts_documents, rowid, title, tags)
+            VALUES ('delete', old.rowid, old.title, old.tags);
+            INSERT INTO fts_documents (rowid, title, tags)
+            VALUES (new.rowid, new.title, new.tags);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS blocks_fts_insert AFTER INSERT ON blocks BEGIN
+            INSERT INTO fts_blocks (rowid, content)
+            VALUES (new.rowid, new.content);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS blocks_fts_delete AFTER DELETE ON blocks BEGIN
+            INSERT INTO fts_blocks (fts_blocks, rowid, content)
+            VALUES ('delete', old.rowid, old.content);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS blocks_fts_update AFTER UPDATE OF content ON blocks BEGIN
+            INSERT INTO fts_blocks (fts_blocks, rowid, content)
+            VALUES ('delete', old.rowid, old.content);
+            INSERT INTO fts_blocks (rowid, content)
+            VALUES (new.rowid, new.content);
+        END;
+
+        INSERT INTO fts_documents (fts_documents) VALUES ('rebuild');
+        INSERT INTO fts_blocks (fts_blocks) VALUES ('rebuild');",
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// documents.title_tokens / tag_tokens, blocks.content_tokens: CJK stems and bigrams
+// (see tokenizer::index_text), indexed next to the raw text
+fn add_cjk_search_tokens(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute_batch(
+        "DROP TRIGGER IF EXISTS documents_fts_insert;
+        DROP TRIGGER IF EXISTS documents_fts_delete;
+        DROP TRIGGER IF EXISTS documents_fts_update;
+        DROP TRIGGER IF EXISTS blocks_fts_insert;
+        DROP TRIGGER IF EXISTS blocks_fts_delete;
+        DROP TRIGGER IF EXISTS blocks_fts_update;
+        DROP TABLE IF EXISTS fts_documents;
+        DROP TABLE IF EXISTS fts_blocks;
+
+        ALTER TABLE documents ADD COLUMN title_tokens TEXT;
+        ALTER TABLE documents ADD COLUMN tag_tokens TEXT;
+        ALTER TABLE blocks ADD COLUMN content_tokens TEXT;",
+    )?;
+
+    let documents: Vec<(String, Option<String>, Option<String>)> = {
+        let mut stmt = tx.prepare("SELECT id, title, tags FROM documents")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+
+    for (id, title, tags) in documents {
+        tx.execute(
+            "UPDATE documents SET title_tokens = ?1, tag_tokens = ?2 WHERE id = ?3",
+            (
+                index_text(title.as_deref().unwrap_or("")),
+                index_text(tags.as_deref().unwrap_or("")),
+                &id,
+            ),
+        )?;
+    }
+
+    let blocks: Vec<(String, Option<String>)> = {
+        let mut stmt = tx.prepare("SELECT id, content FROM blocks")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+
+    for (id, content) in blocks {
+        tx.execute(
+            "UPDATE blocks SET content_tokens = ?1 WHERE id = ?2",
+            (index_text(content.as_deref().unwrap_or("")), &id),
+        )?;
+    }
+
+    tx.execute_batch(
+        "CREATE VIRTUAL TABLE fts_documents USING fts5(
+            title,
+            tags,
+            title_tokens,
+            tag_tokens,
+            content='documents',
+            content_rowid='rowid',
+            tokenize='unicode61 remove_diacritics 2',
+            prefix='2 3'
+        );
+
+        CREATE VIRTUAL TABLE fts_blocks USING fts5(
+            content,
+            content_tokens,
+            content='blocks',
+            content_rowid='rowid',
+            tokenize='unicode61 remove_diacritics 2',
+            prefix='2 3'
+        );
+
+        CREATE TRIGGER documents_fts_insert AFTER INSERT ON documents BEGIN
+            INSERT INTO fts_documents (rowid, title, tags, title_tokens, tag_tokens)
+            VALUES (new.rowid, new.title, new.tags, new.title_tokens, new.tag_tokens);
+        END;
+
+        CREATE TRIGGER documents_fts_delete AFTER DELETE ON documents BEGIN
+            INSERT INTO fts_documents (fts_documents, rowid, title, tags, title_tokens, tag_tokens)
+            VALUES ('delete', old.rowid, old.title, old.tags, old.title_tokens, old.tag_tokens);
+        END;
+
+        CREATE TRIGGER documents_fts_update
+        AFTER UPDATE OF title, tags, title_tokens, tag_tokens ON documents BEGIN
+            INSERT INTO fts_documents (fts_documents, rowid, title, tags, title_tokens, tag_tokens)
+            VALUES ('delete', old.rowid, old.title, old.tags, old.title_tokens, old.tag_tokens);
+            INSERT INTO fts_documents (rowid, title, tags, title_tokens, tag_tokens)
+            VALUES (new.rowid, new.title, new.tags, new.title_tokens, new.tag_tokens);
+        END;
+
+        CREATE TRIGGER blocks_fts_insert AFTER INSERT ON blocks BEGIN
+            INSERT INTO fts_blocks (rowid, content, content_tokens)
+            VALUES (new.rowid, new.content, new.content_tokens);
+        END;
+
+        CREATE TRIGGER blocks_fts_delete AFTER DELETE ON blocks BEGIN
+            INSERT INTO fts_blocks (fts_blocks, rowid, content, content_tokens)
+            VALUES ('delete', old.rowid, old.content, old.content_tokens);
+        END;
+
+        CREATE TRIGGER blocks_fts_update AFTER UPDATE OF content, content_tokens ON blocks BEGIN
+            INSERT INTO fts_blocks (fts_blocks, rowid, content, content_tokens)
+            VALUES ('delete', old.rowid, old.content, old.content_tokens);
+            INSERT INTO fts_blocks (rowid, content, content_tokens)
+            VALUES (new.rowid, new.content, new.content_tokens);
+        END;
+
+        INSERT INTO fts_documents (fts_documents) VALUES ('rebuild');
+        INSERT INTO fts_blocks (fts_blocks) VALUES ('rebuild');",
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// block_chunks: overlapping windows of a block's content, one vector each.
+// vec_blocks rows are keyed by block_chunks.id instead of blocks.rowid from here on.
+fn create_block_chunks(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS block_chunks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            block_id TEXT NOT NULL,
+            chunk_index INTEGER NOT NULL,
+            start_offset INTEGER NOT NULL,
+            end_offset INTEGER NOT NULL,
+            FOREIGN KEY(block_id) REFERENCES blocks(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_block_chunks_block_id ON block_chunks(block_id);",
+    )?;
+
+    // Existing vectors become the single chunk of their block, keeping their rowid
+    tx.execute(
+        "INSERT INTO block_chunks (id, block_id, chunk_index, start_offset, end_offset)
+         SELECT b.rowid, b.id, 0, 0, LENGTH(COALESCE(b.content, ''))
+         FROM blocks b
+         WHERE b.rowid IN (SELECT rowid FROM vec_blocks)",
+        [],
+    )?;
+
+    // Those vectors were truncated at the model's token limit; a token almost always
+    // spans a character or more, so only blocks longer than that need re-embedding
+    tx.execute(
+        "UPDATE blocks SET indexing_status = 0 WHERE LENGTH(content) > 510",
+        [],
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// block_chunks.model_id / dimension: model and vector size each stored vector was built with
+// embedding_metadata: the model and dimension the index is currently built for (a single row)
+fn add_embedding_metadata(conn: &mut Connection) -> rusqlite::Result<()> {
+    let dimension = vector_dimension(conn)?;
+    let tx = conn.transaction()?;
+
+    tx.execute("ALTER TABLE block_chunks ADD COLUMN model_id TEXT", [])?;
+    tx.execute("ALTER TABLE block_chunks ADD COLUMN dimension INTEGER", [])?;
+
+    tx.execute(
+        "UPDATE block_chunks
+         SET model_id = (SELECT embedding_model FROM blocks WHERE blocks.id = block_chunks.block_id),
+             dimension = ?1",
+        [dimension.map(|d| d as i64)],
+    )?;
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS embedding_metadata (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            model_id TEXT NOT NULL,
+            dimension INTEGER NOT NULL,
+            updated_at DATETIME DEFAULT (datetime('now', 'localtime'))
+        )",
+        [],
+    )?;
+
+    // Seed with the model most existing vectors came from, so a different model
+    // configured at startup is detected as a change
+    tx.execute(
+        "INSERT OR IGNORE INTO embedding_metadata (id, model_id, dimension)
+         SELECT 1, model_id, dimension
+         FROM block_chunks
+         WHERE model_id IS NOT NULL AND dimension IS NOT NULL
+         GROUP BY model_id, dimension
+         ORDER BY COUNT(*) DESC
+         LIMIT 1",
+        [],
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// vec0 tables can't gain columns, so vec_blocks is rebuilt with a model_id
+// partition key, copying each vector with the model recorded for its chunk
+fn partition_vectors_by_model(conn: &mut Connection) -> rusqlite::Result<()> {
+    let Some(dimension) = vector_dimension(conn)? else {
+        return Ok(());
+    };
+    let tx = conn.transaction()?;
+
+    tx.execute_batch(
+        "CREATE TEMP TABLE vec_blocks_backup AS
+            SELECT v.rowid AS id, COALESCE(c.model_id, '') AS model_id, v.embedding
+            FROM vec_blocks v
+            JOIN block_chunks c ON c.id = v.rowid;
+
+        DROP TABLE vec_blocks;",
+    )?;
+    tx.execute(&vec_blocks_sql(dimension, VectorStorage::Float), [])?;
+    tx.execute_batch(
+        "INSERT INTO vec_blocks (rowid, model_id, embedding)
+            SELECT id, model_id, embedding FROM vec_blocks_backup;
+
+        DROP TABLE vec_blocks_backup;",
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// blocks.indexing_attempts / indexing_error: failed indexing attempts since the last success, and the last error
+// blocks.next_attempt_at: the block is not retried before this time
+fn add_indexing_attempts(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute("ALTER TABLE blocks ADD COLUMN indexing_attempts INTEGER DEFAULT 0", [])?;
+    tx.execute("ALTER TABLE blocks ADD COLUMN indexing_error TEXT", [])?;
+    tx.execute("ALTER TABLE blocks ADD COLUMN next_attempt_at DATETIME", [])?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// reindex_job: the reindex in progress, if any (a single row). scope_kind is
+// 'all', 'document' or 'tag', with the document id or tag in scope_value
+// reindex_queue.status: 0 = PENDING, 1 = EMBEDDED, 2 = FAILED, 3 = SWAPPED (edges not synced
+// yet), 4 = SYNCED
+// shadow_block_chunks: vectors built by the job, swapped into block_chunks and
+// vec_blocks once every block in its scope is embedded
+fn create_reindex_shadow(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS reindex_job (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            scope_kind TEXT NOT NULL,
+            scope_value TEXT,
+            model_id TEXT NOT NULL,
+            total INTEGER NOT NULL,
+            started_at DATETIME DEFAULT (datetime('now', 'localtime'))
+        );
+
+        CREATE TABLE IF NOT EXISTS reindex_queue (
+            block_id TEXT PRIMARY KEY,
+            status SMALLINT DEFAULT 0,
+            indexed_hash TEXT,
+            error TEXT,
+            FOREIGN KEY(block_id) REFERENCES blocks(id) ON DELETE CASCADE
+        );
+
+        CREATE TABLE IF NOT EXISTS shadow_block_chunks (
+            block_id TEXT NOT NULL,
+            chunk_index INTEGER NOT NULL,
+            start_offset INTEGER NOT NULL,
+            end_offset INTEGER NOT NULL,
+            model_id TEXT NOT NULL,
+            dimension INTEGER NOT NULL,
+            embedding BLOB NOT NULL,
+            PRIMARY KEY (block_id, chunk_index),
+            FOREIGN KEY(block_id) REFERENCES blocks(id) ON DELETE CASCADE
+        );",
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// block_chunks.embedding: full-precision copy of a quantized vector, kept for re-scoring
+// embedding_metadata.storage: 'float', 'int8' or 'binary', how vec_blocks stores vectors
+// embedding_metadata.rescore: whether block_chunks.embedding is kept
+fn add_vector_storage(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute("ALTER TABLE block_chunks ADD COLUMN embedding BLOB", [])?;
+    tx.execute(
+        "ALTER TABLE embedding_metadata ADD COLUMN storage TEXT NOT NULL DEFAULT 'float'",
+        [],
+    )?;
+    tx.execute(
+        "ALTER TABLE embedding_metadata ADD COLUMN rescore INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// edges.relation_type: 'similar' (maintained by the indexing scheduler) or 'link'
+// (a [[...]] reference in the source document); part of the key, so two documents
+// can be both similar and linked
+fn key_edges_by_relation(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute_batch(
+        "CREATE TABLE edges_new (
+            source_id TEXT NOT NULL,
+            target_id TEXT NOT NULL,
+            relation_type TEXT NOT NULL DEFAULT 'similar',
+            weight REAL DEFAULT 1.0,
+            created_at DATETIME DEFAULT (datetime('now', 'localtime')),
+            PRIMARY KEY (source_id, target_id, relation_type),
+            FOREIGN KEY(source_id) REFERENCES documents(id),
+            FOREIGN KEY(target_id) REFERENCES documents(id)
+        );
+
+        INSERT INTO edges_new (source_id, target_id, relation_type, weight, created_at)
+        SELECT source_id, target_id, COALESCE(relation_type, 'similar'), weight, created_at
+        FROM edges;
+
+        DROP TABLE edges;
+        ALTER TABLE edges_new RENAME TO edges;
+
+        CREATE INDEX IF NOT EXISTS idx_edges_target_id ON edges(target_id);",
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// blocks.source_block_id: the block this one embeds or quotes (source_document_id is its document)
+// blocks.reference_kind: 'embed' (content follows the source) or 'quote' (a copy)
+// blocks.source_hash: content hash of the source when the block was last in sync with it
+fn add_block_references(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute_batch(
+        "ALTER TABLE blocks ADD COLUMN source_block_id TEXT;
+        ALTER TABLE blocks ADD COLUMN reference_kind TEXT;
+        ALTER TABLE blocks ADD COLUMN source_hash TEXT;
+
+        CREATE INDEX IF NOT EXISTS idx_blocks_source_block_id ON blocks(source_block_id);",
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// edges.pinned: the indexing scheduler never removes the edge
+// edges.label / note: the user's annotation of the relation
+// blocked_edges: document pairs the scheduler must not link, stored once with source_id < target_id
+fn add_edge_curation(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute_batch(
+        "ALTER TABLE edges ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE edges ADD COLUMN label TEXT;
+        ALTER TABLE edges ADD COLUMN note TEXT;
+
+        CREATE TABLE IF NOT EXISTS blocked_edges (
+            source_id TEXT NOT NULL,
+            target_id TEXT NOT NULL,
+            created_at DATETIME DEFAULT (datetime('now', 'localtime')),
+            PRIMARY KEY (source_id, target_id),
+            FOREIGN KEY(source_id) REFERENCES documents(id) ON DELETE CASCADE,
+            FOREIGN KEY(target_id) REFERENCES documents(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_blocked_edges_target_id ON blocked_edges(target_id);",
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// The block pairs behind similar edges: for each indexed block, the blocks of
+// other documents it was found similar to, with their similarity (1 - distance)
+fn create_block_similarities(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS block_similarities (
+            block_id TEXT NOT NULL,
+            similar_block_id TEXT NOT NULL,
+            similarity REAL NOT NULL,
+            created_at DATETIME DEFAULT (datetime('now', 'localtime')),
+            PRIMARY KEY (block_id, similar_block_id),
+            FOREIGN KEY(block_id) REFERENCES blocks(id) ON DELETE CASCADE,
+            FOREIGN KEY(similar_block_id) REFERENCES blocks(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_block_similarities_similar_block_id
+            ON block_similarities(similar_block_id);",
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// documents.seq / blocks.seq: INTEGER PRIMARY KEY aliases of the rowid, which VACUUM
+// may renumber on tables keyed by TEXT; the FTS5 indexes are keyed on them instead.
+// Foreign keys are off while the tables are rebuilt, so dropping them cascades nowhere,
+// and back on whether or not the rebuild succeeds
+fn add_stable_rowids(conn: &mut Connection) -> rusqlite::Result<()> {
+    conn.execute("PRAGMA foreign_keys=off", [])?;
+    let result = rebuild_with_stable_rowids(conn);
+    conn.execute("PRAGMA foreign_keys=on", [])?;
+    result
+}
+
+fn rebuild_with_stable_rowids(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute_batch(
+        "DROP TRIGGER IF EXISTS documents_fts_insert;
+        DROP TRIGGER IF EXISTS documents_fts_delete;
+        DROP TRIGGER IF EXISTS documents_fts_update;
+        DROP TRIGGER IF EXISTS blocks_fts_insert;
+        DROP TRIGGER IF EXISTS blocks_fts_delete;
+        DROP TRIGGER IF EXISTS blocks_fts_update;
+        DROP TABLE IF EXISTS fts_documents;
+        DROP TABLE IF EXISTS fts_blocks;
+
+        CREATE TABLE documents_new (
+            seq INTEGER PRIMARY KEY,
+            id TEXT NOT NULL UNIQUE,
+            title TEXT,
+            status SMALLINT DEFAULT 0,
+            tags TEXT,
+            created_at DATETIME DEFAULT (datetime('now', 'localtime')),
+            updated_at DATETIME DEFAULT (datetime('now', 'localtime')),
+            title_tokens TEXT,
+            tag_tokens TEXT
+        );
+
+        INSERT INTO documents_new (seq, id, title, status, tags, created_at, updated_at, title_tokens, tag_tokens)
+        SELECT rowid, id, title, status, tags, created_at, updated_at, title_tokens, tag_tokens
+        FROM documents;
+
+        DROP TABLE documents;
+        ALTER TABLE documents_new RENAME TO documents;
+
+        CREATE TABLE blocks_new (
+            seq INTEGER PRIMARY KEY,
+            id TEXT NOT NULL UNIQUE,
+            document_id TEXT NOT NULL,
+            content TEXT,
+            order_index REAL NOT NULL,
+            source_document_id TEXT,
+            indexing_status SMALLINT DEFAULT 0,
+            created_at DATETIME DEFAULT (datetime('now', 'localtime')),
+            updated_at DATETIME DEFAULT (datetime('now', 'localtime')),
+            content_hash TEXT,
+            indexed_hash TEXT,
+            embedding_model TEXT,
+            content_tokens TEXT,
+            indexing_attempts INTEGER DEFAULT 0,
+            indexing_error TEXT,
+            next_attempt_at DATETIME,
+            source_block_id TEXT,
+            reference_kind TEXT,
+            source_hash TEXT,
+            FOREIGN KEY(document_id) REFERENCES documents(id) ON DELETE CASCADE
+        );
+
+        INSERT INTO blocks_new (
+            seq, id, document_id, content, order_index, source_document_id, indexing_status,
+            created_at, updated_at, content_hash, indexed_hash, embedding_model, content_tokens,
+            indexing_attempts, indexing_error, next_attempt_at, source_block_id, reference_kind,
+            source_hash
+        )
+        SELECT
+            rowid, id, document_id, content, order_index, source_document_id, indexing_status,
+            created_at, updated_at, content_hash, indexed_hash, embedding_model, content_tokens,
+            indexing_attempts, indexing_error, next_attempt_at, source_block_id, reference_kind,
+            source_hash
+        FROM blocks;
+
+        DROP TABLE blocks;
+        ALTER TABLE blocks_new RENAME TO blocks;
+
+        CREATE INDEX IF NOT EXISTS idx_blocks_source_block_id ON blocks(source_block_id);
+
+        CREATE VIRTUAL TABLE fts_documents USING fts5(
+            title,
+            tags,
+            title_tokens,
+            tag_tokens,
+            content='documents',
+            content_rowid='seq',
+            tokenize='unicode61 remove_diacritics 2',
+            prefix='2 3'
+        );
+
+        CREATE VIRTUAL TABLE fts_blocks USING fts5(
+            content,
+            content_tokens,
+            content='blocks',
+            content_rowid='seq',
+            tokenize='unicode61 remove_diacritics 2',
+            prefix='2 3'
+        );
+
+        CREATE TRIGGER documents_fts_insert AFTER INSERT ON documents BEGIN
+            INSERT INTO fts_documents (rowid, title, tags, title_tokens, tag_tokens)
+            VALUES (new.seq, new.title, new.tags, new.title_tokens, new.tag_tokens);
+        END;
+
+        CREATE TRIGGER documents_fts_delete AFTER DELETE ON documents BEGIN
+            INSERT INTO fts_documents (fts_documents, rowid, title, tags, title_tokens, tag_tokens)
+            VALUES ('delete', old.seq, old.title, old.tags, old.title_tokens, old.tag_tokens);
+        END;
+
+        CREATE TRIGGER documents_fts_update
+        AFTER UPDATE OF title, tags, title_tokens, tag_tokens ON documents BEGIN
+            INSERT INTO fts_documents (fts_documents, rowid, title, tags, title_tokens, tag_tokens)
+            VALUES ('delete', old.seq, old.title, old.tags, old.title_tokens, old.tag_tokens);
+            INSERT INTO fts_documents (rowid, title, tags, title_tokens, tag_tokens)
+            VALUES (new.seq, new.title, new.tags, new.title_tokens, new.tag_tokens);
+        END;
+
+        CREATE TRIGGER blocks_fts_insert AFTER INSERT ON blocks BEGIN
+            INSERT INTO fts_blocks (rowid, content, content_tokens)
+            VALUES (new.seq, new.content, new.content_tokens);
+        END;
+
+        CREATE TRIGGER blocks_fts_delete AFTER DELETE ON blocks BEGIN
+            INSERT INTO fts_blocks (fts_blocks, rowid, content, content_tokens)
+            VALUES ('delete', old.seq, old.content, old.content_tokens);
+        END;
+
+        CREATE TRIGGER blocks_fts_update AFTER UPDATE OF content, content_tokens ON blocks BEGIN
+            INSERT INTO fts_blocks (fts_blocks, rowid, content, content_tokens)
+            VALUES ('delete', old.seq, old.content, old.content_tokens);
+            INSERT INTO fts_blocks (rowid, content, content_tokens)
+            VALUES (new.seq, new.content, new.content_tokens);
+        END;
+
+        INSERT INTO fts_documents (fts_documents) VALUES ('rebuild');
+        INSERT INTO fts_blocks (fts_blocks) VALUES ('rebuild');",
+    )?;
+
+    // Nothing checked the references while the tables were swapped
+    let violation: Option<(String, String)> = tx
+        .prepare("PRAGMA foreign_key_check")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(2)?)))?
+        .next()
+        .transpose()?;
+    if let Some((table, parent)) = violation {
+        return Err(rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT_FOREIGNKEY),
+            Some(format!("{} references a missing row of {}", table, parent)),
+        ));
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+// documents.connection_count: number of edges from or to the document, kept by triggers on edges
+// idx_documents_*: (sort column, id) indexes for keyset pagination of the document list,
+// the title one on the same expression the listing sorts by
+fn index_document_listing(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute_batch(
+        "ALTER TABLE documents ADD COLUMN connection_count INTEGER NOT NULL DEFAULT 0;
+
+        UPDATE documents SET connection_count = (
+            SELECT COUNT(*) FROM edges e
+            WHERE e.source_id = documents.id OR e.target_id = documents.id
+        );
+
+        CREATE TRIGGER IF NOT EXISTS edges_count_insert AFTER INSERT ON edges BEGIN
+            UPDATE documents SET connection_count = connection_count + 1
+            WHERE id IN (new.source_id, new.target_id);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS edges_count_delete AFTER DELETE ON edges BEGIN
+            UPDATE documents SET connection_count = connection_count - 1
+            WHERE id IN (old.source_id, old.target_id);
+        END;
+
+        CREATE INDEX IF NOT EXISTS idx_documents_updated_at ON documents(updated_at, id);
+        CREATE INDEX IF NOT EXISTS idx_documents_created_at ON documents(created_at, id);
+        CREATE INDEX IF NOT EXISTS idx_documents_title
+            ON documents(COALESCE(title, '') COLLATE NOCASE, id);
+        CREATE INDEX IF NOT EXISTS idx_documents_connection_count
+            ON documents(connection_count, id);",
+    )?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+// blocks.last_error_at: when the block last failed to index; blocks that failed
+// before this column existed have none and are listed after the others
+fn add_last_error_at(conn: &mut Connection) -> rusqlite::Result<()> {
+    conn.execute("ALTER TABLE blocks ADD COLUMN last_error_at DATETIME", [])?;
+    Ok(())
+}
+
+// document_vectors: the document and model of each vec_documents row, which is
+// keyed by its id, so a document keeps a vector per model. Document vectors are
+// derived from the block vectors, so the old ones are dropped and recomputed by
+// the scheduler
+fn key_document_vectors_by_model(conn: &mut Connection) -> rusqlite::Result<()> {
+    let dimension = vector_dimension(conn)?;
+    let tx = conn.transaction()?;
+
+    tx.execute(
+        "CREATE TABLE IF NOT EXISTS document_vectors (
+            id INTEGER PRIMARY KEY,
+            document_id TEXT NOT NULL,
+            model_id TEXT NOT NULL,
+            UNIQUE(document_id, model_id)
+        )",
+        [],
+    )?;
+    tx.execute("DROP TABLE IF EXISTS vec_documents", [])?;
+    if let Some(dimension) = dimension {
+        tx.execute(&vec_documents_sql(dimension), [])?;
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+// block_similarities.chunk_index / similar_chunk_index: the chunks of the two blocks
+// that matched best. Pairs recorded before have none and show the blocks' opening
+// text until their blocks are indexed again
+fn add_block_similarity_chunks(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+
+    tx.execute("ALTER TABLE block_similarities ADD COLUMN chunk_index INTEGER", [])?;
+    tx.execute("ALTER TABLE block_similarities ADD COLUMN similar_chunk_index INTEGER", [])?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::database::test_support::test_database;
+
+    fn matching_block_ids(conn: &Connection, query: &str) -> Vec<String> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT b.id FROM fts_blocks
+                 JOIN blocks b ON b.seq = fts_blocks.rowid
+                 WHERE fts_blocks MATCH ?1
+                 ORDER BY b.id",
+            )
+            .unwrap();
+        let rows = stmt.query_map([query], |row| row.get(0)).unwrap();
+        rows.collect::<rusqlite::Result<_>>().unwrap()
+    }
+
+    #[test]
+    fn full_text_index_survives_vacuum() {
+        let (_dir, conn) = test_database();
+
+        conn.execute_batch(
+            "INSERT INTO documents (id, title) VALUES ('d1', 'Notes');
+             INSERT INTO blocks (id, document_id, content, order_index) VALUES
+                ('b1', 'd1', 'apple', 1),
+                ('b2', 'd1', 'banana', 2),
+                ('b3', 'd1', 'cherry', 3);
+             DELETE FROM blocks WHERE id = 'b1';
+             VACUUM;",
+        )
+        .unwrap();
+
+        assert_eq!(matching_block_ids(&conn, "banana"), vec!["b2".to_string()]);
+        assert_eq!(matching_block_ids(&conn, "cherry"), vec!["b3".to_string()]);
+        assert!(matching_block_ids(&conn, "apple").is_empty());
+    }
+
+    #[test]
+    fn rebuilt_tables_keep_cascading_deletes() {
+        let (_dir, conn) = test_database();
+
+        conn.execute_batch(
+            "INSERT INTO documents (id, title) VALUES ('d1', 'Notes');
+             INSERT INTO blocks (id, document_id, content, order_index) VALUES ('b1', 'd1', 'apple', 1);
+             DELETE FROM documents WHERE id = 'd1';",
+        )
+        .unwrap();
+
+        let blocks: i64 = conn
+            .query_row("SELECT COUNT(*) FROM blocks", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(blocks, 0);
+        assert!(matching_block_ids(&conn, "apple").is_empty());
+    }
+
+    #[test]
+    fn rebuild_fails_on_broken_references_and_restores_foreign_keys() {
+        let (_dir, mut conn) = test_database();
+
+        conn.execute_batch(
+            "PRAGMA foreign_keys=off;
+             INSERT INTO blocks (id, document_id, content, order_index) VALUES ('b1', 'missing', 'apple', 1);
+             PRAGMA foreign_keys=on;",
+        )
+        .unwrap();
+
+        assert!(add_stable_rowids(&mut conn).is_err());
+        let foreign_keys: i64 = conn
+            .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(foreign_keys, 1);
+        assert_eq!(matching_block_ids(&conn, "apple"), vec!["b1".to_string()]);
+    }
+}
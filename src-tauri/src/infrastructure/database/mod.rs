@@ -1,5 +1,8 @@
 pub mod connection;
 pub mod extension;
 pub mod migrations;
+pub mod pool;
 pub mod query;
 pub mod schema;
+#[cfg(test)]
+pub mod test_support;
@@ -0,0 +1,292 @@
+use crate::infrastructure::database::connection::create_connection;
+use rusqlite::{Connection, Result};
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+
+const MAX_IDLE_READERS: usize = 4;
+
+/// A small pool of SQLite connections bound to one storage path.
+///
+/// Readers are handed out from an idle list and opened on demand; all writes
+/// go through a single writer connection so WAL writers never contend.
+pub struct ConnectionPool {
+    path: PathBuf,
+    readers: Mutex<Vec<Connection>>,
+    writer: Mutex<Option<Connection>>,
+    writer_available: Condvar,
+}
+
+enum ConnectionKind {
+    Reader,
+    Writer,
+}
+
+/// A connection checked out of a [`ConnectionPool`].
+/// The connection is returned to the pool when the guard is dropped.
+pub struct PooledConnection {
+    pool: Arc<ConnectionPool>,
+    conn: Option<Connection>,
+    kind: ConnectionKind,
+}
+
+impl ConnectionPool {
+    pub fn open(path: &Path) -> Result<Arc<Self>> {
+        let writer = create_connection(&path.to_path_buf())?;
+
+        Ok(Arc::new(Self {
+            path: path.to_path_buf(),
+            readers: Mutex::new(Vec::new()),
+            writer: Mutex::new(Some(writer)),
+            writer_available: Condvar::new(),
+        }))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn reader(self: &Arc<Self>) -> Result<PooledConnection> {
+        let idle = self
+            .readers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .pop();
+
+        let conn = match idle {
+            Some(conn) => conn,
+            None => create_connection(&self.path)?,
+        };
+
+        Ok(PooledConnection {
+            pool: Arc::clone(self),
+            conn: Some(conn),
+            kind: ConnectionKind::Reader,
+        })
+    }
+
+    /// Blocks until the writer connection is free.
+    pub fn writer(self: &Arc<Self>) -> Result<PooledConnection> {
+        let mut slot = self
+            .writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        while slot.is_none() {
+            slot = self
+                .writer_available
+                .wait(slot)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+
+        Ok(PooledConnection {
+            pool: Arc::clone(self),
+            conn: slot.take(),
+            kind: ConnectionKind::Writer,
+        })
+    }
+
+    fn release(&self, conn: Connection, kind: &ConnectionKind) {
+        match kind {
+            ConnectionKind::Reader => {
+                let mut readers = self
+                    .readers
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                if readers.len() < MAX_IDLE_READERS {
+                    readers.push(conn);
+                }
+            }
+            ConnectionKind::Writer => {
+                let mut slot = self
+                    .writer
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                *slot = Some(conn);
+                self.writer_available.notify_one();
+            }
+        }
+    }
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("pooled connection already released")
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.conn.as_mut().expect("pooled connection already released")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            // A writer left inside an open transaction must not be reused as-is.
+            if !conn.is_autocommit() {
+                let _ = conn.execute_batch("ROLLBACK");
+            }
+            self.pool.release(conn, &self.kind);
+        }
+    }
+}
+
+/// App-state holder for the pool of the currently configured storage path.
+/// The pool is opened lazily and re-opened when the storage path changes.
+#[derive(Default)]
+pub struct DatabaseState {
+    pool: Mutex<Option<Arc<ConnectionPool>>>,
+}
+
+impl DatabaseState {
+    pub fn pool(&self, path: &Path) -> Result<Arc<ConnectionPool>> {
+        let mut pool = self
+            .pool
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(existing) = pool.as_ref() {
+            if existing.path() == path {
+                return Ok(Arc::clone(existing));
+            }
+        }
+
+        let opened = ConnectionPool::open(path)?;
+        *pool = Some(Arc::clone(&opened));
+        Ok(opened)
+    }
+
+    pub fn reader(&self, path: &Path) -> Result<PooledConnection> {
+        self.pool(path)?.reader()
+    }
+
+    pub fn writer(&self, path: &Path) -> Result<PooledConnection> {
+        self.pool(path)?.writer()
+    }
+
+    pub fn invalidate(&self) {
+        let mut pool = self
+            .pool
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *pool = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn open_pool() -> (TempDir, Arc<ConnectionPool>) {
+        std::env::set_var("DATABASE_NAME", "test.db");
+        let dir = TempDir::new().unwrap();
+        let pool = ConnectionPool::open(dir.path()).unwrap();
+        (dir, pool)
+    }
+
+    fn idle_readers(pool: &ConnectionPool) -> usize {
+        pool.readers.lock().unwrap().len()
+    }
+
+    #[test]
+    fn reader_is_reused_after_release() {
+        let (_dir, pool) = open_pool();
+
+        {
+            let reader = pool.reader().unwrap();
+            reader.execute_batch("CREATE TEMP TABLE marker (id INTEGER)").unwrap();
+        }
+        assert_eq!(idle_readers(&pool), 1);
+
+        // Temp tables are private to a connection, so the marker shows it is the same one
+        let reader = pool.reader().unwrap();
+        let marker: i64 = reader
+            .query_row(
+                "SELECT COUNT(*) FROM temp.sqlite_master WHERE name = 'marker'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(marker, 1);
+        assert_eq!(idle_readers(&pool), 0);
+    }
+
+    #[test]
+    fn idle_readers_are_capped() {
+        let (_dir, pool) = open_pool();
+
+        let readers: Vec<PooledConnection> = (0..MAX_IDLE_READERS + 2)
+            .map(|_| pool.reader().unwrap())
+            .collect();
+        drop(readers);
+
+        assert_eq!(idle_readers(&pool), MAX_IDLE_READERS);
+    }
+
+    #[test]
+    fn writer_waits_until_released() {
+        let (_dir, pool) = open_pool();
+        let writer = pool.writer().unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let waiting = Arc::clone(&pool);
+        let handle = thread::spawn(move || {
+            let _writer = waiting.writer().unwrap();
+            sender.send(()).unwrap();
+        });
+
+        assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+        drop(writer);
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn open_transaction_is_rolled_back_on_release() {
+        let (_dir, pool) = open_pool();
+
+        {
+            let writer = pool.writer().unwrap();
+            writer
+                .execute_batch(
+                    "CREATE TABLE items (id INTEGER);
+                     BEGIN;
+                     INSERT INTO items (id) VALUES (1);",
+                )
+                .unwrap();
+        }
+
+        let writer = pool.writer().unwrap();
+        assert!(writer.is_autocommit());
+        let count: i64 = writer
+            .query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn state_reopens_pool_for_another_path() {
+        std::env::set_var("DATABASE_NAME", "test.db");
+        let first = TempDir::new().unwrap();
+        let second = TempDir::new().unwrap();
+        let state = DatabaseState::default();
+
+        let pool = state.pool(first.path()).unwrap();
+        assert!(Arc::ptr_eq(&pool, &state.pool(first.path()).unwrap()));
+
+        let other = state.pool(second.path()).unwrap();
+        assert!(!Arc::ptr_eq(&pool, &other));
+        assert_eq!(other.path(), second.path());
+
+        state.invalidate();
+        assert!(!Arc::ptr_eq(&other, &state.pool(second.path()).unwrap()));
+    }
+}
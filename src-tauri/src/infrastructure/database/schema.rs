@@ -1,6 +1,18 @@
-use rusqlite::Connection;
+use crate::domains::config::model::VectorStorage;
+use rusqlite::{Connection, OptionalExtension};
+use tracing::info;
 
-pub fn init_schema(conn: &mut Connection) -> rusqlite::Result<()> {
+/// Scale of sqlite-vec's 'unit' int8 quantization, which maps [-1, 1] onto [-128, 127].
+const INT8_SCALE: f32 = 127.5;
+/// How many coarse matches a two-stage search re-scores per result it returns.
+pub const RESCORE_OVERSAMPLING: i64 = 8;
+/// Largest `k` sqlite-vec accepts in a KNN query.
+const KNN_MAX_K: i64 = 4096;
+
+/// Creates the tables that don't exist yet. `vec_blocks` and `vec_documents` are
+/// created with `dimension`, the vector size of the active embedding provider;
+/// `vec_blocks` stores floats until `apply_vector_layout` converts it.
+pub fn init_schema(conn: &mut Connection, dimension: usize) -> rusqlite::Result<()> {
     let tx = conn.transaction()?;
 
     // documents.status: 0 = FLEETING, 1 = PERMANENT, 99 = ARCHIVED
@@ -32,12 +44,8 @@ pub fn init_schema(conn: &mut Connection) -> rusqlite::Result<()> {
         [],
     )?;
 
-    tx.execute(
-        "CREATE VIRTUAL TABLE IF NOT EXISTS vec_blocks USING vec0(
-            embedding float[1024]
-        )",
-        [],
-    )?;
+    tx.execute(&vec_blocks_sql(dimension, VectorStorage::Float), [])?;
+    tx.execute(&vec_documents_sql(dimension), [])?;
 
     tx.execute(
         "CREATE TABLE IF NOT EXISTS edges (
@@ -56,3 +64,422 @@ pub fn init_schema(conn: &mut Connection) -> rusqlite::Result<()> {
     tx.commit()?;
     Ok(())
 }
+
+// vec_blocks is partitioned by model, so a KNN query only scans the vectors of
+// the model that embedded the query
+pub fn vec_blocks_sql(dimension: usize, storage: VectorStorage) -> String {
+    let column_type = match storage {
+        VectorStorage::Float => "float",
+        VectorStorage::Int8 => "int8",
+        VectorStorage::Binary => "bit",
+    };
+    format!(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS vec_blocks USING vec0(
+            model_id text partition key,
+            embedding {}[{}]
+        )",
+        column_type, dimension
+    )
+}
+
+// vec_documents holds one vector per document and model, the length-weighted mean
+// of its chunk vectors, keyed by the id of its `document_vectors` row. There are
+// few enough of them to always store floats.
+pub fn vec_documents_sql(dimension: usize) -> String {
+    format!(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS vec_documents USING vec0(
+            model_id text partition key,
+            embedding float[{}]
+        )",
+        dimension
+    )
+}
+
+fn storage_name(storage: VectorStorage) -> &'static str {
+    match storage {
+        VectorStorage::Float => "float",
+        VectorStorage::Int8 => "int8",
+        VectorStorage::Binary => "binary",
+    }
+}
+
+fn parse_storage(name: &str) -> VectorStorage {
+    match name {
+        "int8" => VectorStorage::Int8,
+        "binary" => VectorStorage::Binary,
+        _ => VectorStorage::Float,
+    }
+}
+
+/// How `vec_blocks` stores vectors. With `rescore`, quantized vectors are only
+/// used to find candidates, which are then ranked by the full-precision copies
+/// kept in `block_chunks.embedding`.
+///
+/// Queries take a float32 query vector and report L2 distances whatever the
+/// storage, so thresholds and scores mean the same in every layout; int8 and
+/// hamming distances are converted to the L2 distance they approximate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VectorLayout {
+    pub storage: VectorStorage,
+    pub rescore: bool,
+    pub dimension: usize,
+}
+
+impl VectorLayout {
+    /// Binary vectors need a dimension that is a whole number of bytes;
+    /// other dimensions fall back to int8.
+    pub fn new(storage: VectorStorage, rescore: bool, dimension: usize) -> Self {
+        let storage = match storage {
+            VectorStorage::Binary if !dimension.is_multiple_of(8) => VectorStorage::Int8,
+            storage => storage,
+        };
+        Self {
+            storage,
+            rescore,
+            dimension,
+        }
+    }
+
+    /// Whether full-precision copies are kept next to the quantized vectors.
+    pub fn keeps_full_vectors(&self) -> bool {
+        self.rescore && self.storage != VectorStorage::Float
+    }
+
+    /// Largest `k` `knn_sql` can be given, leaving room for the oversampling of re-scoring.
+    pub fn max_k(&self) -> i64 {
+        if self.keeps_full_vectors() {
+            KNN_MAX_K / RESCORE_OVERSAMPLING
+        } else {
+            KNN_MAX_K
+        }
+    }
+
+    /// `param`, a float32 vector, in the storage's vector type.
+    pub fn vector_sql(&self, param: &str) -> String {
+        match self.storage {
+            VectorStorage::Float => param.to_string(),
+            VectorStorage::Int8 => format!("vec_quantize_int8({}, 'unit')", param),
+            VectorStorage::Binary => format!("vec_quantize_binary({})", param),
+        }
+    }
+
+    /// Distance between the stored vector of chunk `c` / vector `v` and the
+    /// float32 vector `param`, in the units `to_l2` converts from. With re-scoring,
+    /// a chunk without a full-precision copy yet is measured by its quantized vector.
+    pub fn distance_sql(&self, param: &str) -> String {
+        if self.keeps_full_vectors() {
+            return format!(
+                "CASE WHEN c.embedding IS NULL THEN {} ELSE vec_distance_l2(c.embedding, {}) END",
+                self.quantized_l2_sql(param),
+                param
+            );
+        }
+        match self.storage {
+            VectorStorage::Binary => {
+                format!("vec_distance_hamming(v.embedding, {})", self.vector_sql(param))
+            }
+            _ => format!("vec_distance_l2(v.embedding, {})", self.vector_sql(param)),
+        }
+    }
+
+    /// L2 distance between the stored vector `v` and the float32 vector `param`,
+    /// as approximated from the quantized vector; `to_l2` in SQL. SQLite is built
+    /// without math functions, so the sine is a Taylor series, good to 2e-4 over
+    /// the half angles hamming distances give.
+    fn quantized_l2_sql(&self, param: &str) -> String {
+        match self.storage {
+            VectorStorage::Float => format!("vec_distance_l2(v.embedding, {})", param),
+            VectorStorage::Int8 => format!(
+                "vec_distance_l2(v.embedding, {}) / {}",
+                self.vector_sql(param),
+                INT8_SCALE
+            ),
+            VectorStorage::Binary => format!(
+                "(SELECT 2.0 * (x - x * x * x / 6.0 + x * x * x * x * x / 120.0
+                                - x * x * x * x * x * x * x / 5040.0)
+                  FROM (SELECT vec_distance_hamming(v.embedding, {}) * {} AS x))",
+                self.vector_sql(param),
+                std::f64::consts::PI / self.dimension.max(1) as f64 / 2.0
+            ),
+        }
+    }
+
+    /// Subquery of the `k` chunks of `model` nearest to the float32 vector
+    /// `embedding`, as (chunk_id, distance). With re-scoring, the coarse search
+    /// fetches more candidates than `k`, and chunks without a full-precision copy
+    /// yet keep their quantized distance until they are re-embedded.
+    pub fn knn_sql(&self, embedding: &str, k: &str, model: &str) -> String {
+        if self.keeps_full_vectors() {
+            return format!(
+                "SELECT chunk_id, distance FROM (
+                    SELECT v.rowid AS chunk_id, {distance} AS distance
+                    FROM vec_blocks v
+                    JOIN block_chunks c ON c.id = v.rowid
+                    WHERE v.embedding MATCH {query}
+                      AND k = {k} * {oversampling}
+                      AND v.model_id = {model}
+                 )
+                 ORDER BY distance
+                 LIMIT {k}",
+                distance = self.distance_sql(embedding),
+                query = self.vector_sql(embedding),
+                oversampling = RESCORE_OVERSAMPLING,
+            );
+        }
+        format!(
+            "SELECT rowid AS chunk_id, distance
+             FROM vec_blocks
+             WHERE embedding MATCH {query}
+               AND k = {k}
+               AND model_id = {model}",
+            query = self.vector_sql(embedding),
+        )
+    }
+
+    /// Converts an L2 distance threshold into the units of `knn_sql` and `distance_sql`.
+    pub fn native_distance(&self, l2: f32) -> f64 {
+        if self.keeps_full_vectors() {
+            return l2 as f64;
+        }
+        match self.storage {
+            VectorStorage::Float => l2 as f64,
+            VectorStorage::Int8 => (l2 * INT8_SCALE) as f64,
+            // For unit vectors the share of differing sign bits approximates
+            // angle / pi, and the L2 distance is 2 sin(angle / 2)
+            VectorStorage::Binary => {
+                let angle = 2.0 * (l2 as f64 / 2.0).clamp(-1.0, 1.0).asin();
+                angle / std::f64::consts::PI * self.dimension as f64
+            }
+        }
+    }
+
+    /// Converts a distance reported by `knn_sql` or `distance_sql` into an L2 distance.
+    pub fn to_l2(self, native: f32) -> f32 {
+        if self.keeps_full_vectors() {
+            return native;
+        }
+        match self.storage {
+            VectorStorage::Float => native,
+            VectorStorage::Int8 => native / INT8_SCALE,
+            VectorStorage::Binary => {
+                let angle = native as f64 / self.dimension.max(1) as f64 * std::f64::consts::PI;
+                (2.0 * (angle / 2.0).sin()) as f32
+            }
+        }
+    }
+}
+
+/// Layout `vec_blocks` currently has, as recorded in `embedding_metadata`.
+pub fn vector_layout(conn: &Connection) -> rusqlite::Result<VectorLayout> {
+    let recorded: Option<(String, bool)> = conn
+        .query_row(
+            "SELECT storage, rescore FROM embedding_metadata WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    let (storage, rescore) = recorded
+        .map(|(storage, rescore)| (parse_storage(&storage), rescore))
+        .unwrap_or((VectorStorage::Float, false));
+    let dimension = vector_dimension(conn)?.unwrap_or_default();
+
+    Ok(VectorLayout {
+        storage,
+        rescore,
+        dimension,
+    })
+}
+
+/// Dimension `vec_blocks` was created with, or `None` if the table doesn't exist yet.
+pub fn vector_dimension(conn: &Connection) -> rusqlite::Result<Option<usize>> {
+    let sql: Option<String> = conn
+        .query_row(
+            "SELECT sql FROM sqlite_master WHERE name = 'vec_blocks'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    // The embedding column is the only one with a size, e.g. `int8[1024]`
+    Ok(sql.and_then(|sql| {
+        let start = sql.rfind('[')? + 1;
+        let end = start + sql[start..].find(']')?;
+        sql[start..end].trim().parse().ok()
+    }))
+}
+
+/// Records `model_id` and `dimension` as the ones the index is built for, and
+/// returns the previously recorded pair if it differs.
+pub fn sync_embedding_metadata(
+    conn: &Connection,
+    model_id: &str,
+    dimension: usize,
+) -> rusqlite::Result<Option<(String, usize)>> {
+    let previous: Option<(String, i64)> = conn
+        .query_row(
+            "SELECT model_id, dimension FROM embedding_metadata WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    let previous = previous.map(|(previous_model, previous_dimension)| {
+        (previous_model, previous_dimension as usize)
+    });
+
+    if previous.as_ref() == Some(&(model_id.to_string(), dimension)) {
+        return Ok(None);
+    }
+
+    conn.execute(
+        "INSERT INTO embedding_metadata (id, model_id, dimension) VALUES (1, ?1, ?2)
+         ON CONFLICT(id) DO UPDATE SET
+            model_id = excluded.model_id,
+            dimension = excluded.dimension,
+            updated_at = datetime('now', 'localtime')",
+        rusqlite::params![model_id, dimension as i64],
+    )?;
+
+    Ok(previous)
+}
+
+/// The model other than `model_id` that most stored block vectors were built with,
+/// if any: blocks not yet re-embedded since the model changed. Vectors a block was
+/// since re-embedded over don't count.
+pub fn previous_vector_model(conn: &Connection, model_id: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row(
+        "SELECT c.model_id FROM block_chunks c
+         WHERE c.model_id != ?1
+           AND NOT EXISTS (
+               SELECT 1 FROM block_chunks newer
+               WHERE newer.block_id = c.block_id AND newer.model_id != c.model_id AND newer.id > c.id
+           )
+         GROUP BY c.model_id
+         ORDER BY COUNT(*) DESC
+         LIMIT 1",
+        [model_id],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Recreates `vec_blocks` and `vec_documents` with a new dimension. Existing
+/// vectors can't be converted, so they are dropped along with their chunks and
+/// every block is queued for indexing again. A reindex in progress is dropped
+/// too, since its shadow vectors have the old dimension.
+pub fn recreate_vector_table(conn: &mut Connection, dimension: usize) -> rusqlite::Result<()> {
+    let storage = VectorLayout::new(vector_layout(conn)?.storage, false, dimension).storage;
+    let tx = conn.transaction()?;
+
+    tx.execute("DROP TABLE IF EXISTS vec_blocks", [])?;
+    tx.execute(&vec_blocks_sql(dimension, storage), [])?;
+    tx.execute("DROP TABLE IF EXISTS vec_documents", [])?;
+    tx.execute(&vec_documents_sql(dimension), [])?;
+    tx.execute("DELETE FROM document_vectors", [])?;
+    tx.execute("DELETE FROM block_chunks", [])?;
+    tx.execute_batch(
+        "DELETE FROM shadow_block_chunks;
+         DELETE FROM reindex_queue;
+         DELETE FROM reindex_job;",
+    )?;
+    tx.execute("UPDATE blocks SET indexing_status = 0", [])?;
+
+    tx.commit()?;
+    Ok(())
+}
+
+/// Rebuilds `vec_blocks` for `layout` if it is stored differently, and returns
+/// whether anything changed. Vectors are converted from full-precision ones when
+/// there are any: a float table, or the copies kept for re-scoring. Otherwise the
+/// index starts over and every block is queued for indexing again.
+pub fn apply_vector_layout(conn: &mut Connection, layout: VectorLayout) -> rusqlite::Result<bool> {
+    let current = vector_layout(conn)?;
+    if current == layout {
+        return Ok(false);
+    }
+
+    // Where the full-precision vectors are, if anywhere
+    let full_vectors = if current.storage == VectorStorage::Float {
+        Some("v.embedding")
+    } else if current.keeps_full_vectors() {
+        Some("c.embedding")
+    } else {
+        None
+    };
+
+    let tx = conn.transaction()?;
+
+    if current.storage == layout.storage && current.dimension == layout.dimension {
+        // Only re-scoring was switched: keep or drop the full-precision copies
+        if !layout.keeps_full_vectors() {
+            tx.execute("UPDATE block_chunks SET embedding = NULL", [])?;
+        } else if full_vectors.is_none() {
+            // The quantized vectors stay in use while blocks are re-embedded with copies
+            info!("Re-embedding blocks to keep full-precision vectors for re-scoring");
+            tx.execute(
+                "UPDATE blocks SET indexing_status = 0
+                 WHERE id IN (SELECT block_id FROM block_chunks WHERE embedding IS NULL)",
+                [],
+            )?;
+        }
+    } else if let Some(full_vectors) = full_vectors {
+        // Chunks indexed before re-scoring was turned on have no copy to convert
+        if full_vectors == "c.embedding" {
+            tx.execute(
+                "UPDATE blocks SET indexing_status = 0
+                 WHERE id IN (SELECT block_id FROM block_chunks WHERE embedding IS NULL)",
+                [],
+            )?;
+        }
+        info!(
+            "Converting vectors from {} to {}",
+            storage_name(current.storage),
+            storage_name(layout.storage)
+        );
+        tx.execute_batch(&format!(
+            "CREATE TEMP TABLE vec_blocks_backup AS
+                SELECT v.rowid AS id, v.model_id, {} AS embedding
+                FROM vec_blocks v
+                JOIN block_chunks c ON c.id = v.rowid;
+
+            DROP TABLE vec_blocks;",
+            full_vectors
+        ))?;
+        tx.execute(&vec_blocks_sql(layout.dimension, layout.storage), [])?;
+        tx.execute(
+            &format!(
+                "INSERT INTO vec_blocks (rowid, model_id, embedding)
+                 SELECT id, model_id, {} FROM vec_blocks_backup WHERE embedding IS NOT NULL",
+                layout.vector_sql("embedding")
+            ),
+            [],
+        )?;
+        if layout.keeps_full_vectors() {
+            tx.execute(
+                "UPDATE block_chunks
+                 SET embedding = (SELECT embedding FROM vec_blocks_backup b WHERE b.id = block_chunks.id)",
+                [],
+            )?;
+        } else {
+            tx.execute("UPDATE block_chunks SET embedding = NULL", [])?;
+        }
+        tx.execute("DROP TABLE vec_blocks_backup", [])?;
+    } else {
+        // Quantized vectors can't be turned back into full ones
+        info!(
+            "Rebuilding the vector index as {}, re-indexing all blocks",
+            storage_name(layout.storage)
+        );
+        tx.execute("DROP TABLE IF EXISTS vec_blocks", [])?;
+        tx.execute(&vec_blocks_sql(layout.dimension, layout.storage), [])?;
+        tx.execute("DELETE FROM block_chunks", [])?;
+        tx.execute("UPDATE blocks SET indexing_status = 0", [])?;
+    }
+
+    tx.execute(
+        "UPDATE embedding_metadata SET storage = ?1, rescore = ?2 WHERE id = 1",
+        rusqlite::params![storage_name(layout.storage), layout.rescore],
+    )?;
+
+    tx.commit()?;
+    Ok(true)
+}
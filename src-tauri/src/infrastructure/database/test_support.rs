@@ -0,0 +1,21 @@
+use crate::infrastructure::database::connection::create_connection;
+use crate::infrastructure::database::migrations::run_migrations;
+use crate::infrastructure::database::schema::init_schema;
+use rusqlite::Connection;
+use tempfile::TempDir;
+
+/// Model id the vectors of test databases are recorded with.
+pub const TEST_MODEL_ID: &str = "test-model";
+/// Vector size of test databases.
+pub const TEST_DIMENSION: usize = 8;
+
+/// A fully migrated database in a temporary directory, which is removed when
+/// the returned `TempDir` is dropped.
+pub fn test_database() -> (TempDir, Connection) {
+    std::env::set_var("DATABASE_NAME", "test.db");
+    let dir = TempDir::new().unwrap();
+    let mut conn = create_connection(&dir.path().to_path_buf()).unwrap();
+    init_schema(&mut conn, TEST_DIMENSION).unwrap();
+    run_migrations(&mut conn, TEST_MODEL_ID).unwrap();
+    (dir, conn)
+}
@@ -2,7 +2,9 @@ mod domains;
 mod infrastructure;
 mod utils;
 
+use crate::domains::config::state::ConfigState;
 use crate::domains::document::scheduler::INDEXING_SCHEDULER;
+use crate::infrastructure::database::pool::DatabaseState;
 use crate::utils::shortcuts::AppShortcuts;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -20,6 +22,8 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
+        .manage(ConfigState::default())
+        .manage(DatabaseState::default())
         .setup(|app| {
             AppShortcuts::setup_menu(app.handle())?;
 
@@ -43,15 +47,40 @@ pub fn run() {
             domains::document::command::retrieve_document,
             domains::document::command::delete_document,
             domains::document::command::search_documents,
+            domains::document::command::find_similar_documents,
+            domains::document::command::get_backlinks,
             // Block
+            domains::document::command::get_block,
             domains::document::command::delete_block,
             // Reindexing
             domains::document::command::trigger_reindex_all,
+            domains::document::command::trigger_reindex,
+            domains::document::command::cancel_reindex,
+            domains::document::command::get_indexing_stats,
+            domains::document::command::list_failed_blocks,
+            domains::document::command::retry_failed_blocks,
+            domains::document::command::get_indexing_progress,
+            domains::document::command::pause_indexing,
+            domains::document::command::resume_indexing,
+            domains::document::command::cancel_indexing,
+            domains::document::command::set_indexing_interval,
+            domains::document::command::benchmark_vector_storage,
             // Graph
             domains::document::command::get_graph_data,
+            domains::document::command::get_document_neighborhood,
+            domains::document::command::pin_edge,
+            domains::document::command::block_edge,
+            domains::document::command::unblock_edge,
+            domains::document::command::annotate_edge,
+            domains::document::command::explain_edge,
             // AI
             domains::ai::command::generate_tags,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                INDEXING_SCHEDULER.stop();
+            }
+        });
 }
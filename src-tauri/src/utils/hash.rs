@@ -0,0 +1,15 @@
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a hash. Stable across builds and platforms, unlike `DefaultHasher`,
+/// so it is safe to persist.
+pub fn fnv1a_64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Hex-encoded hash of a block's text, used to detect content changes.
+pub fn content_hash(text: &str) -> String {
+    format!("{:016x}", fnv1a_64(text.as_bytes()))
+}
@@ -1,4 +1,5 @@
 pub mod app_data;
 pub mod error_logger;
 pub mod file_system;
+pub mod hash;
 pub mod shortcuts;
@@ -57,6 +57,8 @@ export const useIsRightSidebarOpen = () =>
 export const useTabs = () => useAppStore((state) => state.tabs);
 export const useActiveDocumentId = () =>
   useAppStore((state) => state.activeDocumentId);
+export const useActiveBlockId = () =>
+  useAppStore((state) => state.activeTab?.blockId);
 // DocumentSlice
 export const useDocument = () => useAppStore((state) => state.document);
 export const useBlockInEditing = () =>
@@ -103,6 +105,8 @@ export const getStoreActions = () => {
     switchToPreviousBlock: state.switchToPreviousBlock,
     switchToNextBlock: state.switchToNextBlock,
     updateBlockContent: state.updateBlockContent,
+    setBlockReference: state.setBlockReference,
+    detachBlockReference: state.detachBlockReference,
     // SettingsSlice
     openSettings: state.openSettings,
     closeSettings: state.closeSettings,
@@ -116,6 +120,7 @@ export const getStoreActions = () => {
     applyEdgeChanges: state.applyEdgeChanges,
     // SidebarSlice
     setDocumentList: state.setDocumentList,
+    appendDocumentList: state.appendDocumentList,
     addDocumentToList: state.addDocumentToList,
     removeDocumentFromList: state.removeDocumentFromList,
     updateDocumentInList: state.updateDocumentInList,
@@ -1,7 +1,7 @@
 import { documentApi } from '@/shared/api/document.api';
 import { BlockFactory } from '@/shared/lib/factory';
 
-import type { Block, Document } from '@/core/types';
+import type { Block, BlockReferenceKind, Document } from '@/core/types';
 import type { StateCreator } from 'zustand';
 
 // ============================================
@@ -29,6 +29,12 @@ export interface DocumentSliceActions {
   ) => void;
   switchToNextBlock: (eventTriggeredBlock: Block, cursorOffset: number) => void;
   updateBlockContent: (block: Block, content: string) => void;
+  setBlockReference: (
+    block: Block,
+    sourceBlockId: string,
+    referenceKind: BlockReferenceKind,
+  ) => Promise<boolean>;
+  detachBlockReference: (block: Block) => void;
 }
 
 export type DocumentSlice = DocumentSliceState & DocumentSliceActions;
@@ -176,7 +182,54 @@ export const createDocumentSlice: StateCreator<
     if (blockIndex === -1) return;
 
     const newBlocks = blocks.map((b, index) =>
-      index === blockIndex ? { ...block, content } : b,
+      index === blockIndex ? { ...b, content } : b,
+    );
+
+    set({ document: { ...document, blocks: newBlocks } });
+  },
+  setBlockReference: async (
+    block: Block,
+    sourceBlockId: string,
+    referenceKind: BlockReferenceKind,
+  ) => {
+    const response = await documentApi.getBlock(sourceBlockId);
+    const source = response.success ? response.data : null;
+    if (!source || source.id === block.id) return false;
+
+    const document = get().document;
+    if (!document) return false;
+
+    // A reference to a reference points at the original block
+    const newBlocks = document.blocks.map((b) =>
+      b.id === block.id
+        ? {
+            ...b,
+            content: source.content,
+            sourceBlockId: source.sourceBlockId ?? source.id,
+            sourceDocumentId: source.sourceDocumentId ?? source.documentId,
+            referenceKind,
+            sourceChanged: false,
+          }
+        : b,
+    );
+
+    set({ document: { ...document, blocks: newBlocks } });
+    return true;
+  },
+  detachBlockReference: (block: Block) => {
+    const document = get().document;
+    if (!document) return;
+
+    const newBlocks = document.blocks.map((b) =>
+      b.id === block.id
+        ? {
+            ...b,
+            sourceBlockId: null,
+            sourceDocumentId: null,
+            referenceKind: null,
+            sourceChanged: false,
+          }
+        : b,
     );
 
     set({ document: { ...document, blocks: newBlocks } });
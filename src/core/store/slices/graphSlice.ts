@@ -1,4 +1,9 @@
-import type { DocumentGraphInfo, EdgeChangeInfo, GraphData } from '../../types';
+import type {
+  DocumentGraphInfo,
+  EdgeChangeInfo,
+  GraphData,
+  GraphEdge,
+} from '../../types';
 import type { StateCreator } from 'zustand';
 
 export interface GraphSliceState {
@@ -118,24 +123,23 @@ export const createGraphSlice: StateCreator<GraphSlice, [], [], GraphSlice> = (
 
     const { addedEdges, removedEdges } = changes;
 
+    // Two documents can be both similar and linked, so the relation is part of the key
+    const edgeKey = (e: GraphEdge) =>
+      `${e.source}-${e.target}-${e.relationType ?? ''}`;
+
     // Create a set of edge keys to remove
-    const removeKeys = new Set(
-      removedEdges.map((e) => `${e.source}-${e.target}`),
-    );
+    const removeKeys = new Set(removedEdges.map(edgeKey));
 
     // Filter out removed edges
     const filteredEdges = current.edges.filter(
-      (edge) => !removeKeys.has(`${edge.source}-${edge.target}`),
+      (edge) => !removeKeys.has(edgeKey(edge)),
     );
 
     // Add new edges (avoid duplicates)
-    const existingKeys = new Set(
-      filteredEdges.map((e) => `${e.source}-${e.target}`),
+    const existingKeys = new Set(filteredEdges.map(edgeKey));
+    const edgesToAdd = addedEdges.filter(
+      (edge) => !existingKeys.has(edgeKey(edge)),
     );
-    const edgesToAdd = addedEdges.filter((edge) => {
-      const key = `${edge.source}-${edge.target}`;
-      return !existingKeys.has(key);
-    });
 
     const newEdges = [...filteredEdges, ...edgesToAdd];
 
@@ -16,6 +16,7 @@ export interface SidebarSliceState {
 
 export interface SidebarSliceActions {
   setDocumentList: (documents: DocumentListItem[]) => void;
+  appendDocumentList: (documents: DocumentListItem[]) => void;
   addDocumentToList: (document: DocumentListItem) => void;
   removeDocumentFromList: (documentId: string) => void;
   updateDocumentInList: (
@@ -44,6 +45,17 @@ export const createSidebarSlice: StateCreator<
 
   setDocumentList: (documentList) => set({ documentList }),
 
+  appendDocumentList: (documents) =>
+    set((state) => {
+      const listedIds = new Set(state.documentList.map((doc) => doc.id));
+      return {
+        documentList: [
+          ...state.documentList,
+          ...documents.filter((doc) => !listedIds.has(doc.id)),
+        ],
+      };
+    }),
+
   addDocumentToList: (document) =>
     set((state) => ({
       documentList: [document, ...state.documentList],
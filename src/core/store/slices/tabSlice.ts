@@ -54,10 +54,11 @@ export const createTabSlice: StateCreator<TabSlice, [], [], TabSlice> = (
     });
   },
   switchTab: (tab: Tab) => {
-    const newTabs = get().tabs.map((t) => ({
-      ...t,
-      isActive: t.documentId === tab.documentId,
-    }));
+    const newTabs = get().tabs.map((t) =>
+      t.documentId === tab.documentId
+        ? { ...t, blockId: tab.blockId, isActive: true }
+        : { ...t, isActive: false },
+    );
     const activeTab = newTabs.find((t) => t.isActive);
 
     set({
@@ -8,6 +8,30 @@ export interface Tab {
 
 export interface VectorSettings {
   similarityThreshold: number; // 0.0 ~ 1.0
+  keywordWeight?: number; // hybrid search weight of title/tag/content matches
+  semanticWeight?: number; // hybrid search weight of vector similarity
+  rrfK?: number; // reciprocal rank fusion constant
+  storage?: VectorStorage; // how vectors are stored, default 'float'
+  rescore?: boolean; // re-score quantized matches with full-precision copies
+}
+
+export type VectorStorage = 'float' | 'int8' | 'binary';
+
+export const VECTOR_STORAGES: {
+  value: VectorStorage;
+  label: string;
+}[] = [
+  { value: 'float', label: 'Full precision (Default)' },
+  { value: 'int8', label: 'int8 (4x smaller)' },
+  { value: 'binary', label: 'Binary (32x smaller)' },
+];
+
+export interface VectorStorageBenchmark {
+  storage: VectorStorage;
+  rescore: boolean;
+  recall: number; // share of the exact nearest neighbours found, 0.0 ~ 1.0
+  bytesPerVector: number;
+  totalBytes: number;
 }
 
 export interface GraphColors {
@@ -22,6 +46,25 @@ export interface GraphSettings {
   colors: GraphColors;
 }
 
+export type EmbeddingProviderKind = 'onnx' | 'openAiCompatible' | 'fallback';
+
+export const EMBEDDING_PROVIDERS: {
+  value: EmbeddingProviderKind;
+  label: string;
+}[] = [
+  { value: 'onnx', label: 'Bundled BGE-M3 (Default)' },
+  { value: 'openAiCompatible', label: 'OpenAI-compatible endpoint' },
+  { value: 'fallback', label: 'Keyword hashing (no model)' },
+];
+
+export interface EmbeddingSettings {
+  provider: EmbeddingProviderKind;
+  model: string | null; // model name sent to the endpoint
+  baseUrl: string | null; // e.g. http://localhost:11434/v1
+  apiKey: string | null;
+  dimension: number | null; // probed from the endpoint when null
+}
+
 export type GeminiModel =
   | 'gemini-2.5-flash'
   | 'gemini-2.0-flash'
@@ -43,6 +86,7 @@ export interface AppConfig {
   tabs: Tab[];
   vectorSettings: VectorSettings;
   graphSettings: GraphSettings;
+  embeddingSettings: EmbeddingSettings;
   geminiApiKey: string | null;
   geminiModel: GeminiModel;
 }
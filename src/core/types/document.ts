@@ -14,15 +14,82 @@ export interface Block {
   content: string | null;
   orderIndex: number;
   sourceDocumentId: string | null;
+  sourceBlockId: string | null; // the block this one embeds or quotes
+  referenceKind: BlockReferenceKind | null;
+  sourceChanged?: boolean; // a quote whose source was edited since it was copied
   indexingStatus: IndexingStatus;
 }
 
+// 'embed' follows its source block; 'quote' is a copy of it
+export type BlockReferenceKind = 'embed' | 'quote';
+
+// Document Listing
+export type DocumentSortKey = 'updated' | 'created' | 'title' | 'connections';
+
+export interface DocumentListRequest {
+  cursor?: string | null;
+  limit?: number;
+  sortBy?: DocumentSortKey;
+  direction?: 'asc' | 'desc';
+  statuses?: DocumentStatus[];
+  tag?: string;
+  query?: string;
+}
+
+export interface DocumentPage {
+  items: Document[];
+  nextCursor: string | null;
+  totalCount: number;
+}
+
 // Document Status: 0 = FLEETING, 1 = PERMANENT, 99 = ARCHIVED
 export type DocumentStatus = number;
 
 // Indexing Status: 0 = PENDING, 1 = INDEXED, 2 = FAILED
 export type IndexingStatus = number;
 
+// A block that exhausted its indexing attempts
+export interface FailedBlock {
+  blockId: string;
+  documentId: string;
+  documentTitle: string | null;
+  excerpt: string | null;
+  attempts: number;
+  lastError: string | null;
+  updatedAt: string | null;
+}
+
+export type IndexingState = 'running' | 'idle' | 'paused' | 'waitingForProvider';
+
+// Blocks a reindex covers
+export type ReindexScope =
+  | { kind: 'all' }
+  | { kind: 'document'; documentId: string }
+  | { kind: 'tag'; tag: string };
+
+// A reindex in progress, building new vectors while the current ones stay in use
+export interface ReindexProgress {
+  scope: ReindexScope;
+  total: number;
+  embedded: number;
+  failed: number;
+  startedAt: string | null;
+  // The embedding model changed since the reindex started; it resumes once it is back
+  paused: boolean;
+}
+
+// Payload of the 'indexing-progress' event
+export interface IndexingProgress {
+  state: IndexingState;
+  queueLength: number;
+  failed: number;
+  indexedThisSession: number;
+  blocksPerSecond: number | null;
+  etaSeconds: number | null;
+  idleIntervalSecs: number;
+  reindex: ReindexProgress | null;
+}
+
 // Cursor Position
 export interface CursorPosition {
   line: number;
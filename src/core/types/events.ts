@@ -1,3 +1,4 @@
+import type { IndexingProgress } from './document';
 import type { EdgeChangeInfo } from './graph';
 
 export interface DocumentDeletedEvent {
@@ -16,4 +17,5 @@ export interface TauriEventRegistry {
   'graph-edge-changed': EdgeChangeInfo;
   'document-deleted': DocumentDeletedEvent;
   'document-updated': DocumentUpdatedEvent;
+  'indexing-progress': IndexingProgress;
 }
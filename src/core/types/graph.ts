@@ -2,13 +2,18 @@ export interface GraphNode {
   id: string;
   label: string;
   nodeType: 'document' | 'tag';
+  hop?: number | null;
 }
 
 export interface GraphEdge {
   source: string;
   target: string;
   edgeType: 'document-document' | 'document-tag';
+  relationType?: 'similar' | 'link' | null;
   weight?: number;
+  pinned?: boolean; // kept by the indexing scheduler whatever the similarity
+  label?: string | null;
+  note?: string | null;
 }
 
 export interface GraphData {
@@ -27,3 +32,24 @@ export interface EdgeChangeInfo {
   addedEdges: GraphEdge[];
   removedEdges: GraphEdge[];
 }
+
+export interface BlockPairEvidence {
+  sourceBlockId: string;
+  sourceSnippet?: string | null;
+  targetBlockId: string;
+  targetSnippet?: string | null;
+  similarity: number;
+}
+
+// Block pairs count when their distance (1 - similarity) is below similarityThreshold
+export interface EdgeExplanation {
+  sourceId: string;
+  targetId: string;
+  weight?: number | null;
+  pinned: boolean;
+  documentSimilarity?: number | null;
+  similarityThreshold: number;
+  blockPairs: BlockPairEvidence[];
+  // Closest chunks measured now, for an edge drawn from the document vectors
+  blockPairsMeasured: boolean;
+}
@@ -3,17 +3,34 @@ export type {
   Tab,
   DatabaseHealth,
   VectorSettings,
+  VectorStorage,
+  VectorStorageBenchmark,
   GraphColors,
   GraphSettings,
+  EmbeddingProviderKind,
+  EmbeddingSettings,
   GeminiModel,
 } from './config';
-export { GEMINI_MODELS } from './config';
+export {
+  EMBEDDING_PROVIDERS,
+  GEMINI_MODELS,
+  VECTOR_STORAGES,
+} from './config';
 export type { ApiResponse } from './api';
 export type {
   Document,
   Block,
+  BlockReferenceKind,
+  DocumentListRequest,
+  DocumentPage,
+  DocumentSortKey,
   DocumentStatus,
   IndexingStatus,
+  FailedBlock,
+  IndexingProgress,
+  IndexingState,
+  ReindexScope,
+  ReindexProgress,
   CursorPosition,
 } from './document';
 export type {
@@ -22,8 +39,15 @@ export type {
   GraphData,
   DocumentGraphInfo,
   EdgeChangeInfo,
+  EdgeExplanation,
+  BlockPairEvidence,
 } from './graph';
-export type { SearchResult } from './search';
+export type {
+  SearchResult,
+  BlockHit,
+  RelatedDocument,
+  Backlink,
+} from './search';
 export type {
   DocumentDeletedEvent,
   DocumentUpdatedEvent,
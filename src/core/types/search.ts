@@ -3,7 +3,35 @@ export interface SearchResult {
   title: string | null;
   tags: string[] | null;
   status: number;
-  matchType: 'title' | 'tag' | 'content' | 'similar';
+  matchType: 'title' | 'tag' | 'content' | 'similar' | 'filter';
   matchSnippet: string | null;
   similarityScore: number | null;
+  score: number; // hybrid ranking score, higher is better
+  matchedSignals: ('title' | 'tag' | 'content' | 'semantic' | 'filter')[];
+  blockHits: BlockHit[]; // matching blocks, best first
+}
+
+// A document similar to another as a whole, for "related notes"
+export interface RelatedDocument {
+  id: string;
+  title: string | null;
+  tags: string[] | null;
+  status: number;
+  similarityScore: number;
+}
+
+// A document linking to another with [[...]] references, and the referencing blocks
+export interface Backlink {
+  id: string;
+  title: string | null;
+  tags: string[] | null;
+  status: number;
+  blocks: BlockHit[];
+}
+
+export interface BlockHit {
+  blockId: string;
+  orderIndex: number;
+  snippet: string | null; // may contain <mark> highlights
+  similarityScore: number | null;
 }
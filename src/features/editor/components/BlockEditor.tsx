@@ -1,6 +1,6 @@
 'use client';
 
-import React, { useEffect, memo, useMemo } from 'react';
+import React, { useCallback, useEffect, memo, useMemo } from 'react';
 
 import Placeholder from '@tiptap/extension-placeholder';
 import { useEditor } from '@tiptap/react';
@@ -19,6 +19,9 @@ import { BlockEditorView } from './BlockEditorView';
 
 import type { Block } from '@/core/types';
 
+// A block containing only ((block-id)) embeds that block; > ((block-id)) quotes it
+const BLOCK_REFERENCE_PATTERN = /^(>\s*)?\(\(([\w-]+)\)\)$/;
+
 interface BlockEditorProps {
   block: Block;
 }
@@ -47,6 +50,7 @@ const BlockEditorComponent: React.FC<BlockEditorProps> = ({ block }) => {
       }),
     ],
     content: block.content || '',
+    editable: block.referenceKind !== 'embed',
     immediatelyRender: false,
     editorProps: {
       attributes: {
@@ -66,6 +70,15 @@ const BlockEditorComponent: React.FC<BlockEditorProps> = ({ block }) => {
           return true;
         }
 
+        if (
+          (event.metaKey || event.ctrlKey) &&
+          event.shiftKey &&
+          event.key.toLowerCase() === 'c'
+        ) {
+          navigator.clipboard.writeText(`((${block.id}))`);
+          return true;
+        }
+
         if (event.key === 'Enter' && !event.shiftKey) {
           if (manager.isEmptyDocument()) {
             addNewBlock(block);
@@ -105,10 +118,53 @@ const BlockEditorComponent: React.FC<BlockEditorProps> = ({ block }) => {
     },
     onUpdate: ({ editor }) => {
       const text = editor.getText();
+
+      // The block prop is the one the editor was created with
+      const current = useAppStore
+        .getState()
+        .document?.blocks.find((b) => b.id === block.id);
+      const reference = text.trim().match(BLOCK_REFERENCE_PATTERN);
+      if (reference && !current?.sourceBlockId) {
+        debouncedUpdateContent.cancel();
+        getStoreActions()
+          .setBlockReference(
+            block,
+            reference[2],
+            reference[1] ? 'quote' : 'embed',
+          )
+          .then((found) => {
+            if (!found) {
+              getStoreActions().updateBlockContent(block, text);
+            }
+          });
+        return;
+      }
+
       debouncedUpdateContent(block, text);
     },
   });
 
+  useEffect(() => {
+    if (!editor) return;
+    editor.setEditable(block.referenceKind !== 'embed');
+  }, [editor, block.referenceKind]);
+
+  // Show the source's content when the block starts referencing it or a quote is updated
+  useEffect(() => {
+    if (!editor || !block.sourceBlockId) return;
+    editor.commands.setContent(block.content || '', { emitUpdate: false });
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [editor, block.sourceBlockId, block.sourceChanged]);
+
+  const handleUpdateQuote = useCallback(() => {
+    if (!block.sourceBlockId) return;
+    getStoreActions().setBlockReference(block, block.sourceBlockId, 'quote');
+  }, [block]);
+
+  const handleDetach = useCallback(() => {
+    getStoreActions().detachBlockReference(block);
+  }, [block]);
+
   useEffect(() => {
     if (!editor) return;
     if (!blockInEditing) return;
@@ -131,7 +187,15 @@ const BlockEditorComponent: React.FC<BlockEditorProps> = ({ block }) => {
     };
   }, [debouncedUpdateContent]);
 
-  return <BlockEditorView editor={editor} />;
+  return (
+    <BlockEditorView
+      editor={editor}
+      referenceKind={block.referenceKind}
+      sourceChanged={block.sourceChanged ?? false}
+      onUpdateQuote={handleUpdateQuote}
+      onDetach={handleDetach}
+    />
+  );
 };
 
 export const BlockEditor = memo(
@@ -139,7 +203,10 @@ export const BlockEditor = memo(
   (prevProps, nextProps) => {
     return (
       prevProps.block.id === nextProps.block.id &&
-      prevProps.block.content === nextProps.block.content
+      prevProps.block.content === nextProps.block.content &&
+      prevProps.block.sourceBlockId === nextProps.block.sourceBlockId &&
+      prevProps.block.referenceKind === nextProps.block.referenceKind &&
+      prevProps.block.sourceChanged === nextProps.block.sourceChanged
     );
   },
 );
@@ -4,23 +4,63 @@ import React from 'react';
 
 import { EditorContent, Editor } from '@tiptap/react';
 
+import type { BlockReferenceKind } from '@/core/types';
+
 interface BlockEditorViewProps {
   editor: Editor | null;
+  referenceKind?: BlockReferenceKind | null;
+  sourceChanged?: boolean;
+  onUpdateQuote?: () => void;
+  onDetach?: () => void;
 }
 
-export const BlockEditorView: React.FC<BlockEditorViewProps> = ({ editor }) => {
+export const BlockEditorView: React.FC<BlockEditorViewProps> = ({
+  editor,
+  referenceKind,
+  sourceChanged = false,
+  onUpdateQuote,
+  onDetach,
+}) => {
   if (!editor) return null;
 
   return (
     <div
-      className="text-ctp-text leading-relaxed rounded-xl bg-ctp-surface0/50
+      className={`text-ctp-text leading-relaxed rounded-xl bg-ctp-surface0/50
                  [&_.ProseMirror]:outline-none
                  [&_.ProseMirror]:min-h-[1.5rem]
                  [&_.ProseMirror]:px-4
                  [&_.ProseMirror]:py-3
                  [&_.ProseMirror_p]:my-0
-                 [&_.ProseMirror_p]:p-0"
+                 [&_.ProseMirror_p]:p-0
+                 ${referenceKind ? 'border-l-2 border-ctp-lavender/60' : ''}`}
     >
+      {referenceKind && (
+        <div className="flex items-center gap-2 px-4 pt-2 text-[10px] text-ctp-overlay1">
+          <span>{referenceKind === 'embed' ? 'Embedded block' : 'Quote'}</span>
+          {sourceChanged && (
+            <>
+              <span className="text-ctp-peach">· source changed</span>
+              {onUpdateQuote && (
+                <button
+                  onClick={onUpdateQuote}
+                  className="text-ctp-lavender hover:underline"
+                >
+                  Update
+                </button>
+              )}
+            </>
+          )}
+          {onDetach && (
+            <button
+              onClick={onDetach}
+              title="Keep the content as a plain block"
+              className="ml-auto hover:text-ctp-text"
+            >
+              Unlink
+            </button>
+          )}
+        </div>
+      )}
       <EditorContent editor={editor} />
     </div>
   );
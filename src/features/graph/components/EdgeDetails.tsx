@@ -0,0 +1,166 @@
+'use client';
+
+import React, { useEffect, useState } from 'react';
+
+import type { EdgeExplanation, GraphEdge } from '../../../core/types';
+
+interface EdgeDetailsProps {
+  edge: GraphEdge;
+  sourceLabel: string;
+  targetLabel: string;
+  explanation: EdgeExplanation | null;
+  isBlocked: boolean;
+  onPin: (pinned: boolean) => void;
+  onBlock: () => void;
+  onUnblock: () => void;
+  onAnnotate: (label: string, note: string) => void;
+  onClose: () => void;
+}
+
+export const EdgeDetails: React.FC<EdgeDetailsProps> = ({
+  edge,
+  sourceLabel,
+  targetLabel,
+  explanation,
+  isBlocked,
+  onPin,
+  onBlock,
+  onUnblock,
+  onAnnotate,
+  onClose,
+}) => {
+  const [label, setLabel] = useState(edge.label ?? '');
+  const [note, setNote] = useState(edge.note ?? '');
+
+  useEffect(() => {
+    setLabel(edge.label ?? '');
+    setNote(edge.note ?? '');
+  }, [edge]);
+
+  const isSimilar = edge.relationType !== 'link';
+  const isAnnotationChanged =
+    label !== (edge.label ?? '') || note !== (edge.note ?? '');
+
+  return (
+    <div className="absolute left-4 top-4 w-64 space-y-2 rounded-xl bg-ctp-mantle/95 backdrop-blur-sm p-3 text-xs text-ctp-text shadow-lg border border-ctp-surface0">
+      <div className="flex items-start justify-between gap-2">
+        <p className="min-w-0">
+          <span className="block truncate font-medium">{sourceLabel}</span>
+          <span className="block truncate text-ctp-subtext0">
+            {isSimilar ? '↔' : '→'} {targetLabel}
+          </span>
+        </p>
+        <button
+          onClick={onClose}
+          className="text-ctp-overlay1 hover:text-ctp-text"
+        >
+          ✕
+        </button>
+      </div>
+
+      <p className="text-ctp-overlay1">
+        {isSimilar ? 'Similar' : 'Link'}
+        {edge.weight != null && ` · ${Math.round(edge.weight * 100)}%`}
+        {edge.pinned && ' · pinned'}
+      </p>
+
+      {isSimilar && explanation && (
+        <div className="space-y-1">
+          <p className="text-ctp-subtext0">
+            Why connected
+            {explanation.documentSimilarity != null &&
+              ` · notes ${Math.round(explanation.documentSimilarity * 100)}%`}
+          </p>
+          {explanation.blockPairsMeasured && (
+            <p className="text-ctp-overlay0">
+              Connected as whole notes. Closest passages:
+            </p>
+          )}
+          {explanation.blockPairs.length === 0 ? (
+            <p className="text-ctp-overlay0">
+              No similar blocks recorded yet. Re-index to record them.
+            </p>
+          ) : (
+            <ul className="max-h-48 space-y-1 overflow-y-auto">
+              {explanation.blockPairs.map((pair) => (
+                <li
+                  key={`${pair.sourceBlockId}-${pair.targetBlockId}`}
+                  className="rounded-lg bg-ctp-surface0/40 px-2 py-1"
+                >
+                  <span className="float-right text-ctp-overlay1">
+                    {Math.round(pair.similarity * 100)}%
+                  </span>
+                  <span className="block truncate">
+                    {pair.sourceSnippet || 'Empty block'}
+                  </span>
+                  <span className="block truncate text-ctp-subtext0">
+                    {pair.targetSnippet || 'Empty block'}
+                  </span>
+                </li>
+              ))}
+            </ul>
+          )}
+          <p className="text-ctp-overlay0">
+            Blocks count as similar above{' '}
+            {Math.round((1 - explanation.similarityThreshold) * 100)}% (similarity
+            threshold {explanation.similarityThreshold}).
+          </p>
+        </div>
+      )}
+
+      {isBlocked ? (
+        <div className="flex items-center justify-between gap-2">
+          <span className="text-ctp-subtext0">
+            These notes won&apos;t be linked again.
+          </span>
+          <button
+            onClick={onUnblock}
+            className="rounded-lg px-2 py-1 hover:bg-ctp-surface0 transition-colors"
+          >
+            Undo
+          </button>
+        </div>
+      ) : (
+        <>
+          {isSimilar && (
+            <div className="flex gap-2">
+              <button
+                onClick={() => onPin(!edge.pinned)}
+                className="flex-1 rounded-lg px-2 py-1 border border-ctp-surface0 hover:bg-ctp-surface0 transition-colors"
+              >
+                {edge.pinned ? 'Unpin' : 'Pin'}
+              </button>
+              <button
+                onClick={onBlock}
+                className="flex-1 rounded-lg px-2 py-1 border border-ctp-surface0 text-ctp-red hover:bg-ctp-surface0 transition-colors"
+              >
+                Never link
+              </button>
+            </div>
+          )}
+
+          <input
+            value={label}
+            onChange={(e) => setLabel(e.target.value)}
+            placeholder="Label"
+            className="w-full rounded-lg bg-ctp-surface0/60 px-2 py-1 outline-none placeholder:text-ctp-overlay0"
+          />
+          <textarea
+            value={note}
+            onChange={(e) => setNote(e.target.value)}
+            placeholder="Note"
+            rows={2}
+            className="w-full resize-none rounded-lg bg-ctp-surface0/60 px-2 py-1 outline-none placeholder:text-ctp-overlay0"
+          />
+          <button
+            onClick={() => onAnnotate(label, note)}
+            disabled={!isAnnotationChanged}
+            className="w-full rounded-lg bg-ctp-lavender px-2 py-1 font-medium text-ctp-crust hover:bg-ctp-blue transition-colors disabled:opacity-50"
+          >
+            Save
+          </button>
+        </>
+      )}
+    </div>
+  );
+};
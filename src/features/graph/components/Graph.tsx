@@ -5,11 +5,19 @@ import React, { useCallback, useEffect, useState } from 'react';
 import { useAppStore } from '@/core/store';
 import { configApi } from '@/shared/api/config.api';
 import { documentApi } from '@/shared/api/document.api';
+import { tauriEventManager } from '@/shared/lib/tauriEventManager';
 
+import { EdgeDetails } from './EdgeDetails';
 import { GraphView } from './GraphView';
 import { useGraphData } from '../hooks/useGraphData';
 
-import type { GraphColors, Tab } from '@/core/types';
+import type {
+  EdgeExplanation,
+  GraphColors,
+  GraphData,
+  GraphEdge,
+  Tab,
+} from '@/core/types';
 
 const DEFAULT_COLORS: GraphColors = {
   documentNode: '#3b82f6',
@@ -24,9 +32,62 @@ export const Graph: React.FC = () => {
   const addTab = useAppStore((state) => state.addTab);
   const switchTab = useAppStore((state) => state.switchTab);
   const [colors, setColors] = useState<GraphColors>(DEFAULT_COLORS);
+  const [isFocused, setIsFocused] = useState(false);
+  const [neighborhood, setNeighborhood] = useState<GraphData | null>(null);
+  const [isNeighborhoodLoading, setIsNeighborhoodLoading] = useState(false);
+  const [selectedEdge, setSelectedEdge] = useState<GraphEdge | null>(null);
+  const [isSelectedEdgeBlocked, setIsSelectedEdgeBlocked] = useState(false);
+  const [explanation, setExplanation] = useState<EdgeExplanation | null>(null);
 
   const activeDocumentId = tabs.find((t) => t.isActive)?.documentId ?? null;
 
+  const fetchNeighborhood = useCallback(async () => {
+    if (!activeDocumentId) {
+      setNeighborhood(null);
+      return;
+    }
+
+    setIsNeighborhoodLoading(true);
+    try {
+      const response =
+        await documentApi.getDocumentNeighborhood(activeDocumentId);
+      if (response.success && response.data) {
+        setNeighborhood(response.data);
+      }
+    } finally {
+      setIsNeighborhoodLoading(false);
+    }
+  }, [activeDocumentId]);
+
+  useEffect(() => {
+    if (isFocused) {
+      fetchNeighborhood();
+    }
+  }, [isFocused, fetchNeighborhood]);
+
+  useEffect(() => {
+    if (!isFocused) return;
+
+    let unsubscribe: (() => void) | null = null;
+
+    const setupListener = async () => {
+      unsubscribe = await tauriEventManager.subscribe(
+        'graph-edge-changed',
+        () => {
+          fetchNeighborhood();
+        },
+      );
+    };
+
+    setupListener();
+
+    return () => {
+      if (unsubscribe) {
+        unsubscribe();
+      }
+    };
+  }, [isFocused, fetchNeighborhood]);
+
   useEffect(() => {
     const loadColors = async () => {
       const response = await configApi.loadConfig();
@@ -37,6 +98,104 @@ export const Graph: React.FC = () => {
     loadColors();
   }, []);
 
+  const refresh = isFocused ? fetchNeighborhood : refetch;
+  const shownGraph = isFocused ? neighborhood : graphData;
+
+  const selectedSource = selectedEdge?.source;
+  const selectedTarget = selectedEdge?.target;
+  const isSelectedSimilar =
+    selectedEdge != null && selectedEdge.relationType !== 'link';
+
+  useEffect(() => {
+    setExplanation(null);
+    if (!selectedSource || !selectedTarget || !isSelectedSimilar) return;
+
+    let isCancelled = false;
+    documentApi
+      .explainEdge(selectedSource, selectedTarget)
+      .then((response) => {
+        if (!isCancelled && response.success && response.data) {
+          setExplanation(response.data);
+        }
+      });
+
+    return () => {
+      isCancelled = true;
+    };
+  }, [selectedSource, selectedTarget, isSelectedSimilar]);
+
+  const handleEdgeClick = useCallback((edge: GraphEdge) => {
+    setSelectedEdge(edge);
+    setIsSelectedEdgeBlocked(false);
+  }, []);
+
+  const handlePin = useCallback(
+    async (pinned: boolean) => {
+      if (!selectedEdge) return;
+
+      const response = await documentApi.pinEdge(
+        selectedEdge.source,
+        selectedEdge.target,
+        pinned,
+      );
+      if (response.success) {
+        setSelectedEdge({ ...selectedEdge, pinned });
+        refresh();
+      }
+    },
+    [selectedEdge, refresh],
+  );
+
+  const handleBlock = useCallback(async () => {
+    if (!selectedEdge) return;
+
+    const response = await documentApi.blockEdge(
+      selectedEdge.source,
+      selectedEdge.target,
+    );
+    if (response.success) {
+      setIsSelectedEdgeBlocked(true);
+    }
+  }, [selectedEdge]);
+
+  const handleUnblock = useCallback(async () => {
+    if (!selectedEdge) return;
+
+    const response = await documentApi.unblockEdge(
+      selectedEdge.source,
+      selectedEdge.target,
+    );
+    if (response.success) {
+      setSelectedEdge(null);
+    }
+  }, [selectedEdge]);
+
+  const handleAnnotate = useCallback(
+    async (label: string, note: string) => {
+      if (!selectedEdge) return;
+
+      const response = await documentApi.annotateEdge(
+        selectedEdge.source,
+        selectedEdge.target,
+        selectedEdge.relationType ?? 'similar',
+        label,
+        note,
+      );
+      if (response.success) {
+        setSelectedEdge({
+          ...selectedEdge,
+          label: label.trim() || null,
+          note: note.trim() || null,
+        });
+        refresh();
+      }
+    },
+    [selectedEdge, refresh],
+  );
+
+  const nodeLabel = (nodeId: string) =>
+    shownGraph?.nodes.find((node) => node.id === nodeId)?.label ?? 'Untitled';
+
   const handleNodeClick = useCallback(
     async (nodeId: string, nodeType: 'document' | 'tag') => {
       if (nodeType === 'document') {
@@ -67,12 +226,31 @@ export const Graph: React.FC = () => {
 
   return (
     <GraphView
-      graphData={graphData}
-      isLoading={isLoading}
+      graphData={shownGraph}
+      isLoading={isFocused ? isNeighborhoodLoading : isLoading}
       colors={colors}
       activeDocumentId={activeDocumentId}
+      isFocused={isFocused}
       onNodeClick={handleNodeClick}
-      onRefresh={refetch}
+      onEdgeClick={handleEdgeClick}
+      onRefresh={refresh}
+      onToggleFocus={() => setIsFocused((focused) => !focused)}
+      edgeDetails={
+        selectedEdge && (
+          <EdgeDetails
+            edge={selectedEdge}
+            sourceLabel={nodeLabel(selectedEdge.source)}
+            targetLabel={nodeLabel(selectedEdge.target)}
+            explanation={explanation}
+            isBlocked={isSelectedEdgeBlocked}
+            onPin={handlePin}
+            onBlock={handleBlock}
+            onUnblock={handleUnblock}
+            onAnnotate={handleAnnotate}
+            onClose={() => setSelectedEdge(null)}
+          />
+        )
+      }
     />
   );
 };
@@ -7,7 +7,7 @@ import ForceGraph2D, {
   type LinkObject,
 } from 'react-force-graph-2d';
 
-import type { GraphColors, GraphData } from '../../../core/types';
+import type { GraphColors, GraphData, GraphEdge } from '../../../core/types';
 
 interface GraphCanvasProps {
   graphData: GraphData;
@@ -16,6 +16,7 @@ interface GraphCanvasProps {
   colors?: GraphColors;
   activeDocumentId?: string | null;
   onNodeClick?: (nodeId: string, nodeType: 'document' | 'tag') => void;
+  onEdgeClick?: (edge: GraphEdge) => void;
 }
 
 interface ForceGraphNode extends NodeObject {
@@ -29,6 +30,7 @@ interface ForceGraphLink extends LinkObject {
   target: string | ForceGraphNode;
   edgeType: 'document-document' | 'document-tag';
   weight?: number;
+  edge: GraphEdge;
 }
 
 const DEFAULT_COLORS: GraphColors = {
@@ -39,6 +41,14 @@ const DEFAULT_COLORS: GraphColors = {
 };
 
 const INACTIVE_COLOR = '#9ca3af';
+
+// Link labels are rendered as HTML tooltips
+const escapeHtml = (text: string) =>
+  text
+    .replace(/&/g, '&amp;')
+    .replace(/</g, '&lt;')
+    .replace(/>/g, '&gt;')
+    .replace(/"/g, '&quot;');
 const INACTIVE_OPACITY = 0.3;
 
 export const GraphCanvas: React.FC<GraphCanvasProps> = ({
@@ -48,6 +58,7 @@ export const GraphCanvas: React.FC<GraphCanvasProps> = ({
   colors = DEFAULT_COLORS,
   activeDocumentId,
   onNodeClick,
+  onEdgeClick,
 }) => {
   // eslint-disable-next-line @typescript-eslint/no-explicit-any
   const graphRef = useRef<any>(undefined);
@@ -64,6 +75,7 @@ export const GraphCanvas: React.FC<GraphCanvasProps> = ({
       target: edge.target,
       edgeType: edge.edgeType as 'document-document' | 'document-tag',
       weight: edge.weight,
+      edge,
     }));
 
     return { nodes, links };
@@ -119,6 +131,15 @@ export const GraphCanvas: React.FC<GraphCanvasProps> = ({
     [onNodeClick],
   );
 
+  const handleLinkClick = useCallback(
+    (link: ForceGraphLink) => {
+      if (onEdgeClick && link.edgeType === 'document-document') {
+        onEdgeClick(link.edge);
+      }
+    },
+    [onEdgeClick],
+  );
+
   const nodeCanvasObject = useCallback(
     (
       node: ForceGraphNode,
@@ -194,8 +215,12 @@ export const GraphCanvas: React.FC<GraphCanvasProps> = ({
         ctx.fill();
       }}
       onNodeClick={handleNodeClick}
+      onLinkClick={handleLinkClick}
       linkColor={linkColor}
-      linkWidth={1}
+      linkWidth={(link) => ((link as ForceGraphLink).edge.pinned ? 2.5 : 1)}
+      linkLabel={(link) =>
+        escapeHtml((link as ForceGraphLink).edge.label ?? '')
+      }
       linkDirectionalParticles={0}
       warmupTicks={100}
       cooldownTicks={0}
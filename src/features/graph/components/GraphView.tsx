@@ -4,15 +4,19 @@ import React, { useCallback, useEffect, useRef, useState } from 'react';
 
 import { GraphCanvas } from './GraphCanvas';
 
-import type { GraphColors, GraphData } from '../../../core/types';
+import type { GraphColors, GraphData, GraphEdge } from '../../../core/types';
 
 interface GraphViewProps {
   graphData: GraphData | null;
   isLoading: boolean;
   colors?: GraphColors;
   activeDocumentId?: string | null;
+  isFocused?: boolean;
   onNodeClick?: (nodeId: string, nodeType: 'document' | 'tag') => void;
+  onEdgeClick?: (edge: GraphEdge) => void;
   onRefresh?: () => void;
+  onToggleFocus?: () => void;
+  edgeDetails?: React.ReactNode;
 }
 
 const DEFAULT_COLORS: GraphColors = {
@@ -27,8 +31,12 @@ export const GraphView: React.FC<GraphViewProps> = ({
   isLoading,
   colors = DEFAULT_COLORS,
   activeDocumentId,
+  isFocused = false,
   onNodeClick,
+  onEdgeClick,
   onRefresh,
+  onToggleFocus,
+  edgeDetails,
 }) => {
   const containerRef = useRef<HTMLDivElement>(null);
   const [dimensions, setDimensions] = useState({ width: 800, height: 600 });
@@ -69,6 +77,14 @@ export const GraphView: React.FC<GraphViewProps> = ({
             Refresh
           </button>
         )}
+        {onToggleFocus && isFocused && (
+          <button
+            onClick={onToggleFocus}
+            className="mt-2 rounded-xl px-4 py-2 text-sm text-ctp-subtext0 hover:bg-ctp-surface0 transition-colors"
+          >
+            Show All
+          </button>
+        )}
       </div>
     );
   }
@@ -82,8 +98,11 @@ export const GraphView: React.FC<GraphViewProps> = ({
         colors={colors}
         activeDocumentId={activeDocumentId}
         onNodeClick={onNodeClick}
+        onEdgeClick={onEdgeClick}
       />
 
+      {edgeDetails}
+
       <div className="absolute bottom-4 left-4 flex gap-4 rounded-xl bg-ctp-mantle/90 backdrop-blur-sm p-2 text-xs text-ctp-text shadow-lg border border-ctp-surface0">
         <div className="flex items-center gap-1">
           <span
@@ -107,6 +126,20 @@ export const GraphView: React.FC<GraphViewProps> = ({
         </div>
       )}
 
+      {onToggleFocus && (
+        <button
+          onClick={onToggleFocus}
+          title={
+            isFocused
+              ? 'Show the whole vault'
+              : 'Show only notes near the open note'
+          }
+          className="absolute right-24 bottom-4 rounded-xl bg-ctp-mantle/90 backdrop-blur-sm px-3 py-1.5 text-sm text-ctp-text shadow-lg border border-ctp-surface0 hover:bg-ctp-surface0 transition-colors"
+        >
+          {isFocused ? 'All' : 'Focus'}
+        </button>
+      )}
+
       {onRefresh && (
         <button
           onClick={onRefresh}
@@ -0,0 +1,110 @@
+'use client';
+
+import React, { memo, useCallback, useEffect, useState } from 'react';
+
+import { useActiveDocumentId, useAppStore } from '@/core/store';
+import { documentApi } from '@/shared/api/document.api';
+import { autoSaveService } from '@/shared/lib/autoSaveService';
+import { tauriEventManager } from '@/shared/lib/tauriEventManager';
+
+import type { Backlink } from '@/core/types';
+
+export const Backlinks = memo(function Backlinks() {
+  const activeDocumentId = useActiveDocumentId();
+  const tabs = useAppStore((state) => state.tabs);
+  const addTab = useAppStore((state) => state.addTab);
+  const switchTab = useAppStore((state) => state.switchTab);
+  const [backlinks, setBacklinks] = useState<Backlink[]>([]);
+
+  const loadBacklinks = useCallback(async () => {
+    if (!activeDocumentId) {
+      setBacklinks([]);
+      return;
+    }
+
+    const response = await documentApi.getBacklinks(activeDocumentId);
+    if (response.success && response.data) {
+      setBacklinks(response.data);
+    }
+  }, [activeDocumentId]);
+
+  useEffect(() => {
+    loadBacklinks();
+  }, [loadBacklinks]);
+
+  // Links change when a document is saved; the snippets may change without them
+  useEffect(() => {
+    const unsubscribes: (() => void)[] = [];
+
+    const setupListeners = async () => {
+      unsubscribes.push(
+        await tauriEventManager.subscribe('graph-edge-changed', () => {
+          loadBacklinks();
+        }),
+        await tauriEventManager.subscribe('document-updated', () => {
+          loadBacklinks();
+        }),
+      );
+    };
+
+    setupListeners();
+
+    return () => {
+      unsubscribes.forEach((unsubscribe) => unsubscribe());
+    };
+  }, [loadBacklinks]);
+
+  const handleClick = useCallback(
+    async (backlink: Backlink, blockId: string) => {
+      await autoSaveService.flushSave();
+
+      const existingTab = tabs.find((t) => t.documentId === backlink.id);
+      if (existingTab) {
+        switchTab({ ...existingTab, blockId });
+        return;
+      }
+
+      addTab({
+        documentId: backlink.id,
+        blockId,
+        title: backlink.title,
+        isActive: true,
+        cursor: 0,
+      });
+    },
+    [tabs, addTab, switchTab],
+  );
+
+  if (!activeDocumentId || backlinks.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="flex-shrink-0 max-h-56 overflow-y-auto border-t border-ctp-surface0/60 px-2 py-2">
+      <p className="px-1 pb-1 text-xs font-medium text-ctp-subtext0">
+        Backlinks
+      </p>
+      <ul className="space-y-1">
+        {backlinks.map((backlink) => (
+          <li key={backlink.id}>
+            <button
+              onClick={() => handleClick(backlink, backlink.blocks[0]?.blockId ?? '')}
+              className="w-full px-2 pt-1 truncate text-left text-sm text-ctp-text hover:text-ctp-blue transition-all"
+            >
+              {backlink.title || 'Untitled'}
+            </button>
+            {backlink.blocks.map((block) => (
+              <button
+                key={block.blockId}
+                onClick={() => handleClick(backlink, block.blockId)}
+                className="w-full px-2 py-1 rounded-lg text-left text-xs text-ctp-subtext1 hover:bg-ctp-surface0/80 transition-all line-clamp-2"
+              >
+                {block.snippet}
+              </button>
+            ))}
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+});
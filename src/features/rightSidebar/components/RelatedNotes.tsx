@@ -0,0 +1,114 @@
+'use client';
+
+import React, { memo, useCallback, useEffect, useState } from 'react';
+
+import { useActiveDocumentId, useAppStore } from '@/core/store';
+import { documentApi } from '@/shared/api/document.api';
+import { autoSaveService } from '@/shared/lib/autoSaveService';
+import { tauriEventManager } from '@/shared/lib/tauriEventManager';
+
+import type { RelatedDocument } from '@/core/types';
+
+const RELATED_NOTES_LIMIT = 8;
+
+export const RelatedNotes = memo(function RelatedNotes() {
+  const activeDocumentId = useActiveDocumentId();
+  const tabs = useAppStore((state) => state.tabs);
+  const addTab = useAppStore((state) => state.addTab);
+  const switchTab = useAppStore((state) => state.switchTab);
+  const [relatedDocuments, setRelatedDocuments] = useState<RelatedDocument[]>(
+    [],
+  );
+
+  const loadRelatedDocuments = useCallback(async () => {
+    if (!activeDocumentId) {
+      setRelatedDocuments([]);
+      return;
+    }
+
+    const response = await documentApi.findSimilarDocuments(
+      activeDocumentId,
+      RELATED_NOTES_LIMIT,
+    );
+    if (response.success && response.data) {
+      setRelatedDocuments(response.data);
+    }
+  }, [activeDocumentId]);
+
+  useEffect(() => {
+    loadRelatedDocuments();
+  }, [loadRelatedDocuments]);
+
+  // Document vectors change when blocks are indexed, along with the edges
+  useEffect(() => {
+    let unsubscribe: (() => void) | null = null;
+
+    const setupListener = async () => {
+      unsubscribe = await tauriEventManager.subscribe(
+        'graph-edge-changed',
+        () => {
+          loadRelatedDocuments();
+        },
+      );
+    };
+
+    setupListener();
+
+    return () => {
+      if (unsubscribe) {
+        unsubscribe();
+      }
+    };
+  }, [loadRelatedDocuments]);
+
+  const handleClick = useCallback(
+    async (related: RelatedDocument) => {
+      await autoSaveService.flushSave();
+
+      const existingTab = tabs.find((t) => t.documentId === related.id);
+      if (existingTab) {
+        switchTab(existingTab);
+        return;
+      }
+
+      const response = await documentApi.getDocument(related.id);
+      if (response.success && response.data) {
+        addTab({
+          documentId: related.id,
+          blockId: response.data.blocks[0]?.id ?? '',
+          title: related.title,
+          isActive: true,
+          cursor: 0,
+        });
+      }
+    },
+    [tabs, addTab, switchTab],
+  );
+
+  if (!activeDocumentId || relatedDocuments.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="flex-shrink-0 max-h-56 overflow-y-auto border-t border-ctp-surface0/60 px-2 py-2">
+      <p className="px-1 pb-1 text-xs font-medium text-ctp-subtext0">
+        Related Notes
+      </p>
+      <ul className="space-y-0.5">
+        {relatedDocuments.map((related) => (
+          <li key={related.id}>
+            <button
+              onClick={() => handleClick(related)}
+              className="w-full px-2 py-1.5 flex items-center justify-between gap-2 rounded-lg text-left text-sm text-ctp-text hover:bg-ctp-surface0/80 transition-all"
+            >
+              <span className="truncate">{related.title || 'Untitled'}</span>
+              <span className="text-[10px] font-mono text-ctp-overlay1 flex-shrink-0">
+                {Math.round(related.similarityScore * 100)}%
+              </span>
+            </button>
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+});
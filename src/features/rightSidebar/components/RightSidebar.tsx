@@ -6,6 +6,8 @@ import dynamic from 'next/dynamic';
 
 import { useAppStore, useIsRightSidebarOpen } from '@/core/store';
 
+import { Backlinks } from './Backlinks';
+import { RelatedNotes } from './RelatedNotes';
 import { RightSidebarHeader } from './RightSidebarHeader';
 
 const Graph = dynamic(
@@ -33,6 +35,8 @@ export const RightSidebar = memo(function RightSidebar() {
       <div className="flex-1 overflow-hidden">
         <Graph />
       </div>
+      <Backlinks />
+      <RelatedNotes />
     </div>
   );
 });
@@ -14,17 +14,33 @@ export const Settings: React.FC = () => {
     isOpen,
     isReindexing,
     isSaving,
+    saveError,
+    failedBlocks,
+    indexingProgress,
+    storageBenchmark,
+    benchmarkError,
+    isBenchmarking,
     localVectorSettings,
     localGraphSettings,
+    localEmbeddingSettings,
     localGeminiApiKey,
     localGeminiModel,
     handleSimilarityThresholdChange,
+    handleVectorSettingsChange,
     handleMultiHopLevelChange,
+    handleEmbeddingSettingsChange,
     handleGeminiApiKeyChange,
     handleGeminiModelChange,
     handleGraphColorChange,
     handleSave,
     handleReindexAll,
+    handleCancelReindex,
+    handleRetryFailedBlocks,
+    handlePauseIndexing,
+    handleResumeIndexing,
+    handleCancelIndexing,
+    handleIndexingIntervalChange,
+    handleBenchmarkVectorStorage,
     handleClose,
   } = useSettings();
 
@@ -43,8 +59,15 @@ export const Settings: React.FC = () => {
       isOpen={isOpen}
       isReindexing={isReindexing}
       isSaving={isSaving}
+      saveError={saveError}
+      failedBlocks={failedBlocks}
+      indexingProgress={indexingProgress}
+      storageBenchmark={storageBenchmark}
+      benchmarkError={benchmarkError}
+      isBenchmarking={isBenchmarking}
       vectorSettings={localVectorSettings}
       graphSettings={localGraphSettings}
+      embeddingSettings={localEmbeddingSettings}
       geminiApiKey={localGeminiApiKey}
       geminiModel={localGeminiModel}
       updateStatus={updateStatus}
@@ -53,12 +76,21 @@ export const Settings: React.FC = () => {
       updateError={updateError}
       currentVersion={APP_VERSION}
       onSimilarityThresholdChange={handleSimilarityThresholdChange}
+      onVectorSettingsChange={handleVectorSettingsChange}
       onMultiHopLevelChange={handleMultiHopLevelChange}
+      onEmbeddingSettingsChange={handleEmbeddingSettingsChange}
       onGeminiApiKeyChange={handleGeminiApiKeyChange}
       onGeminiModelChange={handleGeminiModelChange}
       onGraphColorChange={handleGraphColorChange}
       onSave={handleSave}
       onReindexAll={handleReindexAll}
+      onCancelReindex={handleCancelReindex}
+      onRetryFailedBlocks={handleRetryFailedBlocks}
+      onPauseIndexing={handlePauseIndexing}
+      onResumeIndexing={handleResumeIndexing}
+      onCancelIndexing={handleCancelIndexing}
+      onIndexingIntervalChange={handleIndexingIntervalChange}
+      onBenchmarkVectorStorage={handleBenchmarkVectorStorage}
       onClose={handleClose}
       onCheckUpdate={checkForUpdates}
       onDownloadUpdate={downloadAndInstall}
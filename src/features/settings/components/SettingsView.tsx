@@ -2,17 +2,50 @@
 
 import React from 'react';
 
-import { GEMINI_MODELS } from '@/core/types';
+import {
+  EMBEDDING_PROVIDERS,
+  GEMINI_MODELS,
+  VECTOR_STORAGES,
+} from '@/core/types';
 import { ColorPicker } from '@/shared/ui/ColorPicker';
 
 import type {
+  EmbeddingProviderKind,
+  EmbeddingSettings,
+  FailedBlock,
   GeminiModel,
   GraphColors,
   GraphSettings,
+  IndexingProgress,
   VectorSettings,
+  VectorStorage,
+  VectorStorageBenchmark,
 } from '@/core/types';
 import type { UpdateStatus } from '@/shared/hooks/useUpdater';
 
+const INDEXING_INTERVALS = [
+  { value: 5, label: '5 seconds' },
+  { value: 30, label: '30 seconds' },
+  { value: 60, label: '1 minute' },
+  { value: 300, label: '5 minutes' },
+  { value: 900, label: '15 minutes' },
+];
+
+const formatBytes = (bytes: number) => {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+};
+
+const storageLabel = (result: VectorStorageBenchmark) =>
+  result.rescore ? `${result.storage} + re-score` : result.storage;
+
+const formatEta = (seconds: number) => {
+  if (seconds < 60) return `${seconds}s`;
+  if (seconds < 3600) return `${Math.round(seconds / 60)}m`;
+  return `${Math.floor(seconds / 3600)}h ${Math.round((seconds % 3600) / 60)}m`;
+};
+
 interface UpdateInfo {
   version: string;
   body: string;
@@ -22,8 +55,15 @@ export interface SettingsViewProps {
   isOpen: boolean;
   isReindexing: boolean;
   isSaving: boolean;
+  saveError: string | null;
+  failedBlocks: FailedBlock[];
+  indexingProgress: IndexingProgress | null;
+  storageBenchmark: VectorStorageBenchmark[] | null;
+  benchmarkError: string | null;
+  isBenchmarking: boolean;
   vectorSettings: VectorSettings;
   graphSettings: GraphSettings;
+  embeddingSettings: EmbeddingSettings;
   geminiApiKey: string;
   geminiModel: GeminiModel;
   updateStatus: UpdateStatus;
@@ -32,12 +72,21 @@ export interface SettingsViewProps {
   updateError: string | null;
   currentVersion: string;
   onSimilarityThresholdChange: (value: number) => void;
+  onVectorSettingsChange: (changes: Partial<VectorSettings>) => void;
   onMultiHopLevelChange: (value: number) => void;
+  onEmbeddingSettingsChange: (changes: Partial<EmbeddingSettings>) => void;
   onGeminiApiKeyChange: (value: string) => void;
   onGeminiModelChange: (value: GeminiModel) => void;
   onGraphColorChange: (colorKey: keyof GraphColors, value: string) => void;
   onSave: () => void;
   onReindexAll: () => void;
+  onCancelReindex: () => void;
+  onRetryFailedBlocks: () => void;
+  onPauseIndexing: () => void;
+  onResumeIndexing: () => void;
+  onCancelIndexing: () => void;
+  onIndexingIntervalChange: (value: number) => void;
+  onBenchmarkVectorStorage: () => void;
   onClose: () => void;
   onCheckUpdate: () => void;
   onDownloadUpdate: () => void;
@@ -48,8 +97,15 @@ export const SettingsView: React.FC<SettingsViewProps> = ({
   isOpen,
   isReindexing,
   isSaving,
+  saveError,
+  failedBlocks,
+  indexingProgress,
+  storageBenchmark,
+  benchmarkError,
+  isBenchmarking,
   vectorSettings,
   graphSettings,
+  embeddingSettings,
   geminiApiKey,
   geminiModel,
   updateStatus,
@@ -58,12 +114,21 @@ export const SettingsView: React.FC<SettingsViewProps> = ({
   updateError,
   currentVersion,
   onSimilarityThresholdChange,
+  onVectorSettingsChange,
   onMultiHopLevelChange,
+  onEmbeddingSettingsChange,
   onGeminiApiKeyChange,
   onGeminiModelChange,
   onGraphColorChange,
   onSave,
   onReindexAll,
+  onCancelReindex,
+  onRetryFailedBlocks,
+  onPauseIndexing,
+  onResumeIndexing,
+  onCancelIndexing,
+  onIndexingIntervalChange,
+  onBenchmarkVectorStorage,
   onClose,
   onCheckUpdate,
   onDownloadUpdate,
@@ -71,6 +136,8 @@ export const SettingsView: React.FC<SettingsViewProps> = ({
 }) => {
   if (!isOpen) return null;
 
+  const reindex = indexingProgress?.reindex ?? null;
+
   return (
     <div className="fixed inset-0 z-50 flex items-center justify-center">
       <div
@@ -133,6 +200,87 @@ export const SettingsView: React.FC<SettingsViewProps> = ({
                   <span>More precise</span>
                 </div>
               </div>
+              <div>
+                <label className="block text-sm text-ctp-subtext1 mb-2">
+                  Vector Storage
+                </label>
+                <select
+                  value={vectorSettings.storage ?? 'float'}
+                  onChange={(e) =>
+                    onVectorSettingsChange({
+                      storage: e.target.value as VectorStorage,
+                    })
+                  }
+                  className="w-full px-3 py-2 text-sm text-ctp-text border border-ctp-surface1 rounded-xl bg-ctp-surface0 focus:outline-none focus:ring-2 focus:ring-ctp-lavender focus:border-transparent"
+                >
+                  {VECTOR_STORAGES.map((storage) => (
+                    <option key={storage.value} value={storage.value}>
+                      {storage.label}
+                    </option>
+                  ))}
+                </select>
+                <label className="flex items-center gap-2 mt-2 text-sm text-ctp-subtext1">
+                  <input
+                    type="checkbox"
+                    checked={vectorSettings.rescore ?? false}
+                    disabled={(vectorSettings.storage ?? 'float') === 'float'}
+                    onChange={(e) =>
+                      onVectorSettingsChange({ rescore: e.target.checked })
+                    }
+                    className="accent-ctp-lavender"
+                  />
+                  Re-score matches with full-precision vectors
+                </label>
+                <p className="text-xs text-ctp-overlay1 mt-1.5">
+                  Smaller vectors make the database lighter and search faster,
+                  at some cost in accuracy. Re-scoring keeps a full-precision
+                  copy to rank the candidates the smaller vectors find.
+                </p>
+              </div>
+              <div className="space-y-2">
+                <button
+                  onClick={onBenchmarkVectorStorage}
+                  disabled={isBenchmarking}
+                  className="px-3 py-1 text-xs rounded-lg border border-ctp-surface1 text-ctp-text hover:bg-ctp-surface0 transition-all disabled:opacity-50"
+                >
+                  {isBenchmarking ? 'Measuring...' : 'Compare Storage Modes'}
+                </button>
+                {benchmarkError && (
+                  <p className="text-xs text-ctp-red">{benchmarkError}</p>
+                )}
+                {storageBenchmark && (
+                  <table className="w-full text-xs">
+                    <thead>
+                      <tr className="text-ctp-overlay1 text-left">
+                        <th className="font-normal py-1">Storage</th>
+                        <th className="font-normal py-1 text-right">Recall</th>
+                        <th className="font-normal py-1 text-right">
+                          Per vector
+                        </th>
+                        <th className="font-normal py-1 text-right">Total</th>
+                      </tr>
+                    </thead>
+                    <tbody className="text-ctp-text font-mono">
+                      {storageBenchmark.map((result) => (
+                        <tr key={storageLabel(result)}>
+                          <td className="py-1 font-sans">
+                            {storageLabel(result)}
+                          </td>
+                          <td className="py-1 text-right">
+                            {(result.recall * 100).toFixed(1)}%
+                          </td>
+                          <td className="py-1 text-right">
+                            {formatBytes(result.bytesPerVector)}
+                          </td>
+                          <td className="py-1 text-right">
+                            {formatBytes(result.totalBytes)}
+                          </td>
+                        </tr>
+                      ))}
+                    </tbody>
+                  </table>
+                )}
+              </div>
             </div>
           </section>
 
@@ -203,6 +351,91 @@ export const SettingsView: React.FC<SettingsViewProps> = ({
 
           <div className="border-t border-ctp-surface0" />
 
+          <section>
+            <h3 className="text-sm font-medium text-ctp-text mb-4">
+              Embedding
+            </h3>
+            <div className="space-y-4">
+              <div>
+                <label className="text-sm text-ctp-subtext1 block mb-2">
+                  Provider
+                </label>
+                <select
+                  value={embeddingSettings.provider}
+                  onChange={(e) =>
+                    onEmbeddingSettingsChange({
+                      provider: e.target.value as EmbeddingProviderKind,
+                    })
+                  }
+                  className="w-full px-3 py-2 text-sm text-ctp-text border border-ctp-surface1 rounded-xl bg-ctp-surface0 focus:outline-none focus:ring-2 focus:ring-ctp-lavender focus:border-transparent"
+                >
+                  {EMBEDDING_PROVIDERS.map((provider) => (
+                    <option key={provider.value} value={provider.value}>
+                      {provider.label}
+                    </option>
+                  ))}
+                </select>
+                <p className="text-xs text-ctp-overlay1 mt-1.5">
+                  Changing the provider or vector size re-indexes all blocks.
+                </p>
+              </div>
+              {embeddingSettings.provider === 'openAiCompatible' && (
+                <>
+                  <div>
+                    <label className="text-sm text-ctp-subtext1 block mb-2">
+                      Base URL
+                    </label>
+                    <input
+                      type="text"
+                      value={embeddingSettings.baseUrl || ''}
+                      onChange={(e) =>
+                        onEmbeddingSettingsChange({
+                          baseUrl: e.target.value || null,
+                        })
+                      }
+                      placeholder="http://localhost:11434/v1"
+                      className="w-full px-3 py-2 text-sm text-ctp-text border border-ctp-surface1 rounded-xl bg-ctp-surface0 placeholder-ctp-overlay1 focus:outline-none focus:ring-2 focus:ring-ctp-lavender focus:border-transparent"
+                    />
+                  </div>
+                  <div>
+                    <label className="text-sm text-ctp-subtext1 block mb-2">
+                      Model
+                    </label>
+                    <input
+                      type="text"
+                      value={embeddingSettings.model || ''}
+                      onChange={(e) =>
+                        onEmbeddingSettingsChange({
+                          model: e.target.value || null,
+                        })
+                      }
+                      placeholder="nomic-embed-text"
+                      className="w-full px-3 py-2 text-sm text-ctp-text border border-ctp-surface1 rounded-xl bg-ctp-surface0 placeholder-ctp-overlay1 focus:outline-none focus:ring-2 focus:ring-ctp-lavender focus:border-transparent"
+                    />
+                  </div>
+                  <div>
+                    <label className="text-sm text-ctp-subtext1 block mb-2">
+                      API Key
+                    </label>
+                    <input
+                      type="password"
+                      value={embeddingSettings.apiKey || ''}
+                      onChange={(e) =>
+                        onEmbeddingSettingsChange({
+                          apiKey: e.target.value || null,
+                        })
+                      }
+                      placeholder="Optional"
+                      className="w-full px-3 py-2 text-sm text-ctp-text border border-ctp-surface1 rounded-xl bg-ctp-surface0 placeholder-ctp-overlay1 focus:outline-none focus:ring-2 focus:ring-ctp-lavender focus:border-transparent"
+                    />
+                  </div>
+                </>
+              )}
+            </div>
+          </section>
+
+          <div className="border-t border-ctp-surface0" />
+
           <section>
             <h3 className="text-sm font-medium text-ctp-text mb-4">
               AI Integration
@@ -412,10 +645,10 @@ export const SettingsView: React.FC<SettingsViewProps> = ({
             <div className="space-y-3">
               <button
                 onClick={onReindexAll}
-                disabled={isReindexing}
+                disabled={isReindexing || reindex !== null}
                 className="w-full px-4 py-2.5 flex items-center justify-center gap-2 rounded-xl border border-ctp-surface1 text-ctp-text hover:bg-ctp-surface0 transition-all disabled:opacity-50 disabled:cursor-not-allowed"
               >
-                {isReindexing ? (
+                {isReindexing || reindex !== null ? (
                   <>
                     <svg
                       className="w-4 h-4 animate-spin"
@@ -459,13 +692,140 @@ export const SettingsView: React.FC<SettingsViewProps> = ({
               </button>
               <p className="text-xs text-ctp-overlay1">
                 Recalculates all block embeddings and rebuilds document
-                connections. This may take a while for large collections.
+                connections. Search and the graph keep working while the new
+                index is built, and switch over once it is complete.
               </p>
+              {reindex && (
+                <div className="flex items-center justify-between">
+                  <p className="text-xs text-ctp-subtext1">
+                    {reindex.embedded + reindex.failed} of {reindex.total}{' '}
+                    blocks rebuilt
+                    {reindex.failed > 0 && ` (${reindex.failed} failed)`}
+                    {reindex.paused &&
+                      ', paused until its embedding model is back'}
+                  </p>
+                  <button
+                    onClick={onCancelReindex}
+                    className="px-3 py-1 text-xs rounded-lg border border-ctp-surface1 text-ctp-text hover:bg-ctp-surface0 transition-all"
+                  >
+                    Cancel Rebuild
+                  </button>
+                </div>
+              )}
+              {indexingProgress && (
+                <div className="p-3 bg-ctp-surface0 rounded-xl space-y-2">
+                  <div className="flex items-center justify-between">
+                    <p className="text-sm text-ctp-subtext1">
+                      {indexingProgress.state === 'paused'
+                        ? 'Indexing paused'
+                        : indexingProgress.state === 'waitingForProvider'
+                          ? 'Waiting for the embedding provider'
+                          : indexingProgress.queueLength > 0
+                          ? `${indexingProgress.queueLength} blocks queued`
+                          : 'Index up to date'}
+                    </p>
+                    <div className="flex gap-2">
+                      {indexingProgress.state === 'paused' ? (
+                        <button
+                          onClick={onResumeIndexing}
+                          className="px-3 py-1 text-xs rounded-lg border border-ctp-surface1 text-ctp-text hover:bg-ctp-surface1 transition-all"
+                        >
+                          Resume
+                        </button>
+                      ) : (
+                        <>
+                          <button
+                            onClick={onPauseIndexing}
+                            className="px-3 py-1 text-xs rounded-lg border border-ctp-surface1 text-ctp-text hover:bg-ctp-surface1 transition-all"
+                          >
+                            Pause
+                          </button>
+                          <button
+                            onClick={onCancelIndexing}
+                            disabled={indexingProgress.state !== 'running'}
+                            className="px-3 py-1 text-xs rounded-lg border border-ctp-surface1 text-ctp-text hover:bg-ctp-surface1 transition-all disabled:opacity-50 disabled:cursor-not-allowed"
+                          >
+                            Cancel
+                          </button>
+                        </>
+                      )}
+                    </div>
+                  </div>
+                  {indexingProgress.state === 'running' &&
+                    indexingProgress.blocksPerSecond !== null && (
+                      <p className="text-xs text-ctp-overlay1">
+                        {indexingProgress.blocksPerSecond.toFixed(1)} blocks/s
+                        {indexingProgress.etaSeconds !== null &&
+                          ` · about ${formatEta(indexingProgress.etaSeconds)} left`}
+                      </p>
+                    )}
+                  <div className="flex items-center justify-between">
+                    <span className="text-xs text-ctp-overlay1">
+                      Check for changes every
+                    </span>
+                    <select
+                      value={indexingProgress.idleIntervalSecs}
+                      onChange={(e) =>
+                        onIndexingIntervalChange(parseInt(e.target.value, 10))
+                      }
+                      className="px-2 py-1 text-xs text-ctp-text border border-ctp-surface1 rounded-lg bg-ctp-surface0 focus:outline-none focus:ring-2 focus:ring-ctp-lavender focus:border-transparent"
+                    >
+                      {!INDEXING_INTERVALS.some(
+                        (interval) =>
+                          interval.value === indexingProgress.idleIntervalSecs,
+                      ) && (
+                        <option value={indexingProgress.idleIntervalSecs}>
+                          {indexingProgress.idleIntervalSecs} seconds
+                        </option>
+                      )}
+                      {INDEXING_INTERVALS.map((interval) => (
+                        <option key={interval.value} value={interval.value}>
+                          {interval.label}
+                        </option>
+                      ))}
+                    </select>
+                  </div>
+                </div>
+              )}
+              {failedBlocks.length > 0 && (
+                <div className="space-y-2">
+                  <div className="flex items-center justify-between">
+                    <p className="text-sm text-ctp-subtext1">
+                      {failedBlocks.length} blocks failed to index
+                    </p>
+                    <button
+                      onClick={onRetryFailedBlocks}
+                      className="px-3 py-1 text-xs rounded-lg border border-ctp-surface1 text-ctp-text hover:bg-ctp-surface0 transition-all"
+                    >
+                      Retry All
+                    </button>
+                  </div>
+                  <ul className="max-h-32 overflow-y-auto space-y-1">
+                    {failedBlocks.map((block) => (
+                      <li
+                        key={block.blockId}
+                        className="p-2 bg-ctp-surface0 rounded-lg text-xs"
+                      >
+                        <p className="text-ctp-text truncate">
+                          {block.documentTitle || 'Untitled'}
+                          {block.excerpt ? ` — ${block.excerpt}` : ''}
+                        </p>
+                        <p className="text-ctp-red truncate">
+                          {block.lastError} ({block.attempts} attempts)
+                        </p>
+                      </li>
+                    ))}
+                  </ul>
+                </div>
+              )}
             </div>
           </section>
         </div>
 
-        <div className="px-6 py-4 border-t border-ctp-surface0 bg-ctp-crust flex justify-end gap-3">
+        <div className="px-6 py-4 border-t border-ctp-surface0 bg-ctp-crust flex items-center justify-end gap-3">
+          {saveError && (
+            <p className="mr-auto text-xs text-ctp-red">{saveError}</p>
+          )}
           <button
             onClick={onClose}
             className="px-4 py-2 text-sm text-ctp-subtext1 hover:text-ctp-text transition-colors"
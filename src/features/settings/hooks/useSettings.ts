@@ -3,12 +3,25 @@ import { useCallback, useEffect, useState } from 'react';
 import { useAppStore, useIsReindexing, useIsSettingsOpen } from '@/core/store';
 import {
   AppConfig,
+  EmbeddingSettings,
+  FailedBlock,
   GeminiModel,
   GraphColors,
   GraphSettings,
+  IndexingProgress,
   VectorSettings,
+  VectorStorageBenchmark,
 } from '@/core/types';
 import { configApi } from '@/shared/api/config.api';
+import { tauriEventManager } from '@/shared/lib/tauriEventManager';
+
+const DEFAULT_EMBEDDING_SETTINGS: EmbeddingSettings = {
+  provider: 'onnx',
+  model: null,
+  baseUrl: null,
+  apiKey: null,
+  dimension: null,
+};
 
 const DEFAULT_GRAPH_COLORS: GraphColors = {
   documentNode: '#3b82f6',
@@ -32,17 +45,67 @@ export const useSettings = () => {
     multiHopLevel: 1,
     colors: DEFAULT_GRAPH_COLORS,
   });
+  const [localEmbeddingSettings, setLocalEmbeddingSettings] =
+    useState<EmbeddingSettings>(DEFAULT_EMBEDDING_SETTINGS);
   const [localGeminiApiKey, setLocalGeminiApiKey] = useState('');
   const [localGeminiModel, setLocalGeminiModel] =
     useState<GeminiModel>('gemini-2.0-flash');
   const [isSaving, setIsSaving] = useState(false);
+  const [saveError, setSaveError] = useState<string | null>(null);
+  const [failedBlocks, setFailedBlocks] = useState<FailedBlock[]>([]);
+  const [indexingProgress, setIndexingProgress] =
+    useState<IndexingProgress | null>(null);
+  const [storageBenchmark, setStorageBenchmark] = useState<
+    VectorStorageBenchmark[] | null
+  >(null);
+  const [benchmarkError, setBenchmarkError] = useState<string | null>(null);
+  const [isBenchmarking, setIsBenchmarking] = useState(false);
 
   useEffect(() => {
     if (isOpen) {
       loadConfig();
+      loadFailedBlocks();
+      loadIndexingProgress();
     }
   }, [isOpen]);
 
+  useEffect(() => {
+    if (!isOpen) return;
+
+    let unsubscribe: (() => void) | null = null;
+
+    const setupListener = async () => {
+      unsubscribe = await tauriEventManager.subscribe(
+        'indexing-progress',
+        (payload) => {
+          setIndexingProgress(payload);
+        },
+      );
+    };
+
+    setupListener();
+
+    return () => {
+      if (unsubscribe) {
+        unsubscribe();
+      }
+    };
+  }, [isOpen]);
+
+  const loadIndexingProgress = async () => {
+    const response = await configApi.getIndexingProgress();
+    if (response.success && response.data) {
+      setIndexingProgress(response.data);
+    }
+  };
+
+  const loadFailedBlocks = async () => {
+    const response = await configApi.listFailedBlocks();
+    if (response.success && response.data) {
+      setFailedBlocks(response.data);
+    }
+  };
+
   const loadConfig = async () => {
     const response = await configApi.loadConfig();
     if (response.success && response.data) {
@@ -56,6 +119,9 @@ export const useSettings = () => {
           colors: DEFAULT_GRAPH_COLORS,
         },
       );
+      setLocalEmbeddingSettings(
+        response.data.embeddingSettings || DEFAULT_EMBEDDING_SETTINGS,
+      );
       setLocalGeminiApiKey(response.data.geminiApiKey || '');
       setLocalGeminiModel(response.data.geminiModel || 'gemini-2.0-flash');
     }
@@ -68,6 +134,16 @@ export const useSettings = () => {
     }));
   }, []);
 
+  const handleVectorSettingsChange = useCallback(
+    (changes: Partial<VectorSettings>) => {
+      setLocalVectorSettings((prev) => ({
+        ...prev,
+        ...changes,
+      }));
+    },
+    [],
+  );
+
   const handleMultiHopLevelChange = useCallback((value: number) => {
     setLocalGraphSettings((prev) => ({
       ...prev,
@@ -75,6 +151,16 @@ export const useSettings = () => {
     }));
   }, []);
 
+  const handleEmbeddingSettingsChange = useCallback(
+    (changes: Partial<EmbeddingSettings>) => {
+      setLocalEmbeddingSettings((prev) => ({
+        ...prev,
+        ...changes,
+      }));
+    },
+    [],
+  );
+
   const handleGeminiApiKeyChange = useCallback((value: string) => {
     setLocalGeminiApiKey(value);
   }, []);
@@ -98,20 +184,27 @@ export const useSettings = () => {
 
   const handleSave = useCallback(async () => {
     setIsSaving(true);
+    setSaveError(null);
     try {
-      await configApi.saveConfig({
+      const response = await configApi.saveConfig({
         vectorSettings: localVectorSettings,
         graphSettings: localGraphSettings,
+        embeddingSettings: localEmbeddingSettings,
         geminiApiKey: localGeminiApiKey || null,
         geminiModel: localGeminiModel,
       });
-      closeSettings();
+      if (response.success) {
+        closeSettings();
+      } else {
+        setSaveError(response.message);
+      }
     } finally {
       setIsSaving(false);
     }
   }, [
     localVectorSettings,
     localGraphSettings,
+    localEmbeddingSettings,
     localGeminiApiKey,
     localGeminiModel,
     closeSettings,
@@ -124,11 +217,67 @@ export const useSettings = () => {
       if (!response.success) {
         console.error('Reindex failed:', response.message);
       }
+      await loadIndexingProgress();
     } finally {
       setIsReindexing(false);
     }
   }, [setIsReindexing]);
 
+  const handleCancelReindex = useCallback(async () => {
+    await configApi.cancelReindex();
+    await loadIndexingProgress();
+  }, []);
+
+  const handleRetryFailedBlocks = useCallback(async () => {
+    const response = await configApi.retryFailedBlocks();
+    if (!response.success) {
+      console.error('Retry failed:', response.message);
+    }
+    await loadFailedBlocks();
+  }, []);
+
+  // The scheduler reports its new state once the batch in flight settles,
+  // so the progress is reloaded right away for immediate feedback
+  const handlePauseIndexing = useCallback(async () => {
+    await configApi.pauseIndexing();
+    await loadIndexingProgress();
+  }, []);
+
+  const handleResumeIndexing = useCallback(async () => {
+    await configApi.resumeIndexing();
+    await loadIndexingProgress();
+  }, []);
+
+  const handleCancelIndexing = useCallback(async () => {
+    await configApi.cancelIndexing();
+    await loadIndexingProgress();
+  }, []);
+
+  const handleIndexingIntervalChange = useCallback(async (value: number) => {
+    const response = await configApi.setIndexingInterval(value);
+    if (response.success && response.data !== null) {
+      const idleIntervalSecs = response.data;
+      setIndexingProgress((prev) =>
+        prev ? { ...prev, idleIntervalSecs } : prev,
+      );
+    }
+  }, []);
+
+  const handleBenchmarkVectorStorage = useCallback(async () => {
+    setIsBenchmarking(true);
+    setBenchmarkError(null);
+    try {
+      const response = await configApi.benchmarkVectorStorage();
+      if (response.success && response.data) {
+        setStorageBenchmark(response.data);
+      } else {
+        setBenchmarkError(response.message);
+      }
+    } finally {
+      setIsBenchmarking(false);
+    }
+  }, []);
+
   const handleClose = useCallback(() => {
     closeSettings();
   }, [closeSettings]);
@@ -137,18 +286,34 @@ export const useSettings = () => {
     isOpen,
     isReindexing,
     isSaving,
+    saveError,
     config,
+    failedBlocks,
+    indexingProgress,
+    storageBenchmark,
+    benchmarkError,
+    isBenchmarking,
     localVectorSettings,
     localGraphSettings,
+    localEmbeddingSettings,
     localGeminiApiKey,
     localGeminiModel,
     handleSimilarityThresholdChange,
+    handleVectorSettingsChange,
     handleMultiHopLevelChange,
+    handleEmbeddingSettingsChange,
     handleGeminiApiKeyChange,
     handleGeminiModelChange,
     handleGraphColorChange,
     handleSave,
     handleReindexAll,
+    handleCancelReindex,
+    handleRetryFailedBlocks,
+    handlePauseIndexing,
+    handleResumeIndexing,
+    handleCancelIndexing,
+    handleIndexingIntervalChange,
+    handleBenchmarkVectorStorage,
     handleClose,
   };
 };
@@ -7,7 +7,11 @@ import type { SearchResult } from '@/core/types';
 interface SearchResultItemProps {
   result: SearchResult;
   isActive: boolean;
-  onClick: (documentId: string, title: string | null) => void;
+  onClick: (
+    documentId: string,
+    title: string | null,
+    blockId?: string,
+  ) => void;
 }
 
 const MatchTypeBadge = memo(function MatchTypeBadge({
@@ -20,6 +24,7 @@ const MatchTypeBadge = memo(function MatchTypeBadge({
     tag: { label: 'Tag', className: 'bg-ctp-yellow/20 text-ctp-yellow' },
     content: { label: 'Content', className: 'bg-ctp-blue/20 text-ctp-blue' },
     similar: { label: 'Similar', className: 'bg-ctp-mauve/20 text-ctp-mauve' },
+    filter: { label: 'Filter', className: 'bg-ctp-peach/20 text-ctp-peach' },
   }[type] ?? { label: type, className: 'bg-ctp-surface0 text-ctp-subtext0' };
 
   return (
@@ -31,13 +36,42 @@ const MatchTypeBadge = memo(function MatchTypeBadge({
   );
 });
 
+// Snippets from the FTS index wrap matched terms in <mark>...</mark>
+const HighlightedSnippet = memo(function HighlightedSnippet({
+  snippet,
+}: {
+  snippet: string;
+}) {
+  const parts = snippet.split(/<mark>|<\/mark>/);
+
+  return (
+    <>
+      {parts.map((part, index) =>
+        index % 2 === 1 ? (
+          <mark
+            key={index}
+            className="bg-ctp-yellow/30 text-ctp-text rounded-sm"
+          >
+            {part}
+          </mark>
+        ) : (
+          <React.Fragment key={index}>{part}</React.Fragment>
+        ),
+      )}
+    </>
+  );
+});
+
 export const SearchResultItem = memo(function SearchResultItem({
   result,
   isActive,
   onClick,
 }: SearchResultItemProps) {
+  const topBlockHit = result.blockHits[0];
+  const snippet = result.matchSnippet ?? topBlockHit?.snippet;
+
   const handleClick = () => {
-    onClick(result.id, result.title);
+    onClick(result.id, result.title, topBlockHit?.blockId);
   };
 
   return (
@@ -71,11 +105,13 @@ export const SearchResultItem = memo(function SearchResultItem({
           </p>
           <MatchTypeBadge type={result.matchType} />
         </div>
-        {result.matchSnippet && result.matchType === 'content' && (
-          <p className="text-[10px] text-ctp-overlay1 truncate mt-0.5">
-            {result.matchSnippet}
-          </p>
-        )}
+        {snippet &&
+          (result.matchType === 'content' ||
+            result.matchType === 'similar') && (
+            <p className="text-[10px] text-ctp-overlay1 truncate mt-0.5">
+              <HighlightedSnippet snippet={snippet} />
+            </p>
+          )}
         {result.tags && result.tags.length > 0 && (
           <div className="flex flex-wrap gap-1 mt-1">
             {result.tags.slice(0, 3).map((tag) => (
@@ -16,7 +16,8 @@ export const Sidebar: React.FC = () => {
   const addTab = useAppStore((state) => state.addTab);
   const addDocumentToList = useAppStore((state) => state.addDocumentToList);
   const activeDocumentId = useActiveDocumentId();
-  const { documentList, handleDocumentClick } = useDocumentListLoader();
+  const { documentList, handleDocumentClick, loadMoreDocuments } =
+    useDocumentListLoader();
   const {
     query,
     isSearching,
@@ -68,6 +69,7 @@ export const Sidebar: React.FC = () => {
       onToggleCollapse={handleToggleCollapse}
       onNewNote={handleNewNote}
       onDocumentClick={handleDocumentClick}
+      onLoadMoreDocuments={loadMoreDocuments}
       onSearchChange={handleQueryChange}
       onSearchClear={clearSearch}
       onOpenSettings={handleOpenSettings}
@@ -21,7 +21,12 @@ export interface SidebarViewProps {
   isSearchActive: boolean;
   onToggleCollapse: () => void;
   onNewNote: () => void;
-  onDocumentClick: (documentId: string, title: string | null) => void;
+  onDocumentClick: (
+    documentId: string,
+    title: string | null,
+    blockId?: string,
+  ) => void;
+  onLoadMoreDocuments: () => void;
   onSearchChange: (query: string) => void;
   onSearchClear: () => void;
   onOpenSettings: () => void;
@@ -39,6 +44,7 @@ export const SidebarView: React.FC<SidebarViewProps> = memo(
     onToggleCollapse,
     onNewNote,
     onDocumentClick,
+    onLoadMoreDocuments,
     onSearchChange,
     onSearchClear,
     onOpenSettings,
@@ -179,6 +185,7 @@ export const SidebarView: React.FC<SidebarViewProps> = memo(
           ) : (
             <Virtuoso
               data={documentList}
+              endReached={onLoadMoreDocuments}
               itemContent={(_, document) => (
                 <DocumentListItem
                   document={document}
@@ -1,50 +1,76 @@
 'use client';
 
-import { useCallback, useEffect } from 'react';
+import { useCallback, useEffect, useRef } from 'react';
 
 import {
   useAppStore,
   useDocumentList,
   type DocumentListItem,
 } from '@/core/store';
+import type { Document } from '@/core/types';
 import { documentApi } from '@/shared/api/document.api';
 import { autoSaveService } from '@/shared/lib/autoSaveService';
 
+function toListItem(doc: Document): DocumentListItem {
+  return {
+    id: doc.id,
+    title: doc.title,
+    tags: doc.tags,
+    status: doc.status,
+    updatedAt: doc.updatedAt ?? '',
+  };
+}
+
 export function useDocumentListLoader() {
   const documentList = useDocumentList();
   const setDocumentList = useAppStore((state) => state.setDocumentList);
+  const appendDocumentList = useAppStore((state) => state.appendDocumentList);
   const tabs = useAppStore((state) => state.tabs);
   const addTab = useAppStore((state) => state.addTab);
   const switchTab = useAppStore((state) => state.switchTab);
 
+  // Cursor of the next page; null once the last page is loaded
+  const nextCursorRef = useRef<string | null>(null);
+  const isLoadingMoreRef = useRef(false);
+
   const loadDocuments = useCallback(async () => {
     const response = await documentApi.listDocuments();
     if (response.success && response.data) {
-      const items: DocumentListItem[] = response.data.map((doc) => ({
-        id: doc.id,
-        title: doc.title,
-        tags: doc.tags,
-        status: doc.status,
-        updatedAt: '',
-      }));
-      setDocumentList(items);
+      nextCursorRef.current = response.data.nextCursor;
+      setDocumentList(response.data.items.map(toListItem));
     }
   }, [setDocumentList]);
 
+  const loadMoreDocuments = useCallback(async () => {
+    const cursor = nextCursorRef.current;
+    if (!cursor || isLoadingMoreRef.current) return;
+
+    isLoadingMoreRef.current = true;
+    try {
+      const response = await documentApi.listDocuments({ cursor });
+      if (response.success && response.data) {
+        nextCursorRef.current = response.data.nextCursor;
+        appendDocumentList(response.data.items.map(toListItem));
+      }
+    } finally {
+      isLoadingMoreRef.current = false;
+    }
+  }, [appendDocumentList]);
+
   const handleDocumentClick = useCallback(
-    async (documentId: string, title: string | null) => {
+    async (documentId: string, title: string | null, blockId?: string) => {
       await autoSaveService.flushSave();
 
       const existingTab = tabs.find((t) => t.documentId === documentId);
       if (existingTab) {
-        switchTab(existingTab);
+        switchTab(blockId ? { ...existingTab, blockId } : existingTab);
       } else {
         const response = await documentApi.getDocument(documentId);
         if (response.success && response.data) {
           const firstBlock = response.data.blocks[0];
           addTab({
             documentId,
-            blockId: firstBlock?.id ?? '',
+            blockId: blockId ?? firstBlock?.id ?? '',
             title,
             isActive: true,
             cursor: 0,
@@ -62,5 +88,6 @@ export function useDocumentListLoader() {
   return {
     documentList,
     handleDocumentClick,
+    loadMoreDocuments,
   };
 }
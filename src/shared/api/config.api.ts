@@ -1,6 +1,14 @@
 import { open } from '@tauri-apps/plugin-dialog';
 
-import { AppConfig, ApiResponse, DatabaseHealth } from '@/core/types';
+import {
+  AppConfig,
+  ApiResponse,
+  DatabaseHealth,
+  FailedBlock,
+  IndexingProgress,
+  ReindexScope,
+  VectorStorageBenchmark,
+} from '@/core/types';
 
 import { invokeTauri } from './client';
 
@@ -44,7 +52,58 @@ export const configApi = {
     return invokeTauri<DatabaseHealth>('load_database');
   },
 
-  async triggerReindexAll(): Promise<ApiResponse<void>> {
-    return invokeTauri<void>('trigger_reindex_all');
+  // Returns the number of blocks queued
+  async triggerReindexAll(): Promise<ApiResponse<number>> {
+    return invokeTauri<number>('trigger_reindex_all');
+  },
+
+  async triggerReindex(scope: ReindexScope): Promise<ApiResponse<number>> {
+    return invokeTauri<number>('trigger_reindex', { scope });
+  },
+
+  async cancelReindex(): Promise<ApiResponse<void>> {
+    return invokeTauri<void>('cancel_reindex');
+  },
+
+  // Compares recall and size of the storage modes on the vault's own vectors
+  async benchmarkVectorStorage(
+    sampleSize?: number,
+  ): Promise<ApiResponse<VectorStorageBenchmark[]>> {
+    return invokeTauri<VectorStorageBenchmark[]>('benchmark_vector_storage', {
+      sampleSize,
+    });
+  },
+
+  async listFailedBlocks(): Promise<ApiResponse<FailedBlock[]>> {
+    return invokeTauri<FailedBlock[]>('list_failed_blocks');
+  },
+
+  // Retries the given blocks, or every failed block when omitted
+  async retryFailedBlocks(blockIds?: string[]): Promise<ApiResponse<number>> {
+    return invokeTauri<number>('retry_failed_blocks', { blockIds });
+  },
+
+  async getIndexingProgress(): Promise<ApiResponse<IndexingProgress>> {
+    return invokeTauri<IndexingProgress>('get_indexing_progress');
+  },
+
+  async pauseIndexing(): Promise<ApiResponse<void>> {
+    return invokeTauri<void>('pause_indexing');
+  },
+
+  async resumeIndexing(): Promise<ApiResponse<void>> {
+    return invokeTauri<void>('resume_indexing');
+  },
+
+  // Pauses and discards the batch in flight; its blocks stay queued
+  async cancelIndexing(): Promise<ApiResponse<void>> {
+    return invokeTauri<void>('cancel_indexing');
+  },
+
+  // Returns the interval applied after clamping
+  async setIndexingInterval(
+    intervalSecs: number,
+  ): Promise<ApiResponse<number>> {
+    return invokeTauri<number>('set_indexing_interval', { intervalSecs });
   },
 };
@@ -2,9 +2,15 @@ import { invokeTauri } from './client';
 
 import type {
   ApiResponse,
+  Backlink,
+  Block,
   Document,
   DocumentGraphInfo,
+  DocumentListRequest,
+  DocumentPage,
+  EdgeExplanation,
   GraphData,
+  RelatedDocument,
   SearchResult,
 } from '../../core/types';
 
@@ -23,19 +29,101 @@ export const documentApi = {
     return invokeTauri<string>('delete_document', { documentId });
   },
 
+  async getBlock(blockId: string): Promise<ApiResponse<Block>> {
+    return invokeTauri<Block>('get_block', { blockId });
+  },
+
   async deleteBlock(blockId: string): Promise<ApiResponse<void>> {
     return invokeTauri<void>('delete_block', { blockId });
   },
 
-  async listDocuments(): Promise<ApiResponse<Document[]>> {
-    return invokeTauri<Document[]>('retrieve_document', { page: 1 });
+  async listDocuments(
+    request?: DocumentListRequest,
+  ): Promise<ApiResponse<DocumentPage>> {
+    return invokeTauri<DocumentPage>('retrieve_document', { request });
   },
 
   async searchDocuments(query: string): Promise<ApiResponse<SearchResult[]>> {
     return invokeTauri<SearchResult[]>('search_documents', { query });
   },
 
+  async findSimilarDocuments(
+    documentId: string,
+    limit?: number,
+  ): Promise<ApiResponse<RelatedDocument[]>> {
+    return invokeTauri<RelatedDocument[]>('find_similar_documents', {
+      documentId,
+      limit,
+    });
+  },
+
+  async getBacklinks(documentId: string): Promise<ApiResponse<Backlink[]>> {
+    return invokeTauri<Backlink[]>('get_backlinks', { documentId });
+  },
+
+  async pinEdge(
+    sourceId: string,
+    targetId: string,
+    pinned: boolean,
+  ): Promise<ApiResponse<void>> {
+    return invokeTauri<void>('pin_edge', { sourceId, targetId, pinned });
+  },
+
+  async blockEdge(
+    sourceId: string,
+    targetId: string,
+  ): Promise<ApiResponse<void>> {
+    return invokeTauri<void>('block_edge', { sourceId, targetId });
+  },
+
+  async unblockEdge(
+    sourceId: string,
+    targetId: string,
+  ): Promise<ApiResponse<void>> {
+    return invokeTauri<void>('unblock_edge', { sourceId, targetId });
+  },
+
+  async annotateEdge(
+    sourceId: string,
+    targetId: string,
+    relationType: string,
+    label: string | null,
+    note: string | null,
+  ): Promise<ApiResponse<void>> {
+    return invokeTauri<void>('annotate_edge', {
+      sourceId,
+      targetId,
+      relationType,
+      label,
+      note,
+    });
+  },
+
+  async explainEdge(
+    sourceId: string,
+    targetId: string,
+    limit?: number,
+  ): Promise<ApiResponse<EdgeExplanation>> {
+    return invokeTauri<EdgeExplanation>('explain_edge', {
+      sourceId,
+      targetId,
+      limit,
+    });
+  },
+
   async getGraphData(): Promise<ApiResponse<GraphData>> {
     return invokeTauri<GraphData>('get_graph_data');
   },
+
+  async getDocumentNeighborhood(
+    documentId: string,
+    hops?: number,
+    minWeight?: number,
+  ): Promise<ApiResponse<GraphData>> {
+    return invokeTauri<GraphData>('get_document_neighborhood', {
+      documentId,
+      hops,
+      minWeight,
+    });
+  },
 };
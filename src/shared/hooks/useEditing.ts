@@ -1,10 +1,27 @@
 import { useEffect } from 'react';
 
-import { getStoreActions, useActiveDocumentId } from '@/core/store';
+import {
+  getStoreActions,
+  useActiveBlockId,
+  useActiveDocumentId,
+  useAppStore,
+} from '@/core/store';
 import { documentApi } from '@/shared/api/document.api';
 
+import type { Document } from '@/core/types';
+
+// Focuses the tab's block (e.g. a search hit), which scrolls it into view
+function focusBlock(document: Document | null, blockId: string | undefined) {
+  const block = document?.blocks.find((b) => b.id === blockId);
+  if (block) {
+    getStoreActions().setCursorOffset(0);
+    getStoreActions().setBlockInEditing(block);
+  }
+}
+
 export function useEditing() {
   const activeDocumentId = useActiveDocumentId();
+  const activeBlockId = useActiveBlockId();
 
   useEffect(() => {
     if (!activeDocumentId) {
@@ -17,9 +34,15 @@ export function useEditing() {
 
       if (response.success && response.data) {
         getStoreActions().setDocument(response.data);
+        focusBlock(response.data, useAppStore.getState().activeTab?.blockId);
       }
     };
 
     loadDocument();
   }, [activeDocumentId]);
+
+  // Jump within the already loaded document
+  useEffect(() => {
+    focusBlock(useAppStore.getState().document, activeBlockId);
+  }, [activeBlockId]);
 }
@@ -28,6 +28,8 @@ export class BlockFactory {
       content: null,
       orderIndex: 0,
       sourceDocumentId: null,
+      sourceBlockId: null,
+      referenceKind: null,
       indexingStatus: 0,
       ...overrides,
     };